@@ -0,0 +1,551 @@
+//! quasi — command-line interface to QUASI state files and circuits.
+//!
+//! State files hold one `QuasiState` in the versioned JSON schema.
+//! Circuits are OpenQASM 3 programs. Every command accepts `--json` for
+//! machine-readable output.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use quasi::qasm;
+use quasi::{
+    DensityMatrixBackend, Executor, KrausChannel, NoiseModel, QuasiState, SplitMix64,
+    StateVectorBackend, TransitionPolicy,
+};
+use serde_json::json;
+
+const USAGE: &str = "\
+usage: quasi <command> [options]
+
+commands:
+  new <file> <id> <matter> <antimatter> [--qtype <name>] [--force]
+                          create a state file
+  show <file>             print a state
+  coherence <file>        print a state's coherence
+  observe <file> [--seed <n>] [--strict]
+                          collapse a state and save it
+  invert <file> [--strict]
+                          swap matter and antimatter and save the state
+  repl [--seed <n>] [--history <file>]
+                          interactive shell over named states
+  run <circuit.qasm> [--shots <n>] [--seed <n>] [--backend statevector|density]
+                     [--depolarizing <p>] [--amplitude-damping <g>] [--phase-damping <l>]
+                          execute an OpenQASM 3 circuit and print the histogram
+                          (at most 20 states, or 10 on the density backend)
+
+options:
+  --json                  machine-readable output
+  -h, --help              show this help";
+
+/// Largest circuit `run` accepts on the state-vector backend: `2^n`
+/// amplitudes, 16 MiB at the limit.
+const MAX_STATEVECTOR_STATES: usize = 20;
+/// Largest circuit `run` accepts on the density backend: `4^n` entries.
+const MAX_DENSITY_STATES: usize = 10;
+
+/// A failed command: message and process exit code.
+struct Failure {
+    message: String,
+    code: u8,
+}
+
+impl Failure {
+    fn usage(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: 2,
+        }
+    }
+
+    fn runtime(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: 1,
+        }
+    }
+}
+
+/// Positional arguments plus `--flag` / `--key value` options.
+///
+/// Anything starting with `--` is an option; single-dash words such as
+/// negative numbers stay positional.
+struct Args {
+    positional: Vec<String>,
+    options: BTreeMap<String, Option<String>>,
+}
+
+/// Options that take a value.
+const VALUED: [&str; 8] = [
+    "qtype",
+    "history",
+    "seed",
+    "shots",
+    "backend",
+    "depolarizing",
+    "amplitude-damping",
+    "phase-damping",
+];
+
+impl Args {
+    fn parse(raw: impl Iterator<Item = String>) -> Result<Self, Failure> {
+        let mut positional = Vec::new();
+        let mut options = BTreeMap::new();
+        let mut raw = raw.peekable();
+        while let Some(arg) = raw.next() {
+            if arg == "-h" {
+                options.insert("help".to_string(), None);
+            } else if let Some(name) = arg.strip_prefix("--") {
+                let (name, inline) = match name.split_once('=') {
+                    Some((n, v)) => (n.to_string(), Some(v.to_string())),
+                    None => (name.to_string(), None),
+                };
+                let value = if VALUED.contains(&name.as_str()) {
+                    match inline.or_else(|| raw.next()) {
+                        Some(v) => Some(v),
+                        None => return Err(Failure::usage(format!("--{} needs a value", name))),
+                    }
+                } else if inline.is_some() {
+                    return Err(Failure::usage(format!("--{} takes no value", name)));
+                } else {
+                    None
+                };
+                options.insert(name, value);
+            } else {
+                positional.push(arg);
+            }
+        }
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(|v| v.as_deref())
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, Failure> {
+        self.value(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| Failure::usage(format!("invalid value `{}` for --{}", v, name)))
+            })
+            .transpose()
+    }
+
+    /// Exactly `n` positionals after the command.
+    fn expect_positional(&self, n: usize, command: &str) -> Result<&[String], Failure> {
+        let rest = &self.positional[1..];
+        if rest.len() != n {
+            return Err(Failure::usage(format!(
+                "`{}` takes {} argument{}",
+                command,
+                n,
+                if n == 1 { "" } else { "s" }
+            )));
+        }
+        Ok(rest)
+    }
+
+    /// Reject options the command does not understand.
+    fn allow(&self, known: &[&str]) -> Result<(), Failure> {
+        match self
+            .options
+            .keys()
+            .find(|k| *k != "json" && !known.contains(&k.as_str()))
+        {
+            Some(k) => Err(Failure::usage(format!("unknown option --{}", k))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<QuasiState, Failure> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| Failure::runtime(format!("cannot read {}: {}", path.display(), e)))?;
+    serde_json::from_str(&text).map_err(|e| {
+        Failure::runtime(format!(
+            "{} is not a valid state file: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// Write through a temporary sibling so a failed write never leaves a
+/// half-written state file behind.
+fn save(path: &Path, state: &QuasiState) -> Result<(), Failure> {
+    let text = serde_json::to_string_pretty(state)
+        .map_err(|e| Failure::runtime(format!("cannot encode state: {}", e)))?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, text + "\n")
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| Failure::runtime(format!("cannot write {}: {}", path.display(), e)))
+}
+
+fn print_state(state: &QuasiState, json: bool) {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(state).expect("valid states encode")
+        );
+    } else {
+        println!("{}", state);
+    }
+}
+
+fn policy(args: &Args) -> TransitionPolicy {
+    if args.flag("strict") {
+        TransitionPolicy::Strict
+    } else {
+        TransitionPolicy::Lenient
+    }
+}
+
+fn cmd_new(args: &Args) -> Result<(), Failure> {
+    args.allow(&["qtype", "force"])?;
+    let [file, id, matter, antimatter] = args.expect_positional(4, "new")? else {
+        unreachable!()
+    };
+    let number = |name: &str, v: &str| {
+        v.parse::<f64>()
+            .map_err(|_| Failure::usage(format!("{} must be a number, got `{}`", name, v)))
+    };
+    let qtype = args.value("qtype").unwrap_or("energy");
+    let state = QuasiState::try_new(
+        id,
+        qtype,
+        number("matter", matter)?,
+        number("antimatter", antimatter)?,
+    )
+    .map_err(|e| Failure::runtime(e.to_string()))?;
+    let path = Path::new(file);
+    if path.exists() && !args.flag("force") {
+        return Err(Failure::runtime(format!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        )));
+    }
+    save(path, &state)?;
+    print_state(&state, args.flag("json"));
+    Ok(())
+}
+
+fn cmd_show(args: &Args) -> Result<(), Failure> {
+    args.allow(&[])?;
+    let [file] = args.expect_positional(1, "show")? else {
+        unreachable!()
+    };
+    print_state(&load(Path::new(file))?, args.flag("json"));
+    Ok(())
+}
+
+fn cmd_coherence(args: &Args) -> Result<(), Failure> {
+    args.allow(&[])?;
+    let [file] = args.expect_positional(1, "coherence")? else {
+        unreachable!()
+    };
+    let state = load(Path::new(file))?;
+    if args.flag("json") {
+        println!(
+            "{}",
+            json!({ "id": state.id, "coherence": state.measure_coherence() })
+        );
+    } else {
+        println!("{:.6}", state.measure_coherence());
+    }
+    Ok(())
+}
+
+fn cmd_observe(args: &Args) -> Result<(), Failure> {
+    args.allow(&["seed", "strict"])?;
+    let [file] = args.expect_positional(1, "observe")? else {
+        unreachable!()
+    };
+    let path = Path::new(file);
+    let mut state = load(path)?;
+    let mut rng = match args.parsed::<u64>("seed")? {
+        Some(seed) => SplitMix64::seed_from_u64(seed),
+        None => SplitMix64::from_entropy(),
+    };
+    let outcome = state
+        .try_observe_with(policy(args), &mut rng)
+        .map_err(|e| Failure::runtime(e.to_string()))?;
+    save(path, &state)?;
+    if args.flag("json") {
+        println!(
+            "{}",
+            json!({ "id": state.id, "branch": outcome.branch, "value": outcome.value })
+        );
+    } else {
+        println!("{} ({:.3})", outcome.branch, outcome.value);
+    }
+    Ok(())
+}
+
+fn cmd_invert(args: &Args) -> Result<(), Failure> {
+    args.allow(&["strict"])?;
+    let [file] = args.expect_positional(1, "invert")? else {
+        unreachable!()
+    };
+    let path = Path::new(file);
+    let mut state = load(path)?;
+    state
+        .try_invert(policy(args))
+        .map_err(|e| Failure::runtime(e.to_string()))?;
+    save(path, &state)?;
+    print_state(&state, args.flag("json"));
+    Ok(())
+}
+
+fn cmd_run(args: &Args) -> Result<(), Failure> {
+    args.allow(&[
+        "shots",
+        "seed",
+        "backend",
+        "depolarizing",
+        "amplitude-damping",
+        "phase-damping",
+    ])?;
+    let [file] = args.expect_positional(1, "run")? else {
+        unreachable!()
+    };
+    let source = fs::read_to_string(file)
+        .map_err(|e| Failure::runtime(format!("cannot read {}: {}", file, e)))?;
+    let circuit = qasm::parse(&source).map_err(|e| Failure::runtime(format!("{}:{}", file, e)))?;
+    let backend = args.value("backend").unwrap_or("statevector");
+    let limit = match backend {
+        "statevector" => MAX_STATEVECTOR_STATES,
+        "density" => MAX_DENSITY_STATES,
+        other => return Err(Failure::usage(format!("unknown backend `{}`", other))),
+    };
+    if circuit.num_states() > limit {
+        return Err(Failure::runtime(format!(
+            "{} declares {} states; the {} backend simulates at most {}",
+            file,
+            circuit.num_states(),
+            backend,
+            limit
+        )));
+    }
+
+    let mut noise = NoiseModel::new();
+    if let Some(p) = args.parsed::<f64>("depolarizing")? {
+        noise = noise.with_default(KrausChannel::depolarizing(p));
+    }
+    if let Some(g) = args.parsed::<f64>("amplitude-damping")? {
+        noise = noise.with_default(KrausChannel::amplitude_damping(g));
+    }
+    if let Some(l) = args.parsed::<f64>("phase-damping")? {
+        noise = noise.with_default(KrausChannel::phase_damping(l));
+    }
+    let shots = args.parsed::<usize>("shots")?.unwrap_or(1024);
+    let seed = args.parsed::<u64>("seed")?;
+    let result = match backend {
+        "statevector" => {
+            configure(Executor::new(StateVectorBackend::new()), shots, seed, noise).run(&circuit)
+        }
+        "density" => configure(
+            Executor::new(DensityMatrixBackend::new()),
+            shots,
+            seed,
+            noise,
+        )
+        .run(&circuit),
+        _ => unreachable!("backend checked above"),
+    };
+
+    if args.flag("json") {
+        println!(
+            "{}",
+            json!({
+                "circuit": file,
+                "backend": backend,
+                "shots": result.num_shots(),
+                "histogram": result.histogram,
+            })
+        );
+    } else {
+        for (bits, count) in &result.histogram {
+            println!(
+                "{}  {:>6}  {:.4}",
+                if bits.is_empty() { "-" } else { bits },
+                count,
+                result.frequency(bits)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn configure<B: quasi::circuit::Backend>(
+    executor: Executor<B>,
+    shots: usize,
+    seed: Option<u64>,
+    noise: NoiseModel,
+) -> Executor<B> {
+    let executor = executor.shots(shots).noise(noise);
+    match seed {
+        Some(seed) => executor.seed(seed),
+        None => executor,
+    }
+}
+
+#[cfg(feature = "repl")]
+mod shell {
+    use std::path::PathBuf;
+
+    use quasi::repl::{Reply, Session};
+    use rustyline::completion::{Completer, FilenameCompleter, Pair};
+    use rustyline::error::ReadlineError;
+    use rustyline::highlight::Highlighter;
+    use rustyline::hint::Hinter;
+    use rustyline::history::DefaultHistory;
+    use rustyline::validate::Validator;
+    use rustyline::{Config, Context, Editor, Helper};
+
+    use super::{Args, Failure};
+
+    /// Owns the session so completion always sees the current state ids.
+    struct ShellHelper {
+        session: Session,
+        files: FilenameCompleter,
+    }
+
+    impl Completer for ShellHelper {
+        type Candidate = Pair;
+
+        fn complete(
+            &self,
+            line: &str,
+            pos: usize,
+            ctx: &Context<'_>,
+        ) -> rustyline::Result<(usize, Vec<Pair>)> {
+            let command = line.split_whitespace().next().unwrap_or("");
+            if matches!(command, ":save" | ":load") && pos > command.len() {
+                return self.files.complete(line, pos, ctx);
+            }
+            let (start, words) = self.session.complete(line, pos);
+            let pairs = words
+                .into_iter()
+                .map(|w| Pair {
+                    display: w.clone(),
+                    replacement: w,
+                })
+                .collect();
+            Ok((start, pairs))
+        }
+    }
+
+    impl Hinter for ShellHelper {
+        type Hint = String;
+    }
+
+    impl Highlighter for ShellHelper {}
+
+    impl Validator for ShellHelper {}
+
+    impl Helper for ShellHelper {}
+
+    /// `--history`, else `~/.quasi_history`, else no persistent history.
+    fn history_path(args: &Args) -> Option<PathBuf> {
+        match args.value("history") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".quasi_history")),
+        }
+    }
+
+    pub(super) fn cmd_repl(args: &Args) -> Result<(), Failure> {
+        args.allow(&["seed", "history"])?;
+        args.expect_positional(0, "repl")?;
+        let session = match args.parsed::<u64>("seed")? {
+            Some(seed) => Session::seeded(seed),
+            None => Session::new(),
+        };
+        let config = Config::builder().auto_add_history(true).build();
+        let mut editor = Editor::<ShellHelper, DefaultHistory>::with_config(config)
+            .map_err(|e| Failure::runtime(format!("cannot start shell: {}", e)))?;
+        editor.set_helper(Some(ShellHelper {
+            session,
+            files: FilenameCompleter::new(),
+        }));
+        let history = history_path(args);
+        if let Some(path) = &history {
+            // A missing history file is normal on first use.
+            let _ = editor.load_history(path);
+        }
+        loop {
+            let line = match editor.readline("quasi> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(Failure::runtime(format!("cannot read input: {}", e))),
+            };
+            let session = &mut editor.helper_mut().expect("helper is set").session;
+            match session.execute(&line) {
+                Ok(Reply::Text(text)) if text.is_empty() => {}
+                Ok(Reply::Text(text)) => println!("{}", text),
+                Ok(Reply::Quit) => break,
+                Err(e) => eprintln!("error: {}", e),
+            }
+        }
+        if let Some(path) = &history {
+            if let Err(e) = editor.save_history(path) {
+                eprintln!(
+                    "quasi: warning: cannot save history to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn dispatch(args: &Args) -> Result<(), Failure> {
+    let Some(command) = args.positional.first() else {
+        println!("{}", USAGE);
+        return if args.flag("help") {
+            Ok(())
+        } else {
+            Err(Failure::usage("missing command"))
+        };
+    };
+    if args.flag("help") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    match command.as_str() {
+        "new" => cmd_new(args),
+        "show" => cmd_show(args),
+        "coherence" => cmd_coherence(args),
+        "observe" => cmd_observe(args),
+        "invert" => cmd_invert(args),
+        "run" => cmd_run(args),
+        #[cfg(feature = "repl")]
+        "repl" => shell::cmd_repl(args),
+        other => Err(Failure::usage(format!("unknown command `{}`", other))),
+    }
+}
+
+fn main() -> ExitCode {
+    let result = Args::parse(std::env::args().skip(1)).and_then(|args| dispatch(&args));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("quasi: error: {}", failure.message);
+            if failure.code == 2 {
+                eprintln!("run `quasi --help` for usage");
+            }
+            ExitCode::from(failure.code)
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//! Circuits — recorded gate, measurement and conditional sequences.
+//!
+//! A [`Circuit`] addresses states by register index and classical results
+//! by bit index. An [`Executor`] interprets it on a [`Backend`] for any
+//! number of shots and gathers an [`ExecutionResult`].
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::gate::Gate;
+use crate::noise::{KrausChannel, NoiseModel};
+use crate::quasi_core::Branch;
+use crate::register::{check_targets, QuasiRegister};
+use crate::rng::{QuasiRng, SplitMix64};
+
+/// Most bits a [`Condition`] may read, so that they fit one `u64`.
+pub const MAX_CONDITION_BITS: usize = 64;
+
+/// Classical guard on an operation: the listed bits, read little-endian,
+/// must equal `value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub bits: Vec<usize>,
+    pub value: u64,
+}
+
+impl Condition {
+    /// Single bit equals `value`.
+    pub fn bit(bit: usize, value: bool) -> Self {
+        Self {
+            bits: vec![bit],
+            value: value as u64,
+        }
+    }
+
+    /// The bits, `bits[0]` least significant, read as an integer equal `value`.
+    ///
+    /// # Panics
+    /// If there are more than [`MAX_CONDITION_BITS`] bits.
+    pub fn register(bits: Vec<usize>, value: u64) -> Self {
+        let condition = Self { bits, value };
+        condition.check_width();
+        condition
+    }
+
+    /// Evaluate against the current classical bits.
+    ///
+    /// # Panics
+    /// If the condition reads more than [`MAX_CONDITION_BITS`] bits, or
+    /// a bit is out of range of `clbits`.
+    pub fn holds(&self, clbits: &[bool]) -> bool {
+        self.check_width();
+        let read = self
+            .bits
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, &b)| acc | (clbits[b] as u64) << i);
+        read == self.value
+    }
+
+    fn check_width(&self) {
+        assert!(
+            self.bits.len() <= MAX_CONDITION_BITS,
+            "conditions are limited to {} bits",
+            MAX_CONDITION_BITS
+        );
+    }
+}
+
+/// One recorded step of a circuit.
+#[derive(Clone, Debug)]
+pub enum Operation {
+    /// Apply `gate` to the states at `targets`.
+    Gate {
+        gate: Arc<dyn Gate>,
+        targets: Vec<usize>,
+    },
+    /// Observe state `index` and store the outcome (antimatter = 1) in `bit`.
+    Measure { index: usize, bit: usize },
+    /// Run `op` only if `condition` holds.
+    Conditional {
+        condition: Condition,
+        op: Box<Operation>,
+    },
+}
+
+/// An ordered program over `num_states` register entries and `num_bits`
+/// classical bits.
+#[derive(Clone, Debug, Default)]
+pub struct Circuit {
+    num_states: usize,
+    num_bits: usize,
+    ops: Vec<Operation>,
+}
+
+impl Circuit {
+    pub fn new(num_states: usize, num_bits: usize) -> Self {
+        Self {
+            num_states,
+            num_bits,
+            ops: Vec::new(),
+        }
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.ops
+    }
+
+    /// Record `gate` on `targets`.
+    ///
+    /// # Panics
+    /// If the target count does not match the gate's arity, an index is
+    /// out of range or a target is repeated.
+    pub fn gate(&mut self, gate: impl Gate + 'static, targets: &[usize]) -> &mut Self {
+        self.push_gate(Arc::new(gate), targets)
+    }
+
+    /// Record an already shared gate on `targets`, checked as
+    /// [`gate`](Self::gate) does.
+    pub fn push_gate(&mut self, gate: Arc<dyn Gate>, targets: &[usize]) -> &mut Self {
+        let op = self.gate_op(gate, targets);
+        self.ops.push(op);
+        self
+    }
+
+    /// Record a measurement of state `index` into classical `bit`.
+    pub fn measure(&mut self, index: usize, bit: usize) -> &mut Self {
+        self.check_index(index);
+        self.check_bit(bit);
+        self.ops.push(Operation::Measure { index, bit });
+        self
+    }
+
+    /// Measure state `k` into bit `k` for every state.
+    ///
+    /// Panics if there are fewer bits than states.
+    pub fn measure_all(&mut self) -> &mut Self {
+        for k in 0..self.num_states {
+            self.measure(k, k);
+        }
+        self
+    }
+
+    /// Record `gate` on `targets`, applied only when `condition` holds.
+    pub fn gate_if(
+        &mut self,
+        condition: Condition,
+        gate: impl Gate + 'static,
+        targets: &[usize],
+    ) -> &mut Self {
+        let op = Operation::Gate {
+            gate: Arc::new(gate),
+            targets: targets.to_vec(),
+        };
+        self.push_conditional(condition, op)
+    }
+
+    /// Record an arbitrary operation guarded by `condition`, checking
+    /// both as [`push`](Self::push) does.
+    pub fn push_conditional(&mut self, condition: Condition, op: Operation) -> &mut Self {
+        self.push(Operation::Conditional {
+            condition,
+            op: Box::new(op),
+        })
+    }
+
+    /// Record any operation, checking its indices as the typed builders do.
+    pub fn push(&mut self, op: Operation) -> &mut Self {
+        self.check_op(&op);
+        self.ops.push(op);
+        self
+    }
+
+    fn check_op(&self, op: &Operation) {
+        match op {
+            Operation::Gate { gate, targets } => {
+                self.gate_op(gate.clone(), targets);
+            }
+            Operation::Measure { index, bit } => {
+                self.check_index(*index);
+                self.check_bit(*bit);
+            }
+            Operation::Conditional { condition, op } => {
+                condition.check_width();
+                for &b in &condition.bits {
+                    self.check_bit(b);
+                }
+                self.check_op(op);
+            }
+        }
+    }
+
+    fn gate_op(&self, gate: Arc<dyn Gate>, targets: &[usize]) -> Operation {
+        check_targets(self.num_states, gate.as_ref(), targets);
+        Operation::Gate {
+            gate,
+            targets: targets.to_vec(),
+        }
+    }
+
+    fn check_index(&self, index: usize) {
+        assert!(
+            index < self.num_states,
+            "state index {} out of range for {} states",
+            index,
+            self.num_states
+        );
+    }
+
+    fn check_bit(&self, bit: usize) {
+        assert!(
+            bit < self.num_bits,
+            "bit index {} out of range for {} bits",
+            bit,
+            self.num_bits
+        );
+    }
+}
+
+/// A simulator a circuit can be executed on.
+///
+/// The executor owns control flow; a backend only prepares a fresh state
+/// per shot and carries out gates and measurements on it.
+pub trait Backend {
+    type State;
+
+    /// Fresh state for one shot of a circuit over `num_states` entries.
+    fn prepare(&self, num_states: usize) -> Self::State;
+
+    fn apply_gate(&self, state: &mut Self::State, gate: &dyn Gate, targets: &[usize]);
+
+    /// Observe entry `index`, returning `true` for antimatter.
+    fn measure(&self, state: &mut Self::State, index: usize, rng: &mut dyn QuasiRng) -> bool;
+
+    /// Pass entry `index` through a noise channel.
+    fn apply_channel(
+        &self,
+        state: &mut Self::State,
+        channel: &KrausChannel,
+        index: usize,
+        rng: &mut dyn QuasiRng,
+    );
+}
+
+/// Pure-state backend over [`QuasiRegister`].
+#[derive(Clone, Debug, Default)]
+pub struct StateVectorBackend {
+    initial: Option<QuasiRegister>,
+}
+
+impl StateVectorBackend {
+    /// Start every shot with all states in the matter branch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start every shot from `register`, e.g. one built from existing states.
+    pub fn with_initial(register: QuasiRegister) -> Self {
+        Self {
+            initial: Some(register),
+        }
+    }
+}
+
+impl Backend for StateVectorBackend {
+    type State = QuasiRegister;
+
+    fn prepare(&self, num_states: usize) -> QuasiRegister {
+        match &self.initial {
+            Some(register) => {
+                assert_eq!(
+                    register.len(),
+                    num_states,
+                    "initial register size does not match the circuit"
+                );
+                register.clone()
+            }
+            None => QuasiRegister::new(num_states),
+        }
+    }
+
+    fn apply_gate(&self, state: &mut QuasiRegister, gate: &dyn Gate, targets: &[usize]) {
+        state.apply(gate, targets);
+    }
+
+    fn measure(&self, state: &mut QuasiRegister, index: usize, rng: &mut dyn QuasiRng) -> bool {
+        state.observe_with(index, rng).branch == Branch::Antimatter
+    }
+
+    fn apply_channel(
+        &self,
+        state: &mut QuasiRegister,
+        channel: &KrausChannel,
+        index: usize,
+        rng: &mut dyn QuasiRng,
+    ) {
+        state.apply_channel_with(channel, index, rng);
+    }
+}
+
+/// Outcome of running a circuit for one or more shots.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionResult {
+    /// Classical bits of every shot, indexed by bit.
+    pub shots: Vec<Vec<bool>>,
+    /// Shot count per bitstring, highest bit first.
+    pub histogram: BTreeMap<String, usize>,
+}
+
+impl ExecutionResult {
+    fn from_shots(shots: Vec<Vec<bool>>) -> Self {
+        let mut histogram = BTreeMap::new();
+        for shot in &shots {
+            *histogram.entry(bitstring(shot)).or_insert(0) += 1;
+        }
+        Self { shots, histogram }
+    }
+
+    pub fn num_shots(&self) -> usize {
+        self.shots.len()
+    }
+
+    /// Shots that produced `bits` (highest bit first).
+    pub fn count(&self, bits: &str) -> usize {
+        self.histogram.get(bits).copied().unwrap_or(0)
+    }
+
+    /// Fraction of shots that produced `bits`.
+    pub fn frequency(&self, bits: &str) -> f64 {
+        if self.shots.is_empty() {
+            0.0
+        } else {
+            self.count(bits) as f64 / self.shots.len() as f64
+        }
+    }
+
+    /// The most frequent bitstring, ties broken towards the smaller string.
+    pub fn most_frequent(&self) -> Option<&str> {
+        self.histogram
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(bits, _)| bits.as_str())
+    }
+}
+
+/// Render classical bits highest index first, as OpenQASM does.
+pub fn bitstring(bits: &[bool]) -> String {
+    bits.iter()
+        .rev()
+        .map(|&b| if b { '1' } else { '0' })
+        .collect()
+}
+
+/// Runs circuits on a backend.
+#[derive(Clone, Debug)]
+pub struct Executor<B: Backend> {
+    backend: B,
+    shots: usize,
+    seed: Option<u64>,
+    noise: NoiseModel,
+}
+
+impl<B: Backend> Executor<B> {
+    /// Single-shot executor with an entropy-seeded generator.
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            shots: 1,
+            seed: None,
+            noise: NoiseModel::default(),
+        }
+    }
+
+    /// Number of shots per [`run`](Executor::run).
+    pub fn shots(mut self, shots: usize) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    /// Seed the generator so runs are reproducible.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Apply `noise` after every gate.
+    pub fn noise(mut self, noise: NoiseModel) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Execute `circuit` for the configured number of shots.
+    pub fn run(&self, circuit: &Circuit) -> ExecutionResult {
+        let mut rng = match self.seed {
+            Some(seed) => SplitMix64::seed_from_u64(seed),
+            None => SplitMix64::from_entropy(),
+        };
+        self.run_with(circuit, &mut rng)
+    }
+
+    /// Execute `circuit` drawing randomness from `rng`.
+    pub fn run_with(&self, circuit: &Circuit, rng: &mut dyn QuasiRng) -> ExecutionResult {
+        let shots = (0..self.shots)
+            .map(|_| self.run_shot(circuit, rng).1)
+            .collect();
+        ExecutionResult::from_shots(shots)
+    }
+
+    /// Execute one shot, returning the final backend state and the bits.
+    pub fn run_shot(&self, circuit: &Circuit, rng: &mut dyn QuasiRng) -> (B::State, Vec<bool>) {
+        let mut state = self.backend.prepare(circuit.num_states());
+        let mut bits = vec![false; circuit.num_bits()];
+        for op in circuit.operations() {
+            self.execute(op, &mut state, &mut bits, rng);
+        }
+        (state, bits)
+    }
+
+    fn execute(
+        &self,
+        op: &Operation,
+        state: &mut B::State,
+        bits: &mut [bool],
+        rng: &mut dyn QuasiRng,
+    ) {
+        match op {
+            Operation::Gate { gate, targets } => {
+                self.backend.apply_gate(state, gate.as_ref(), targets);
+                for channel in self.noise.channels_for(gate.name()) {
+                    for &t in targets {
+                        self.backend.apply_channel(state, channel, t, rng);
+                    }
+                }
+            }
+            Operation::Measure { index, bit } => {
+                bits[*bit] = self.backend.measure(state, *index, rng);
+            }
+            Operation::Conditional { condition, op } => {
+                if condition.holds(bits) {
+                    self.execute(op, state, bits, rng);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Density-matrix representation for mixed states and decoherence.
+//!
+//! A [`DensityMatrix`] is the mixed-state counterpart of a
+//! [`QuasiRegister`]: the same index convention, but a `2^N × 2^N`
+//! operator instead of a vector, so coherence can be lost gradually and
+//! read back from the off-diagonal terms.
+
+use crate::circuit::Backend;
+use crate::complex::Complex;
+use crate::error::Result;
+use crate::gate::Gate;
+use crate::matrix::Matrix;
+use crate::memory::qtype::QTypeRegistry;
+use crate::noise::KrausChannel;
+use crate::quasi_core::{Branch, Observation, QuasiState};
+use crate::register::{apply_local, check_targets, QuasiRegister};
+use crate::rng::{QuasiRng, SplitMix64};
+
+/// Joint mixed state of N quasi-states.
+#[derive(Clone, Debug)]
+pub struct DensityMatrix {
+    ids: Vec<String>,
+    qtypes: Vec<String>,
+    scales: Vec<f64>,
+    observed: Vec<bool>,
+    rho: Matrix,
+}
+
+impl DensityMatrix {
+    /// `n` states, all purely in the matter branch.
+    pub fn new(n: usize) -> Self {
+        Self::from_register(&QuasiRegister::new(n))
+    }
+
+    /// Product state of the given states' own density matrices.
+    ///
+    /// Unlike [`QuasiRegister::from_states`], partially decohered inputs
+    /// keep their lost coherence.
+    pub fn from_states(states: &[QuasiState]) -> Self {
+        let mut rho = Matrix::identity(1);
+        for state in states {
+            rho = state.density_matrix().kron(&rho);
+        }
+        let register = QuasiRegister::from_states(states);
+        Self {
+            ids: register.ids,
+            qtypes: register.qtypes,
+            scales: register.scales,
+            observed: register.observed,
+            rho,
+        }
+    }
+
+    /// The pure state `|ψ⟩⟨ψ|` of a register.
+    pub fn from_register(register: &QuasiRegister) -> Self {
+        let psi = register.amplitudes();
+        let total: f64 = psi.iter().map(|z| z.norm_sqr()).sum();
+        let mut rho = Matrix::zeros(psi.len());
+        for (i, &a) in psi.iter().enumerate() {
+            for (j, &b) in psi.iter().enumerate() {
+                rho[(i, j)] = (a * b.conj()) / total;
+            }
+        }
+        Self {
+            ids: register.ids.clone(),
+            qtypes: register.qtypes.clone(),
+            scales: register.scales.clone(),
+            observed: register.observed.clone(),
+            rho,
+        }
+    }
+
+    /// Number of states.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Index of the state with the given id.
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|s| s == id)
+    }
+
+    /// The full `2^N × 2^N` operator.
+    pub fn matrix(&self) -> &Matrix {
+        &self.rho
+    }
+
+    /// Apply `gate` to `targets` as `UρU†`.
+    pub fn apply(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        check_targets(self.len(), gate, targets);
+        self.conjugate(&gate.matrix(), targets);
+    }
+
+    /// [`apply`](DensityMatrix::apply) with the qtype checks of
+    /// [`QuasiRegister::apply_typed`].
+    pub fn apply_typed(
+        &mut self,
+        registry: &QTypeRegistry,
+        gate: &dyn Gate,
+        targets: &[usize],
+    ) -> Result<()> {
+        check_targets(self.len(), gate, targets);
+        let qtypes: Vec<&str> = targets.iter().map(|&t| self.qtypes[t].as_str()).collect();
+        registry.check_gate(gate, &qtypes)?;
+        self.conjugate(&gate.matrix(), targets);
+        Ok(())
+    }
+
+    /// `ρ → OρO†` for a local operator `op` on `targets`.
+    ///
+    /// `op` need not be unitary; callers are responsible for the trace.
+    pub fn conjugate(&mut self, op: &Matrix, targets: &[usize]) {
+        self.rho = conjugated(&self.rho, op, targets);
+    }
+
+    /// Pass the state at `index` through `channel`.
+    pub fn apply_channel(&mut self, channel: &KrausChannel, index: usize) {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let dim = self.rho.dim();
+        let mut out = Matrix::zeros(dim);
+        for k in channel.operators() {
+            let term = conjugated(&self.rho, k, &[index]);
+            for r in 0..dim {
+                for c in 0..dim {
+                    out[(r, c)] += term[(r, c)];
+                }
+            }
+        }
+        self.rho = out;
+    }
+
+    /// Probabilities of every joint basis branch.
+    pub fn probabilities(&self) -> Vec<f64> {
+        let total = self.rho.trace().re;
+        (0..self.rho.dim())
+            .map(|i| self.rho[(i, i)].re / total)
+            .collect()
+    }
+
+    /// Marginal `(p_matter, p_antimatter)` of the state at `index`.
+    pub fn marginal(&self, index: usize) -> (f64, f64) {
+        let rho = self.reduced_density(index);
+        (rho[(0, 0)].re, rho[(1, 1)].re)
+    }
+
+    /// Reduced 2×2 density matrix of the state at `index`.
+    pub fn reduced_density(&self, index: usize) -> Matrix {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let mask = 1usize << index;
+        let mut red = Matrix::zeros(2);
+        for b in (0..self.rho.dim()).filter(|b| b & mask == 0) {
+            red[(0, 0)] += self.rho[(b, b)];
+            red[(0, 1)] += self.rho[(b, b | mask)];
+            red[(1, 0)] += self.rho[(b | mask, b)];
+            red[(1, 1)] += self.rho[(b | mask, b | mask)];
+        }
+        let total = self.rho.trace().re;
+        red.scale(Complex::real(1.0 / total))
+    }
+
+    /// Coherence `2|ρ₀₁|` of the state at `index`.
+    pub fn coherence(&self, index: usize) -> f64 {
+        2.0 * self.reduced_density(index)[(0, 1)].abs()
+    }
+
+    /// Purity `tr(ρ²)` — 1 for a pure joint state.
+    pub fn purity(&self) -> f64 {
+        let total = self.rho.trace().re;
+        (&self.rho * &self.rho).trace().re / (total * total)
+    }
+
+    /// Scale every term coherent in state `index` by `1 - strength`.
+    pub fn decohere(&mut self, index: usize, strength: f64) {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let keep = 1.0 - strength.clamp(0.0, 1.0);
+        let mask = 1usize << index;
+        let dim = self.rho.dim();
+        for i in 0..dim {
+            for j in 0..dim {
+                if (i ^ j) & mask != 0 {
+                    self.rho[(i, j)] = self.rho[(i, j)] * keep;
+                }
+            }
+        }
+    }
+
+    /// Observe the state at `index` with a freshly seeded generator.
+    pub fn observe(&mut self, index: usize) -> Observation {
+        self.observe_with(index, &mut SplitMix64::from_entropy())
+    }
+
+    /// Observe the state at `index`, projecting the joint operator.
+    ///
+    /// As with [`QuasiRegister::observe_with`], the reported value is the
+    /// chosen branch's signed token value in the reduced view, taken
+    /// before collapse.
+    pub fn observe_with<R: QuasiRng + ?Sized>(&mut self, index: usize, rng: &mut R) -> Observation {
+        let (p0, _) = self.marginal(index);
+        let view = self.state(index);
+        let (branch, token, bit) = if rng.next_f64() < p0 {
+            (Branch::Matter, &view.field.matter, 0)
+        } else {
+            (Branch::Antimatter, &view.field.antimatter, 1)
+        };
+        let mask = 1usize << index;
+        let dim = self.rho.dim();
+        for i in 0..dim {
+            for j in 0..dim {
+                let keep = (i & mask != 0) as usize == bit && (j & mask != 0) as usize == bit;
+                if !keep {
+                    self.rho[(i, j)] = Complex::ZERO;
+                }
+            }
+        }
+        let total = self.rho.trace().re;
+        if total > 0.0 {
+            self.rho = self.rho.scale(Complex::real(1.0 / total));
+        }
+        self.observed[index] = true;
+        Observation {
+            branch,
+            value: token.value,
+        }
+    }
+
+    /// Reduced view of the state at `index`, coherence included.
+    pub fn state(&self, index: usize) -> QuasiState {
+        let mut state = QuasiState::unchecked(
+            &self.ids[index],
+            &self.qtypes[index],
+            self.scales[index],
+            0.0,
+        );
+        state.field.set_density(&self.reduced_density(index));
+        state.observed = self.observed[index];
+        state
+    }
+}
+
+/// `OmO†` for a local operator `op` on `targets`.
+fn conjugated(m: &Matrix, op: &Matrix, targets: &[usize]) -> Matrix {
+    let left = columns_mapped(m, op, targets);
+    columns_mapped(&left.dagger(), op, targets).dagger()
+}
+
+/// `op` applied to every column of `m`.
+fn columns_mapped(m: &Matrix, op: &Matrix, targets: &[usize]) -> Matrix {
+    let dim = m.dim();
+    let mut out = m.clone();
+    let mut column = vec![Complex::ZERO; dim];
+    for j in 0..dim {
+        for (i, z) in column.iter_mut().enumerate() {
+            *z = m[(i, j)];
+        }
+        apply_local(op, targets, &mut column);
+        for (i, &z) in column.iter().enumerate() {
+            out[(i, j)] = z;
+        }
+    }
+    out
+}
+
+/// Mixed-state backend over [`DensityMatrix`].
+#[derive(Clone, Debug, Default)]
+pub struct DensityMatrixBackend {
+    initial: Option<DensityMatrix>,
+}
+
+impl DensityMatrixBackend {
+    /// Start every shot with all states purely in the matter branch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start every shot from `rho`.
+    pub fn with_initial(rho: DensityMatrix) -> Self {
+        Self { initial: Some(rho) }
+    }
+}
+
+impl Backend for DensityMatrixBackend {
+    type State = DensityMatrix;
+
+    fn prepare(&self, num_states: usize) -> DensityMatrix {
+        match &self.initial {
+            Some(rho) => {
+                assert_eq!(
+                    rho.len(),
+                    num_states,
+                    "initial density matrix size does not match the circuit"
+                );
+                rho.clone()
+            }
+            None => DensityMatrix::new(num_states),
+        }
+    }
+
+    fn apply_gate(&self, state: &mut DensityMatrix, gate: &dyn Gate, targets: &[usize]) {
+        state.apply(gate, targets);
+    }
+
+    fn measure(&self, state: &mut DensityMatrix, index: usize, rng: &mut dyn QuasiRng) -> bool {
+        state.observe_with(index, rng).branch == Branch::Antimatter
+    }
+
+    fn apply_channel(
+        &self,
+        state: &mut DensityMatrix,
+        channel: &KrausChannel,
+        index: usize,
+        _rng: &mut dyn QuasiRng,
+    ) {
+        state.apply_channel(channel, index);
+    }
+}
@@ -0,0 +1,129 @@
+//! Structured errors for invalid values and state transitions.
+
+use std::fmt::{Display, Formatter};
+
+use crate::memory::qtype::Operation;
+
+/// Largest token magnitude accepted by the fallible constructors.
+///
+/// Squared magnitudes feed the Born weights; staying below `1e150` keeps
+/// them finite.
+pub const MAX_MAGNITUDE: f64 = 1e150;
+
+/// Everything that can go wrong building or transforming quasi-states.
+///
+/// New variants may be added as the library grows, so matches need a
+/// wildcard arm.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum QuasiError {
+    /// A state id was empty.
+    EmptyId,
+    /// A value was NaN or infinite.
+    NonFinite { field: &'static str, value: f64 },
+    /// A finite value fell outside its allowed range.
+    OutOfRange {
+        field: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// The transition is not allowed on a state that has collapsed.
+    AlreadyObserved { id: String },
+    /// Serialized data carries a version this build cannot read: a newer
+    /// one, or 0, which no format uses.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// A qtype was declared with an empty name.
+    EmptyQType,
+    /// A qtype name is not in the registry.
+    UnknownQType { name: String },
+    /// A qtype name was registered twice.
+    DuplicateQType { name: String },
+    /// Two qtypes with different units met in one field or operation.
+    IncompatibleQTypes { a: String, b: String },
+    /// A multi-state gate targets states of incompatible qtypes.
+    IncompatibleGate { gate: String, a: String, b: String },
+    /// The qtype does not allow this operation.
+    OperationNotAllowed { qtype: String, operation: Operation },
+}
+
+impl Display for QuasiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuasiError::EmptyId => write!(f, "state id must not be empty"),
+            QuasiError::NonFinite { field, value } => {
+                write!(f, "{} must be finite, got {}", field, value)
+            }
+            QuasiError::OutOfRange {
+                field,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} = {} is outside the allowed range [{}, {}]",
+                field,
+                Num(*value),
+                Num(*min),
+                Num(*max)
+            ),
+            QuasiError::AlreadyObserved { id } => {
+                write!(f, "state `{}` has already been observed", id)
+            }
+            QuasiError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "format version {} is not supported (the newest supported version is {})",
+                found, supported
+            ),
+            QuasiError::EmptyQType => write!(f, "qtype name must not be empty"),
+            QuasiError::UnknownQType { name } => write!(f, "unknown qtype `{}`", name),
+            QuasiError::DuplicateQType { name } => {
+                write!(f, "qtype `{}` is already registered", name)
+            }
+            QuasiError::IncompatibleQTypes { a, b } => {
+                write!(f, "qtypes `{}` and `{}` are incompatible", a, b)
+            }
+            QuasiError::IncompatibleGate { gate, a, b } => write!(
+                f,
+                "gate `{}` cannot act on incompatible qtypes `{}` and `{}`",
+                gate, a, b
+            ),
+            QuasiError::OperationNotAllowed { qtype, operation } => {
+                write!(f, "qtype `{}` does not allow {}", qtype, operation)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QuasiError {}
+
+/// Plain notation for everyday values, scientific for huge ones.
+struct Num(f64);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0.abs() >= 1e9 {
+            write!(f, "{:e}", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, QuasiError>;
+
+/// Check that `value` is finite and within `[min, max]`.
+pub(crate) fn check_range(field: &'static str, value: f64, min: f64, max: f64) -> Result<()> {
+    if !value.is_finite() {
+        return Err(QuasiError::NonFinite { field, value });
+    }
+    if value < min || value > max {
+        return Err(QuasiError::OutOfRange {
+            field,
+            value,
+            min,
+            max,
+        });
+    }
+    Ok(())
+}
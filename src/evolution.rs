@@ -0,0 +1,281 @@
+//! Time evolution — Hamiltonian dynamics with T1/T2 relaxation.
+//!
+//! [`Dynamics`] advances anything implementing [`Evolve`] by small steps:
+//! a unitary `e^{-iHdt}` followed by amplitude damping (T1) and pure
+//! dephasing (T2). [`TrajectoryRecorder`] samples coherence along the way.
+
+use crate::complex::Complex;
+use crate::density::DensityMatrix;
+use crate::error::{check_range, QuasiError, Result};
+use crate::gate::{Gate, X, Y, Z};
+use crate::matrix::Matrix;
+use crate::noise::KrausChannel;
+use crate::quasi_core::QuasiState;
+
+/// A Hermitian generator of time evolution, in units where `ħ = 1`.
+#[derive(Clone, Debug)]
+pub struct Hamiltonian {
+    matrix: Matrix,
+}
+
+impl Hamiltonian {
+    /// Wrap a Hermitian matrix. Panics if `matrix` is not Hermitian.
+    pub fn new(matrix: Matrix) -> Self {
+        assert!(matrix.is_hermitian(1e-9), "Hamiltonian must be Hermitian");
+        Self { matrix }
+    }
+
+    /// Single-state `H = x·X + y·Y + z·Z`.
+    pub fn pauli(x: f64, y: f64, z: f64) -> Self {
+        let term = |k: f64, g: &dyn Gate| g.matrix().scale(Complex::real(k));
+        Self::new(&(&term(x, &X) + &term(y, &Y)) + &term(z, &Z))
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    /// Number of states the Hamiltonian couples.
+    pub fn arity(&self) -> usize {
+        self.matrix.dim().trailing_zeros() as usize
+    }
+
+    /// Propagator `U(t) = e^{-iHt}`.
+    pub fn propagator(&self, t: f64) -> Matrix {
+        self.matrix.scale(Complex::new(0.0, -t)).exp()
+    }
+}
+
+/// Optional T1 (energy) and T2 (phase) relaxation times.
+///
+/// T1 relaxes antimatter into matter; T2 bounds how long coherence
+/// survives. Both must be positive and finite, and physical values
+/// satisfy `T2 ≤ 2·T1`; [`new`](Relaxation::new) rejects anything else.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Relaxation {
+    pub t1: Option<f64>,
+    pub t2: Option<f64>,
+}
+
+impl Relaxation {
+    /// Validated relaxation times; see [`validate`](Relaxation::validate).
+    pub fn new(t1: Option<f64>, t2: Option<f64>) -> Result<Self> {
+        let relaxation = Self { t1, t2 };
+        relaxation.validate()?;
+        Ok(relaxation)
+    }
+
+    /// Check that each time is positive and finite and that `T2 ≤ 2·T1`,
+    /// e.g. after setting the fields directly.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(t1) = self.t1 {
+            check_time("t1", t1, f64::MAX)?;
+        }
+        if let Some(t2) = self.t2 {
+            check_time("t2", t2, self.t1.map_or(f64::MAX, |t1| 2.0 * t1))?;
+        }
+        Ok(())
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.t1.is_none() && self.t2.is_none()
+    }
+
+    /// Channels realising `dt` of relaxation, amplitude damping first.
+    pub fn channels(&self, dt: f64) -> Vec<KrausChannel> {
+        let mut channels = Vec::new();
+        let t1_rate = self.t1.map_or(0.0, |t1| 1.0 / t1);
+        if t1_rate > 0.0 {
+            channels.push(KrausChannel::amplitude_damping(1.0 - (-dt * t1_rate).exp()));
+        }
+        if let Some(t2) = self.t2 {
+            // Amplitude damping already removes coherence at rate 1/(2·T1);
+            // dephasing supplies the rest of 1/T2.
+            let dephasing_rate = (1.0 / t2 - t1_rate / 2.0).max(0.0);
+            if dephasing_rate > 0.0 {
+                channels.push(KrausChannel::phase_damping(
+                    1.0 - (-2.0 * dt * dephasing_rate).exp(),
+                ));
+            }
+        }
+        channels
+    }
+}
+
+/// Reject relaxation times that are non-finite, not positive or above
+/// `max`.
+fn check_time(field: &'static str, value: f64, max: f64) -> Result<()> {
+    check_range(field, value, 0.0, max)?;
+    if value == 0.0 {
+        return Err(QuasiError::OutOfRange {
+            field,
+            value,
+            min: 0.0,
+            max,
+        });
+    }
+    Ok(())
+}
+
+/// Something that can be advanced in time.
+///
+/// Implemented for [`QuasiState`] and [`DensityMatrix`]. A pure
+/// [`QuasiRegister`](crate::register::QuasiRegister) cannot hold the
+/// mixture relaxation produces: it can only sample one trajectory, which
+/// needs a generator that [`relax`](Evolve::relax) does not take. Evolve
+/// a register with [`QuasiRegister::evolve`] and
+/// [`QuasiRegister::apply_channel_with`], or convert it with
+/// [`DensityMatrix::from_register`].
+///
+/// [`QuasiRegister::evolve`]: crate::register::QuasiRegister::evolve
+/// [`QuasiRegister::apply_channel_with`]: crate::register::QuasiRegister::apply_channel_with
+pub trait Evolve {
+    /// Unitary evolution for time `t`.
+    fn evolve(&mut self, hamiltonian: &Hamiltonian, t: f64);
+
+    /// `dt` of T1/T2 relaxation on every member state.
+    fn relax(&mut self, relaxation: &Relaxation, dt: f64);
+
+    /// Coherence of the system; the mean over member states for registers.
+    fn measure_coherence(&self) -> f64;
+}
+
+impl Evolve for QuasiState {
+    fn evolve(&mut self, hamiltonian: &Hamiltonian, t: f64) {
+        assert_eq!(hamiltonian.arity(), 1, "Hamiltonian must act on one state");
+        self.apply_unitary(&hamiltonian.propagator(t));
+    }
+
+    fn relax(&mut self, relaxation: &Relaxation, dt: f64) {
+        for channel in relaxation.channels(dt) {
+            self.apply_channel(&channel);
+        }
+    }
+
+    fn measure_coherence(&self) -> f64 {
+        QuasiState::measure_coherence(self)
+    }
+}
+
+impl Evolve for DensityMatrix {
+    fn evolve(&mut self, hamiltonian: &Hamiltonian, t: f64) {
+        assert_eq!(
+            hamiltonian.arity(),
+            self.len(),
+            "Hamiltonian must act on the whole register"
+        );
+        let targets: Vec<usize> = (0..self.len()).collect();
+        self.conjugate(&hamiltonian.propagator(t), &targets);
+    }
+
+    fn relax(&mut self, relaxation: &Relaxation, dt: f64) {
+        for channel in relaxation.channels(dt) {
+            for index in 0..self.len() {
+                self.apply_channel(&channel, index);
+            }
+        }
+    }
+
+    fn measure_coherence(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        (0..self.len()).map(|k| self.coherence(k)).sum::<f64>() / self.len() as f64
+    }
+}
+
+/// A Hamiltonian together with optional relaxation.
+#[derive(Clone, Debug)]
+pub struct Dynamics {
+    pub hamiltonian: Hamiltonian,
+    pub relaxation: Relaxation,
+}
+
+impl Dynamics {
+    /// Closed-system dynamics under `hamiltonian`.
+    pub fn new(hamiltonian: Hamiltonian) -> Self {
+        Self {
+            hamiltonian,
+            relaxation: Relaxation::default(),
+        }
+    }
+
+    /// Add T1/T2 relaxation after every step.
+    pub fn with_relaxation(mut self, relaxation: Relaxation) -> Self {
+        self.relaxation = relaxation;
+        self
+    }
+
+    /// Advance `system` by `dt`: unitary first, then relaxation.
+    pub fn step<S: Evolve + ?Sized>(&self, system: &mut S, dt: f64) {
+        system.evolve(&self.hamiltonian, dt);
+        system.relax(&self.relaxation, dt);
+    }
+
+    /// Advance `system` by `t` in `steps` equal steps.
+    pub fn run<S: Evolve + ?Sized>(&self, system: &mut S, t: f64, steps: usize) {
+        let dt = t / steps.max(1) as f64;
+        for _ in 0..steps.max(1) {
+            self.step(system, dt);
+        }
+    }
+}
+
+/// Coherence sampled at fixed times.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trajectory {
+    pub samples: Vec<(f64, f64)>,
+}
+
+impl Trajectory {
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples.iter().map(|s| s.0)
+    }
+
+    pub fn coherence(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples.iter().map(|s| s.1)
+    }
+}
+
+/// Samples `measure_coherence()` every `interval` while evolving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryRecorder {
+    interval: f64,
+    substeps: usize,
+}
+
+impl TrajectoryRecorder {
+    /// Sample every `interval`, integrating with ten steps per interval.
+    pub fn new(interval: f64) -> Self {
+        assert!(interval > 0.0, "sampling interval must be positive");
+        Self {
+            interval,
+            substeps: 10,
+        }
+    }
+
+    /// Integration steps taken between consecutive samples.
+    pub fn substeps(mut self, substeps: usize) -> Self {
+        self.substeps = substeps.max(1);
+        self
+    }
+
+    /// Evolve `system` for `duration`, sampling at `0, interval, …`.
+    pub fn record<S: Evolve + ?Sized>(
+        &self,
+        dynamics: &Dynamics,
+        system: &mut S,
+        duration: f64,
+    ) -> Trajectory {
+        let mut samples = vec![(0.0, system.measure_coherence())];
+        let count = (duration / self.interval).floor() as usize;
+        let dt = self.interval / self.substeps as f64;
+        for k in 1..=count {
+            for _ in 0..self.substeps {
+                dynamics.step(system, dt);
+            }
+            samples.push((k as f64 * self.interval, system.measure_coherence()));
+        }
+        Trajectory { samples }
+    }
+}
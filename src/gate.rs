@@ -0,0 +1,269 @@
+//! Gate library — unitary transformations of states and registers.
+//!
+//! A gate acting on `k` targets is a `2^k × 2^k` unitary. Within that
+//! matrix, bit `j` of the local basis index belongs to the `j`-th target,
+//! with matter as 0 and antimatter as 1, matching [`QuasiRegister`].
+//!
+//! [`QuasiRegister`]: crate::register::QuasiRegister
+
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
+use std::fmt::Debug;
+
+use crate::complex::Complex;
+use crate::matrix::Matrix;
+
+/// A unitary transformation on one or more quasi-states.
+///
+/// Implement this to define custom unitaries; everything that applies the
+/// built-in gates accepts a `&dyn Gate`.
+pub trait Gate: Debug + Send + Sync {
+    /// Lower-case mnemonic, e.g. `"h"` or `"cx"`.
+    fn name(&self) -> &str;
+
+    /// Number of states the gate acts on.
+    fn arity(&self) -> usize;
+
+    /// The `2^arity`-dimensional unitary.
+    fn matrix(&self) -> Matrix;
+
+    /// Angle parameters, empty for fixed gates.
+    fn params(&self) -> Vec<f64> {
+        Vec::new()
+    }
+}
+
+macro_rules! fixed_gate {
+    ($(#[$doc:meta])* $ty:ident, $name:literal, $arity:literal, $matrix:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $ty;
+
+        impl Gate for $ty {
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn arity(&self) -> usize {
+                $arity
+            }
+
+            fn matrix(&self) -> Matrix {
+                $matrix
+            }
+        }
+    };
+}
+
+macro_rules! rotation_gate {
+    ($(#[$doc:meta])* $ty:ident, $name:literal, |$theta:ident| $matrix:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct $ty(pub f64);
+
+        impl Gate for $ty {
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn arity(&self) -> usize {
+                1
+            }
+
+            fn matrix(&self) -> Matrix {
+                let $theta = self.0;
+                $matrix
+            }
+
+            fn params(&self) -> Vec<f64> {
+                vec![self.0]
+            }
+        }
+    };
+}
+
+fixed_gate!(
+    /// Pauli-X — the matter ↔ antimatter inversion.
+    X, "x", 1, Matrix::from_real([[0.0, 1.0], [1.0, 0.0]])
+);
+fixed_gate!(
+    /// Pauli-Y.
+    Y,
+    "y",
+    1,
+    Matrix::from_rows(vec![
+        vec![Complex::ZERO, -Complex::I],
+        vec![Complex::I, Complex::ZERO],
+    ])
+);
+fixed_gate!(
+    /// Pauli-Z — flips the antimatter phase.
+    Z, "z", 1, Matrix::from_real([[1.0, 0.0], [0.0, -1.0]])
+);
+fixed_gate!(
+    /// Hadamard — maps a pure branch onto an even superposition.
+    H,
+    "h",
+    1,
+    Matrix::from_real([[FRAC_1_SQRT_2, FRAC_1_SQRT_2], [FRAC_1_SQRT_2, -FRAC_1_SQRT_2]])
+);
+fixed_gate!(
+    /// Phase gate, `√Z`.
+    S, "s", 1, Matrix::diagonal(&[Complex::ONE, Complex::I])
+);
+fixed_gate!(
+    /// Inverse phase gate, `S†`.
+    Sdg, "sdg", 1, Matrix::diagonal(&[Complex::ONE, -Complex::I])
+);
+fixed_gate!(
+    /// π/8 gate, `√S`.
+    T, "t", 1, Matrix::diagonal(&[Complex::ONE, Complex::cis(FRAC_PI_4)])
+);
+fixed_gate!(
+    /// Inverse π/8 gate, `T†`.
+    Tdg, "tdg", 1, Matrix::diagonal(&[Complex::ONE, Complex::cis(-FRAC_PI_4)])
+);
+fixed_gate!(
+    /// Controlled-X. Target 0 is the control, target 1 is flipped.
+    CNot,
+    "cx",
+    2,
+    Matrix::from_real([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+    ])
+);
+fixed_gate!(
+    /// Controlled-Z. Symmetric in its two targets.
+    CZ, "cz", 2, Matrix::diagonal(&[Complex::ONE, Complex::ONE, Complex::ONE, -Complex::ONE])
+);
+fixed_gate!(
+    /// Exchanges the two target states.
+    Swap,
+    "swap",
+    2,
+    Matrix::from_real([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ])
+);
+
+rotation_gate!(
+    /// Rotation by `θ` about the X axis.
+    Rx,
+    "rx",
+    |theta| {
+        let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+        Matrix::from_rows(vec![
+            vec![Complex::real(c), Complex::new(0.0, -s)],
+            vec![Complex::new(0.0, -s), Complex::real(c)],
+        ])
+    }
+);
+rotation_gate!(
+    /// Rotation by `θ` about the Y axis.
+    Ry,
+    "ry",
+    |theta| {
+        let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+        Matrix::from_real([[c, -s], [s, c]])
+    }
+);
+rotation_gate!(
+    /// Rotation by `θ` about the Z axis.
+    Rz,
+    "rz",
+    |theta| Matrix::diagonal(&[Complex::cis(-theta / 2.0), Complex::cis(theta / 2.0)])
+);
+rotation_gate!(
+    /// Relative phase `e^{iλ}` on the antimatter branch.
+    Phase,
+    "p",
+    |lambda| Matrix::diagonal(&[Complex::ONE, Complex::cis(lambda)])
+);
+
+/// A user-supplied unitary under a chosen name.
+#[derive(Clone, Debug)]
+pub struct UnitaryGate {
+    name: String,
+    arity: usize,
+    matrix: Matrix,
+}
+
+impl UnitaryGate {
+    /// Wrap `matrix` as a gate.
+    ///
+    /// Panics if `matrix` is not a unitary of power-of-two dimension.
+    pub fn new(name: &str, matrix: Matrix) -> Self {
+        let dim = matrix.dim();
+        assert!(
+            dim.is_power_of_two() && dim >= 2,
+            "gate dimension must be a power of two"
+        );
+        assert!(matrix.is_unitary(1e-9), "gate matrix must be unitary");
+        Self {
+            name: name.to_string(),
+            arity: dim.trailing_zeros() as usize,
+            matrix,
+        }
+    }
+}
+
+impl Gate for UnitaryGate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn matrix(&self) -> Matrix {
+        self.matrix.clone()
+    }
+}
+
+/// Every mnemonic [`standard`] accepts, with the number of angles it takes.
+pub const STANDARD: [(&str, usize); 15] = [
+    ("x", 0),
+    ("y", 0),
+    ("z", 0),
+    ("h", 0),
+    ("s", 0),
+    ("sdg", 0),
+    ("t", 0),
+    ("tdg", 0),
+    ("cx", 0),
+    ("cz", 0),
+    ("swap", 0),
+    ("rx", 1),
+    ("ry", 1),
+    ("rz", 1),
+    ("p", 1),
+];
+
+/// Look up a built-in gate by mnemonic, with its angle parameters.
+pub fn standard(name: &str, params: &[f64]) -> Option<Box<dyn Gate>> {
+    let gate: Box<dyn Gate> = match (name, params) {
+        ("x", []) => Box::new(X),
+        ("y", []) => Box::new(Y),
+        ("z", []) => Box::new(Z),
+        ("h", []) => Box::new(H),
+        ("s", []) => Box::new(S),
+        ("sdg", []) => Box::new(Sdg),
+        ("t", []) => Box::new(T),
+        ("tdg", []) => Box::new(Tdg),
+        ("cx", []) => Box::new(CNot),
+        ("cz", []) => Box::new(CZ),
+        ("swap", []) => Box::new(Swap),
+        ("rx", [theta]) => Box::new(Rx(*theta)),
+        ("ry", [theta]) => Box::new(Ry(*theta)),
+        ("rz", [theta]) => Box::new(Rz(*theta)),
+        ("p", [lambda]) => Box::new(Phase(*lambda)),
+        _ => return None,
+    };
+    Some(gate)
+}
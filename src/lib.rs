@@ -0,0 +1,48 @@
+//! Quantum Superpositional Infrastructure (QUASI)
+//! Author: OBINexus / Namdi
+//! License: OBINexus Public License (OPL)
+//!
+//! Core module export. The crate is laid out along the README's
+//! repository layout: quasi-state logic at the root, topological
+//! modelling under [`topology`], tokens and persistence under [`memory`].
+
+pub mod circuit;
+pub mod complex;
+pub mod density;
+pub mod error;
+pub mod evolution;
+pub mod gate;
+pub mod matrix;
+pub mod memory;
+pub mod noise;
+pub mod qasm;
+pub mod qec;
+pub mod quasi_core;
+pub mod register;
+pub mod repl;
+pub mod rng;
+#[cfg(feature = "serde")]
+pub mod schema;
+pub mod topology;
+
+pub use circuit::{Circuit, ExecutionResult, Executor, StateVectorBackend};
+pub use complex::Complex;
+pub use density::{DensityMatrix, DensityMatrixBackend};
+pub use error::QuasiError;
+pub use evolution::{Dynamics, Evolve, Hamiltonian, Relaxation, TrajectoryRecorder};
+pub use gate::Gate;
+pub use matrix::Matrix;
+pub use memory::qmemory::QuasiMemory;
+pub use memory::qtoken::{BellKind, EntanglementGraph, QToken};
+pub use memory::qtype::{QType, QTypeRegistry};
+pub use memory::store::MemoryStore;
+pub use noise::{KrausChannel, NoiseModel};
+pub use qec::{MemoryExperiment, StabilizerCode};
+pub use quasi_core::{Branch, Observation, QuasiState, TransitionPolicy};
+pub use register::QuasiRegister;
+pub use rng::{QuasiRng, SplitMix64};
+pub use topology::braid::{AnyonModel, BraidCompiler, BraidWord};
+pub use topology::entity::QuasiEntity;
+pub use topology::field::{FieldManifold, QuasiField};
+pub use topology::iceberg::Iceberg;
+pub use topology::simplicial::SimplicialComplex;
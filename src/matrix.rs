@@ -0,0 +1,225 @@
+//! Dense square complex matrices for gates, channels and density operators.
+
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use crate::complex::Complex;
+
+/// A dense `dim × dim` complex matrix, stored row-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    dim: usize,
+    data: Vec<Complex>,
+}
+
+impl Matrix {
+    pub fn zeros(dim: usize) -> Self {
+        Self {
+            dim,
+            data: vec![Complex::ZERO; dim * dim],
+        }
+    }
+
+    pub fn identity(dim: usize) -> Self {
+        let mut m = Self::zeros(dim);
+        for i in 0..dim {
+            m[(i, i)] = Complex::ONE;
+        }
+        m
+    }
+
+    /// Build from rows. Panics unless the rows form a square matrix.
+    pub fn from_rows(rows: Vec<Vec<Complex>>) -> Self {
+        let dim = rows.len();
+        assert!(
+            rows.iter().all(|r| r.len() == dim),
+            "matrix rows must form a square"
+        );
+        Self {
+            dim,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Build from a real-valued row-major array.
+    pub fn from_real<const N: usize>(rows: [[f64; N]; N]) -> Self {
+        Self {
+            dim: N,
+            data: rows.iter().flatten().map(|&x| Complex::real(x)).collect(),
+        }
+    }
+
+    /// Diagonal matrix with the given entries.
+    pub fn diagonal(entries: &[Complex]) -> Self {
+        let mut m = Self::zeros(entries.len());
+        for (i, &z) in entries.iter().enumerate() {
+            m[(i, i)] = z;
+        }
+        m
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Conjugate transpose `M†`.
+    pub fn dagger(&self) -> Self {
+        let mut m = Self::zeros(self.dim);
+        for i in 0..self.dim {
+            for j in 0..self.dim {
+                m[(j, i)] = self[(i, j)].conj();
+            }
+        }
+        m
+    }
+
+    pub fn scale(&self, k: Complex) -> Self {
+        Self {
+            dim: self.dim,
+            data: self.data.iter().map(|&z| z * k).collect(),
+        }
+    }
+
+    pub fn trace(&self) -> Complex {
+        (0..self.dim).fold(Complex::ZERO, |acc, i| acc + self[(i, i)])
+    }
+
+    /// Kronecker product `self ⊗ other`.
+    ///
+    /// `other` acts on the low-order index bits, so `B.kron(&A)` applies
+    /// `A` to the first target of a two-target gate and `B` to the second.
+    pub fn kron(&self, other: &Matrix) -> Self {
+        let dim = self.dim * other.dim;
+        let mut m = Self::zeros(dim);
+        for i in 0..self.dim {
+            for j in 0..self.dim {
+                let a = self[(i, j)];
+                for k in 0..other.dim {
+                    for l in 0..other.dim {
+                        m[(i * other.dim + k, j * other.dim + l)] = a * other[(k, l)];
+                    }
+                }
+            }
+        }
+        m
+    }
+
+    /// `M·v`.
+    pub fn apply(&self, v: &[Complex]) -> Vec<Complex> {
+        assert_eq!(
+            v.len(),
+            self.dim,
+            "vector length must match matrix dimension"
+        );
+        (0..self.dim)
+            .map(|i| (0..self.dim).fold(Complex::ZERO, |acc, j| acc + self[(i, j)] * v[j]))
+            .collect()
+    }
+
+    /// Largest entry-wise distance to `other`.
+    pub fn max_abs_diff(&self, other: &Matrix) -> f64 {
+        self.data
+            .iter()
+            .zip(&other.data)
+            .map(|(a, b)| (*a - *b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Matrix exponential `e^M` by scaling and squaring a Taylor series.
+    pub fn exp(&self) -> Matrix {
+        let norm = (0..self.dim)
+            .map(|i| (0..self.dim).map(|j| self[(i, j)].abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+        let mut squarings = 0;
+        let mut scale = 1.0;
+        while norm * scale > 0.5 {
+            scale /= 2.0;
+            squarings += 1;
+        }
+        let a = self.scale(Complex::real(scale));
+        let mut term = Matrix::identity(self.dim);
+        let mut sum = Matrix::identity(self.dim);
+        for k in 1..=18 {
+            term = (&term * &a).scale(Complex::real(1.0 / k as f64));
+            sum = &sum + &term;
+        }
+        for _ in 0..squarings {
+            sum = &sum * &sum;
+        }
+        sum
+    }
+
+    /// `M†M ≈ I` within `tol`.
+    pub fn is_unitary(&self, tol: f64) -> bool {
+        (&self.dagger() * self).max_abs_diff(&Matrix::identity(self.dim)) <= tol
+    }
+
+    /// `M ≈ M†` within `tol`.
+    pub fn is_hermitian(&self, tol: f64) -> bool {
+        self.max_abs_diff(&self.dagger()) <= tol
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = Complex;
+    fn index(&self, (i, j): (usize, usize)) -> &Complex {
+        &self.data[i * self.dim + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Complex {
+        &mut self.data[i * self.dim + j]
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+    fn mul(self, rhs: &Matrix) -> Matrix {
+        assert_eq!(self.dim, rhs.dim, "matrix dimensions must agree");
+        let mut m = Matrix::zeros(self.dim);
+        for i in 0..self.dim {
+            for k in 0..self.dim {
+                let a = self[(i, k)];
+                if a == Complex::ZERO {
+                    continue;
+                }
+                for j in 0..self.dim {
+                    m[(i, j)] += a * rhs[(k, j)];
+                }
+            }
+        }
+        m
+    }
+}
+
+impl Add for &Matrix {
+    type Output = Matrix;
+    fn add(self, rhs: &Matrix) -> Matrix {
+        assert_eq!(self.dim, rhs.dim, "matrix dimensions must agree");
+        Matrix {
+            dim: self.dim,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(&a, &b)| a + b)
+                .collect(),
+        }
+    }
+}
+
+impl Sub for &Matrix {
+    type Output = Matrix;
+    fn sub(self, rhs: &Matrix) -> Matrix {
+        assert_eq!(self.dim, rhs.dim, "matrix dimensions must agree");
+        Matrix {
+            dim: self.dim,
+            data: self
+                .data
+                .iter()
+                .zip(&rhs.data)
+                .map(|(&a, &b)| a - b)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Quantum memory structures — typed tokens and bit-field persistence.
+
+pub mod qmemory;
+pub mod qtoken;
+pub mod qtype;
+pub mod snapshot;
+pub mod store;
+pub mod wal;
@@ -0,0 +1,551 @@
+//! Quantum bit-field persistence model.
+//!
+//! Quasi-Memory keeps state alive across frames. [`QuasiMemory`] stores
+//! [`QuasiState`]s keyed by id; every mutation lands in the current,
+//! open frame, and [`QuasiMemory::commit_frame`] seals it. Each id keeps
+//! a chain of versions tagged with the frame that wrote them, so any
+//! sealed frame can still be read through [`QuasiMemory::at_frame`] until
+//! it is pruned with [`QuasiMemory::retain_from`].
+//!
+//! States can be entangled into Bell-type pairs with
+//! [`QuasiMemory::entangle`]. The [`EntanglementGraph`] describes the open
+//! frame: observing a state collapses every state correlated with it.
+//!
+//! Memory built with [`QuasiMemory::with_registry`] checks every stored
+//! state and every operation against a [`QTypeRegistry`].
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use crate::complex::Complex;
+use crate::error::QuasiError;
+use crate::gate::Gate;
+use crate::matrix::Matrix;
+use crate::memory::qtoken::{BellKind, EntanglementGraph};
+use crate::memory::qtype::{Operation, QTypeRegistry};
+use crate::quasi_core::{Branch, Observation, QuasiState};
+use crate::rng::{QuasiRng, SplitMix64};
+
+/// Why a memory operation was refused.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MemoryError {
+    /// `allocate` found a live state with the same id.
+    Occupied { id: String },
+    /// No live state has this id.
+    Unallocated { id: String },
+    /// The frame has not been written yet.
+    FutureFrame { frame: u64, current: u64 },
+    /// The frame was dropped by `retain_from`.
+    PrunedFrame { frame: u64, oldest: u64 },
+    /// `entangle` was given two states that are already correlated.
+    AlreadyCorrelated { a: String, b: String },
+    /// The gate would mix an entangled state's branches, which needs the
+    /// joint state.
+    EntangledGate { id: String, gate: String },
+    /// The registry rejected a state or operation.
+    Type(QuasiError),
+}
+
+impl Display for MemoryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryError::Occupied { id } => write!(f, "state `{}` is already allocated", id),
+            MemoryError::Unallocated { id } => write!(f, "no state `{}` is allocated", id),
+            MemoryError::FutureFrame { frame, current } => write!(
+                f,
+                "frame {} does not exist yet (current frame is {})",
+                frame, current
+            ),
+            MemoryError::PrunedFrame { frame, oldest } => write!(
+                f,
+                "frame {} was pruned (oldest readable frame is {})",
+                frame, oldest
+            ),
+            MemoryError::AlreadyCorrelated { a, b } => {
+                write!(f, "states `{}` and `{}` are already correlated", a, b)
+            }
+            MemoryError::EntangledGate { id, gate } => write!(
+                f,
+                "gate `{}` would mix the branches of entangled state `{}`",
+                gate, id
+            ),
+            MemoryError::Type(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MemoryError::Type(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<QuasiError> for MemoryError {
+    fn from(e: QuasiError) -> Self {
+        MemoryError::Type(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MemoryError>;
+
+/// One entry of a version chain; `None` records a `free`.
+#[derive(Clone, Debug)]
+struct Version {
+    frame: u64,
+    state: Option<QuasiState>,
+}
+
+/// The version visible at `frame`: the last one written at or before it.
+fn visible(chain: &[Version], frame: u64) -> Option<&Version> {
+    chain[..chain.partition_point(|v| v.frame <= frame)].last()
+}
+
+/// Frame-versioned store of quasi-states keyed by id.
+#[derive(Clone, Debug, Default)]
+pub struct QuasiMemory {
+    slots: BTreeMap<String, Vec<Version>>,
+    frame: u64,
+    oldest: u64,
+    entanglement: EntanglementGraph,
+    registry: Option<QTypeRegistry>,
+}
+
+impl QuasiMemory {
+    /// Empty memory with frame 0 open.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty memory whose first open frame is `frame`; earlier frames
+    /// read as pruned.
+    pub fn starting_at(frame: u64) -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            frame,
+            oldest: frame,
+            entanglement: EntanglementGraph::new(),
+            registry: None,
+        }
+    }
+
+    /// Empty memory that only stores states whose qtypes are declared in
+    /// `registry`, and only performs the operations those types allow.
+    pub fn with_registry(registry: QTypeRegistry) -> Self {
+        Self {
+            registry: Some(registry),
+            ..Self::default()
+        }
+    }
+
+    /// The registry checks run against, if any.
+    pub fn registry(&self) -> Option<&QTypeRegistry> {
+        self.registry.as_ref()
+    }
+
+    /// The open frame that mutations write into.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The oldest frame [`at_frame`](Self::at_frame) can still read.
+    pub fn oldest_frame(&self) -> u64 {
+        self.oldest
+    }
+
+    /// Seal the open frame and open the next one. Returns the sealed
+    /// frame's number.
+    pub fn commit_frame(&mut self) -> u64 {
+        self.frame += 1;
+        self.frame - 1
+    }
+
+    /// Store a new state under its id.
+    pub fn allocate(&mut self, state: QuasiState) -> Result<()> {
+        if self.contains(&state.id) {
+            return Err(MemoryError::Occupied { id: state.id });
+        }
+        self.check_field(&state)?;
+        let id = state.id.clone();
+        self.write(&id, Some(state));
+        Ok(())
+    }
+
+    /// Remove a state, returning it. Earlier frames still see it; its
+    /// pairs are dropped.
+    pub fn free(&mut self, id: &str) -> Result<QuasiState> {
+        let state = self.get(id).cloned().ok_or_else(|| unallocated(id))?;
+        self.entanglement.unlink(id);
+        self.write(id, None);
+        Ok(state)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&QuasiState> {
+        self.slots
+            .get(id)
+            .and_then(|chain| chain.last())
+            .and_then(|v| v.state.as_ref())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Live states in id order.
+    pub fn iter(&self) -> impl Iterator<Item = &QuasiState> {
+        self.slots
+            .values()
+            .filter_map(|chain| chain.last().and_then(|v| v.state.as_ref()))
+    }
+
+    /// Live ids in order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|s| s.id.as_str())
+    }
+
+    /// Number of live states.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Read-only view of the states as they were when `frame` was sealed,
+    /// or of the open frame.
+    pub fn at_frame(&self, frame: u64) -> Result<Frame<'_>> {
+        if frame > self.frame {
+            return Err(MemoryError::FutureFrame {
+                frame,
+                current: self.frame,
+            });
+        }
+        if frame < self.oldest {
+            return Err(MemoryError::PrunedFrame {
+                frame,
+                oldest: self.oldest,
+            });
+        }
+        Ok(Frame {
+            memory: self,
+            frame,
+        })
+    }
+
+    /// Drop history that only frames before `frame` can see. `frame` is
+    /// clamped to the open frame.
+    pub fn retain_from(&mut self, frame: u64) {
+        let frame = frame.min(self.frame);
+        if frame <= self.oldest {
+            return;
+        }
+        self.slots.retain(|_, chain| {
+            let keep = chain.partition_point(|v| v.frame <= frame);
+            if keep > 0 {
+                chain.drain(..keep - 1);
+            }
+            // A chain that is only a tombstone no frame can see is gone.
+            !(chain.len() == 1 && chain[0].state.is_none())
+        });
+        self.oldest = frame;
+    }
+
+    /// Replace a live state with `state` (which must keep its id). The
+    /// replaced state's pairs are dropped.
+    pub fn update(&mut self, state: QuasiState) -> Result<()> {
+        if !self.contains(&state.id) {
+            return Err(unallocated(&state.id));
+        }
+        self.check_field(&state)?;
+        let id = state.id.clone();
+        self.entanglement.unlink(&id);
+        self.write(&id, Some(state));
+        Ok(())
+    }
+
+    /// Collapse a stored state with an entropy-seeded generator.
+    pub fn observe(&mut self, id: &str) -> Result<Observation> {
+        self.observe_with(id, &mut SplitMix64::from_entropy())
+    }
+
+    /// Collapse a stored state, drawing from `rng`. Correlated states
+    /// collapse with it.
+    pub fn observe_with<R: QuasiRng + ?Sized>(
+        &mut self,
+        id: &str,
+        rng: &mut R,
+    ) -> Result<Observation> {
+        self.check(id, Operation::Observe)?;
+        let branch = self
+            .get(id)
+            .ok_or_else(|| unallocated(id))?
+            .sample_branch(rng);
+        self.collapse(id, branch)
+    }
+
+    /// Collapse a stored state onto a known branch, and every state
+    /// correlated with it onto the branch its pair relation implies. The
+    /// pairs are dissolved: afterwards the states are independent.
+    ///
+    /// With a registry, every correlated state must allow observation
+    /// too; otherwise nothing changes.
+    pub fn collapse(&mut self, id: &str, branch: Branch) -> Result<Observation> {
+        self.check(id, Operation::Observe)?;
+        let correlated = self.entanglement.correlated(id);
+        for (partner, _) in &correlated {
+            self.check(partner, Operation::Observe)?;
+        }
+        let observation = self.open(id)?.collapse(branch);
+        for (partner, relation) in &correlated {
+            self.open(partner)?
+                .collapse(relation.partner_branch(branch));
+        }
+        self.entanglement.unlink(id);
+        for (partner, _) in &correlated {
+            self.entanglement.unlink(partner);
+        }
+        Ok(observation)
+    }
+
+    /// Swap matter and antimatter of a stored state. Its pairs swap
+    /// between Φ and Ψ.
+    pub fn invert(&mut self, id: &str) -> Result<()> {
+        self.check(id, Operation::Invert)?;
+        self.open(id)?.invert();
+        self.entanglement.flip(id);
+        Ok(())
+    }
+
+    /// Apply a one-state gate to a stored state.
+    ///
+    /// An entangled state only accepts gates that keep its branches
+    /// apart: diagonal gates leave its pairs as they are, anti-diagonal
+    /// ones (such as `x` and `y`) swap them between Φ and Ψ.
+    ///
+    /// # Panics
+    /// If the gate does not act on exactly one state.
+    pub fn apply_gate(&mut self, id: &str, gate: &dyn Gate) -> Result<()> {
+        self.check_gate(id, gate)?;
+        if self.entanglement.is_entangled(id) && is_anti_diagonal(&gate.matrix()) {
+            self.entanglement.flip(id);
+        }
+        self.open(id)?.apply(gate);
+        Ok(())
+    }
+
+    /// Check that [`apply_gate`](Self::apply_gate) would accept `gate` on
+    /// `id`, without changing anything. With a registry, the state the
+    /// gate produces must also stay in its types' ranges.
+    pub fn check_gate(&self, id: &str, gate: &dyn Gate) -> Result<()> {
+        let state = self.get(id).ok_or_else(|| unallocated(id))?;
+        if let Some(registry) = &self.registry {
+            for token in [&state.field.matter, &state.field.antimatter] {
+                registry.check_gate(gate, &[&token.qtype])?;
+            }
+        }
+        if self.entanglement.is_entangled(id) {
+            let u = gate.matrix();
+            if u.dim() != 2 || !(is_diagonal(&u) || is_anti_diagonal(&u)) {
+                return Err(MemoryError::EntangledGate {
+                    id: id.to_string(),
+                    gate: gate.name().to_string(),
+                });
+            }
+        }
+        if self.registry.is_some() {
+            let mut after = state.clone();
+            after.apply(gate);
+            self.check_field(&after)?;
+        }
+        Ok(())
+    }
+
+    /// Entangle two stored states into a Bell-type pair.
+    ///
+    /// `b`, and everything already correlated with it, takes on `a`'s
+    /// Born weights (swapped across a Ψ relation) while keeping its own
+    /// scale. Each state's local coherence drops to 0: the superposition
+    /// now lives in the pair, and each half on its own is mixed.
+    pub fn entangle(&mut self, a: &str, b: &str, kind: BellKind) -> Result<()> {
+        self.check_entangle(a, b)?;
+        let weights = self
+            .get(a)
+            .ok_or_else(|| unallocated(a))?
+            .field
+            .born_weights();
+        let mut members = vec![(b.to_string(), kind)];
+        members.extend(
+            self.entanglement
+                .correlated(b)
+                .into_iter()
+                .map(|(id, relation)| (id, kind.compose(relation))),
+        );
+        members.push((a.to_string(), BellKind::Phi));
+        for (id, relation) in members {
+            let (p, q) = match relation {
+                BellKind::Phi => weights,
+                BellKind::Psi => (weights.1, weights.0),
+            };
+            let rho = Matrix::diagonal(&[Complex::real(p), Complex::real(q)]);
+            self.open(&id)?.field.set_density(&rho);
+        }
+        self.entanglement.link(a, b, kind);
+        Ok(())
+    }
+
+    /// Check that [`entangle`](Self::entangle) would accept `a` and `b`,
+    /// without changing anything.
+    pub fn check_entangle(&self, a: &str, b: &str) -> Result<()> {
+        self.check(a, Operation::Entangle)?;
+        self.check(b, Operation::Entangle)?;
+        if let (Some(registry), Some(sa), Some(sb)) = (&self.registry, self.get(a), self.get(b)) {
+            registry.check_compatible(&sa.field.matter.qtype, &sb.field.matter.qtype)?;
+        }
+        if a == b || self.entanglement.relation(a, b).is_some() {
+            return Err(MemoryError::AlreadyCorrelated {
+                a: a.to_string(),
+                b: b.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Record a pair between two stored states without touching their
+    /// fields, as when reloading a snapshot taken after `entangle`.
+    pub(crate) fn restore_pair(&mut self, a: &str, b: &str, kind: BellKind) -> Result<()> {
+        for id in [a, b] {
+            if !self.contains(id) {
+                return Err(unallocated(id));
+            }
+        }
+        if !self.entanglement.link(a, b, kind) {
+            return Err(MemoryError::AlreadyCorrelated {
+                a: a.to_string(),
+                b: b.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Dissolve every pair involving `id`. States keep their current,
+    /// mixed fields.
+    pub fn disentangle(&mut self, id: &str) {
+        self.entanglement.unlink(id);
+    }
+
+    /// The pairs between live states.
+    pub fn entanglement(&self) -> &EntanglementGraph {
+        &self.entanglement
+    }
+
+    /// Every state correlated with `id`, with its relation to `id`.
+    pub fn correlated(&self, id: &str) -> Vec<(String, BellKind)> {
+        self.entanglement.correlated(id)
+    }
+
+    /// Check that `id` exists and, with a registry, allows `operation`.
+    fn check(&self, id: &str, operation: Operation) -> Result<()> {
+        let state = self.get(id).ok_or_else(|| unallocated(id))?;
+        if let Some(registry) = &self.registry {
+            registry.check_operation(state, operation)?;
+        }
+        Ok(())
+    }
+
+    /// Registry check of a state about to be stored.
+    fn check_field(&self, state: &QuasiState) -> Result<()> {
+        if let Some(registry) = &self.registry {
+            registry.check_field(&state.field)?;
+        }
+        Ok(())
+    }
+
+    /// Mutable access to `id` in the open frame, copying the previous
+    /// version forward on first write.
+    fn open(&mut self, id: &str) -> Result<&mut QuasiState> {
+        let frame = self.frame;
+        let chain = self
+            .slots
+            .get_mut(id)
+            .filter(|chain| chain.last().is_some_and(|v| v.state.is_some()))
+            .ok_or_else(|| unallocated(id))?;
+        let last = chain.last().expect("chain is non-empty");
+        if last.frame != frame {
+            let state = last.state.clone();
+            chain.push(Version { frame, state });
+        }
+        Ok(chain
+            .last_mut()
+            .and_then(|v| v.state.as_mut())
+            .expect("live version"))
+    }
+
+    fn write(&mut self, id: &str, state: Option<QuasiState>) {
+        let frame = self.frame;
+        let chain = self.slots.entry(id.to_string()).or_default();
+        match chain.last_mut() {
+            Some(last) if last.frame == frame => last.state = state,
+            _ => chain.push(Version { frame, state }),
+        }
+    }
+}
+
+/// Entries below round-off count as zero, so e.g. `rx(π)` is anti-diagonal.
+const GATE_TOL: f64 = 1e-12;
+
+fn is_diagonal(u: &Matrix) -> bool {
+    u[(0, 1)].abs() < GATE_TOL && u[(1, 0)].abs() < GATE_TOL
+}
+
+fn is_anti_diagonal(u: &Matrix) -> bool {
+    u[(0, 0)].abs() < GATE_TOL && u[(1, 1)].abs() < GATE_TOL
+}
+
+fn unallocated(id: &str) -> MemoryError {
+    MemoryError::Unallocated { id: id.to_string() }
+}
+
+/// The states of one frame, as returned by [`QuasiMemory::at_frame`].
+#[derive(Clone, Copy, Debug)]
+pub struct Frame<'a> {
+    memory: &'a QuasiMemory,
+    frame: u64,
+}
+
+impl<'a> Frame<'a> {
+    pub fn number(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn get(&self, id: &str) -> Option<&'a QuasiState> {
+        self.memory
+            .slots
+            .get(id)
+            .and_then(|chain| visible(chain, self.frame))
+            .and_then(|v| v.state.as_ref())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// States live in this frame, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a QuasiState> + 'a {
+        let frame = self.frame;
+        self.memory
+            .slots
+            .values()
+            .filter_map(move |chain| visible(chain, frame).and_then(|v| v.state.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
@@ -0,0 +1,238 @@
+//! Quantum token identity and entanglement pairs.
+
+use std::collections::BTreeMap;
+
+use crate::complex::Complex;
+use crate::error::Result;
+use crate::memory::qtype::{check_declared, QTypeRegistry};
+use crate::quasi_core::Branch;
+
+/// Represents the fundamental quantum token.
+/// Carries both type identity and quantized value.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QToken {
+    pub qtype: String,
+    pub value: f64, // symbolic or probabilistic representation
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub phase: f64, // radians; non-zero only in amplitude mode
+}
+
+impl QToken {
+    /// Real-valued token with zero phase.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn new(qtype: &str, value: f64) -> Self {
+        if let Err(e) = check_declared(qtype) {
+            panic!("{}", e);
+        }
+        Self::unchecked(qtype, value)
+    }
+
+    /// Like [`new`](QToken::new), returning an error for an undeclared
+    /// `qtype`.
+    pub fn try_new(qtype: &str, value: f64) -> Result<Self> {
+        check_declared(qtype)?;
+        Ok(Self::unchecked(qtype, value))
+    }
+
+    /// A token whose `qtype` is not checked, for views that carry no type.
+    pub(crate) fn unchecked(qtype: &str, value: f64) -> Self {
+        Self {
+            qtype: qtype.to_string(),
+            value,
+            phase: 0.0,
+        }
+    }
+
+    /// Real-valued token of a registered type, with its value in range.
+    pub fn typed(registry: &QTypeRegistry, qtype: &str, value: f64) -> Result<Self> {
+        let token = Self::try_new(qtype, value)?;
+        registry.check_token(&token)?;
+        Ok(token)
+    }
+
+    /// Token holding a complex amplitude.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn from_amplitude(qtype: &str, amplitude: Complex) -> Self {
+        let mut token = Self::new(qtype, 0.0);
+        token.set_amplitude(amplitude);
+        token
+    }
+
+    /// The token read as a complex amplitude `value·e^{i·phase}`.
+    pub fn amplitude(&self) -> Complex {
+        if self.phase == 0.0 {
+            Complex::real(self.value)
+        } else {
+            Complex::from_polar(self.value, self.phase)
+        }
+    }
+
+    /// Store `amplitude`, keeping signed real values phase-free.
+    ///
+    /// Imaginary parts below round-off relative to the magnitude are
+    /// treated as zero.
+    pub fn set_amplitude(&mut self, amplitude: Complex) {
+        if amplitude.im.abs() <= 1e-12 * amplitude.abs() {
+            self.value = amplitude.re;
+            self.phase = 0.0;
+        } else {
+            self.value = amplitude.abs();
+            self.phase = amplitude.arg();
+        }
+    }
+}
+
+/// How the outcomes of an entangled pair relate.
+///
+/// Only the outcome correlation is tracked: relative phases of the pair
+/// live in the joint state, which single-state fields do not represent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BellKind {
+    /// `|00⟩ ± |11⟩` — both states collapse onto the same branch.
+    Phi,
+    /// `|01⟩ ± |10⟩` — the states collapse onto opposite branches.
+    Psi,
+}
+
+impl BellKind {
+    /// The relation obtained by following `self` then `other`.
+    pub fn compose(self, other: BellKind) -> BellKind {
+        if self == other {
+            BellKind::Phi
+        } else {
+            BellKind::Psi
+        }
+    }
+
+    /// The same relation after one side is bit-flipped.
+    pub fn flipped(self) -> BellKind {
+        self.compose(BellKind::Psi)
+    }
+
+    /// The partner's branch when one side collapses onto `branch`.
+    pub fn partner_branch(self, branch: Branch) -> Branch {
+        match (self, branch) {
+            (BellKind::Phi, b) => b,
+            (BellKind::Psi, Branch::Matter) => Branch::Antimatter,
+            (BellKind::Psi, Branch::Antimatter) => Branch::Matter,
+        }
+    }
+}
+
+/// Undirected graph of entangled pairs, keyed by state id.
+///
+/// Components are trees: linking two states that are already correlated
+/// is refused, so every path between two states implies the same
+/// relation and observing any member fixes the whole component.
+#[derive(Clone, Debug, Default)]
+pub struct EntanglementGraph {
+    edges: BTreeMap<String, BTreeMap<String, BellKind>>,
+}
+
+impl EntanglementGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a pair. Returns `false`, leaving the graph unchanged, if
+    /// `a` and `b` are already correlated (or equal).
+    pub fn link(&mut self, a: &str, b: &str, kind: BellKind) -> bool {
+        if a == b || self.relation(a, b).is_some() {
+            return false;
+        }
+        self.edges
+            .entry(a.to_string())
+            .or_default()
+            .insert(b.to_string(), kind);
+        self.edges
+            .entry(b.to_string())
+            .or_default()
+            .insert(a.to_string(), kind);
+        true
+    }
+
+    /// Remove every pair involving `id`.
+    pub fn unlink(&mut self, id: &str) {
+        if let Some(partners) = self.edges.remove(id) {
+            for partner in partners.keys() {
+                if let Some(back) = self.edges.get_mut(partner) {
+                    back.remove(id);
+                    if back.is_empty() {
+                        self.edges.remove(partner);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Flip the relation of every pair involving `id`, as a bit flip on
+    /// `id` does.
+    pub fn flip(&mut self, id: &str) {
+        let Some(partners) = self.edges.get_mut(id) else {
+            return;
+        };
+        let ids: Vec<String> = partners.keys().cloned().collect();
+        for kind in partners.values_mut() {
+            *kind = kind.flipped();
+        }
+        for partner in ids {
+            if let Some(kind) = self.edges.get_mut(&partner).and_then(|p| p.get_mut(id)) {
+                *kind = kind.flipped();
+            }
+        }
+    }
+
+    pub fn is_entangled(&self, id: &str) -> bool {
+        self.edges.contains_key(id)
+    }
+
+    /// Direct partners of `id`.
+    pub fn partners(&self, id: &str) -> impl Iterator<Item = (&str, BellKind)> {
+        self.edges
+            .get(id)
+            .into_iter()
+            .flat_map(|p| p.iter().map(|(id, kind)| (id.as_str(), *kind)))
+    }
+
+    /// Every pair once, as `(a, b, kind)` with `a < b`.
+    pub fn pairs(&self) -> impl Iterator<Item = (&str, &str, BellKind)> {
+        self.edges.iter().flat_map(|(a, partners)| {
+            partners
+                .iter()
+                .filter(move |(b, _)| a < *b)
+                .map(move |(b, kind)| (a.as_str(), b.as_str(), *kind))
+        })
+    }
+
+    /// Every state correlated with `id`, directly or through a chain of
+    /// pairs, with its relation to `id`. Breadth-first order; `id`
+    /// itself is not included.
+    pub fn correlated(&self, id: &str) -> Vec<(String, BellKind)> {
+        let mut seen = vec![(id.to_string(), BellKind::Phi)];
+        let mut next = 0;
+        while next < seen.len() {
+            let (current, relation) = seen[next].clone();
+            for (partner, kind) in self.partners(&current) {
+                if !seen.iter().any(|(s, _)| s == partner) {
+                    seen.push((partner.to_string(), relation.compose(kind)));
+                }
+            }
+            next += 1;
+        }
+        seen.remove(0);
+        seen
+    }
+
+    /// The relation between two correlated states.
+    pub fn relation(&self, a: &str, b: &str) -> Option<BellKind> {
+        self.correlated(a)
+            .into_iter()
+            .find(|(id, _)| id == b)
+            .map(|(_, kind)| kind)
+    }
+}
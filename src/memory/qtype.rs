@@ -0,0 +1,304 @@
+//! Declared token types.
+//!
+//! A [`QToken`]'s `qtype` is a name. A [`QTypeRegistry`] gives names
+//! meaning: each [`QType`] has a unit, a range its token values must lie
+//! in, and the [`Operation`]s its states accept. Two types are compatible
+//! when they share a unit, and only compatible types may meet in one
+//! field, one interference or one multi-state gate.
+//!
+//! Names must be [declared](declare) before any token can use them:
+//! `energy`, `spin` and `charge` are declared in every process, and
+//! [`QToken::new`], [`QuasiState::new`] and the other constructors panic
+//! on any other name (their `try_` forms return
+//! [`UnknownQType`](QuasiError::UnknownQType)), so a typo such as
+//! `"enrgy"` fails where it is written. Validation, and so every reader
+//! of saved states, rejects undeclared names too.
+//!
+//! Units, ranges and operations are checked against an explicit
+//! registry: the `typed` constructors and methods (such as
+//! [`QuasiState::typed`] or [`QuasiState::interfere_typed`]) take one,
+//! and a [`QuasiMemory`] built with [`QuasiMemory::with_registry`] checks
+//! every token it stores.
+//!
+//! [`QuasiMemory`]: crate::memory::qmemory::QuasiMemory
+//! [`QuasiMemory::with_registry`]: crate::memory::qmemory::QuasiMemory::with_registry
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+use crate::error::{check_range, QuasiError, Result, MAX_MAGNITUDE};
+use crate::gate::Gate;
+use crate::memory::qtoken::QToken;
+use crate::quasi_core::QuasiState;
+use crate::topology::field::QuasiField;
+
+/// Something that can be done to a state, gated per type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Operation {
+    Observe,
+    Invert,
+    Gate,
+    Interfere,
+    Entangle,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 5] = [
+        Operation::Observe,
+        Operation::Invert,
+        Operation::Gate,
+        Operation::Interfere,
+        Operation::Entangle,
+    ];
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Operation::Observe => "observe",
+            Operation::Invert => "invert",
+            Operation::Gate => "gate",
+            Operation::Interfere => "interfere",
+            Operation::Entangle => "entangle",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Types tokens may use in this process.
+static DECLARED: LazyLock<RwLock<QTypeRegistry>> =
+    LazyLock::new(|| RwLock::new(QTypeRegistry::standard()));
+
+/// Declare `qtype` for the whole process, so tokens of it can be built.
+///
+/// Declaring a type again with the same definition does nothing; a
+/// different definition under a declared name is a
+/// [`DuplicateQType`](QuasiError::DuplicateQType) error.
+pub fn declare(qtype: QType) -> Result<()> {
+    let mut declared = DECLARED.write().unwrap_or_else(PoisonError::into_inner);
+    match declared.get(&qtype.name) {
+        Some(existing) if *existing == qtype => Ok(()),
+        _ => declared.register(qtype),
+    }
+}
+
+/// The declared type called `name`.
+pub fn declared(name: &str) -> Result<QType> {
+    let declared = DECLARED.read().unwrap_or_else(PoisonError::into_inner);
+    declared.lookup(name).cloned()
+}
+
+/// Check that `name` has been [declared](declare).
+pub fn check_declared(name: &str) -> Result<()> {
+    let declared = DECLARED.read().unwrap_or_else(PoisonError::into_inner);
+    declared.lookup(name).map(|_| ())
+}
+
+/// A declared token type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QType {
+    pub name: String,
+    pub unit: String,
+    /// Smallest allowed token value.
+    pub min: f64,
+    /// Largest allowed token value.
+    pub max: f64,
+    operations: Vec<Operation>,
+}
+
+impl QType {
+    /// A type accepting every operation and any value up to
+    /// [`MAX_MAGNITUDE`] in size.
+    pub fn new(name: &str, unit: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            min: -MAX_MAGNITUDE,
+            max: MAX_MAGNITUDE,
+            operations: Operation::ALL.to_vec(),
+        }
+    }
+
+    /// Restrict token values to `[min, max]`.
+    ///
+    /// # Panics
+    /// If either bound is not finite or `min > max`.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        assert!(
+            min.is_finite() && max.is_finite(),
+            "qtype range bounds must be finite"
+        );
+        assert!(min <= max, "qtype range must not be empty");
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Allow only `operations`.
+    pub fn operations(mut self, operations: &[Operation]) -> Self {
+        self.operations = operations.to_vec();
+        self.operations.sort();
+        self.operations.dedup();
+        self
+    }
+
+    pub fn allows(&self, operation: Operation) -> bool {
+        self.operations.contains(&operation)
+    }
+
+    pub fn allowed(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn is_compatible(&self, other: &QType) -> bool {
+        self.unit == other.unit
+    }
+
+    /// Check the name and that `[min, max]` is a finite, non-empty range,
+    /// e.g. after setting the bounds directly.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(QuasiError::EmptyQType);
+        }
+        check_range("qtype min", self.min, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+        check_range("qtype max", self.max, self.min, MAX_MAGNITUDE)?;
+        Ok(())
+    }
+}
+
+/// The set of declared token types.
+#[derive(Clone, Debug, Default)]
+pub struct QTypeRegistry {
+    types: BTreeMap<String, QType>,
+}
+
+impl QTypeRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The types used throughout the examples: `energy` (J), `spin` (ħ)
+    /// and `charge` (e), each allowing every operation.
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+        for qtype in [
+            QType::new("energy", "J"),
+            QType::new("spin", "ħ"),
+            QType::new("charge", "e"),
+        ] {
+            registry.register(qtype).expect("distinct names");
+        }
+        registry
+    }
+
+    /// Declare a type. Names must be unique and the type
+    /// [valid](QType::validate).
+    pub fn register(&mut self, qtype: QType) -> Result<()> {
+        qtype.validate()?;
+        if self.types.contains_key(&qtype.name) {
+            return Err(QuasiError::DuplicateQType { name: qtype.name });
+        }
+        self.types.insert(qtype.name.clone(), qtype);
+        Ok(())
+    }
+
+    /// [Declare](declare) every type in the registry for the process.
+    pub fn declare_all(&self) -> Result<()> {
+        self.types.values().cloned().try_for_each(declare)
+    }
+
+    /// Builder form of [`register`](Self::register).
+    ///
+    /// # Panics
+    /// If the name is empty or already registered.
+    pub fn with(mut self, qtype: QType) -> Self {
+        if let Err(e) = self.register(qtype) {
+            panic!("{}", e);
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&QType> {
+        self.types.get(name)
+    }
+
+    /// The declared type called `name`.
+    pub fn lookup(&self, name: &str) -> Result<&QType> {
+        self.get(name).ok_or_else(|| QuasiError::UnknownQType {
+            name: name.to_string(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QType> {
+        self.types.values()
+    }
+
+    /// Check that `token`'s type is declared and its value in range.
+    pub fn check_token(&self, token: &QToken) -> Result<()> {
+        let qtype = self.lookup(&token.qtype)?;
+        check_range("token value", token.value, qtype.min, qtype.max)?;
+        Ok(())
+    }
+
+    /// Check that two type names are declared and compatible.
+    pub fn check_compatible(&self, a: &str, b: &str) -> Result<()> {
+        let (ta, tb) = (self.lookup(a)?, self.lookup(b)?);
+        if !ta.is_compatible(tb) {
+            return Err(QuasiError::IncompatibleQTypes {
+                a: a.to_string(),
+                b: b.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check both tokens of a field and that they may share it.
+    pub fn check_field(&self, field: &QuasiField) -> Result<()> {
+        self.check_token(&field.matter)?;
+        self.check_token(&field.antimatter)?;
+        self.check_compatible(&field.matter.qtype, &field.antimatter.qtype)
+    }
+
+    /// Check that `operation` is allowed on `state`.
+    pub fn check_operation(&self, state: &QuasiState, operation: Operation) -> Result<()> {
+        for token in [&state.field.matter, &state.field.antimatter] {
+            let qtype = self.lookup(&token.qtype)?;
+            if !qtype.allows(operation) {
+                return Err(QuasiError::OperationNotAllowed {
+                    qtype: qtype.name.clone(),
+                    operation,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `gate` may act on states of the given types: each must
+    /// allow gates, and a multi-state gate needs mutually compatible types.
+    pub fn check_gate(&self, gate: &dyn Gate, qtypes: &[&str]) -> Result<()> {
+        for name in qtypes {
+            let qtype = self.lookup(name)?;
+            if !qtype.allows(Operation::Gate) {
+                return Err(QuasiError::OperationNotAllowed {
+                    qtype: qtype.name.clone(),
+                    operation: Operation::Gate,
+                });
+            }
+        }
+        if let Some((first, rest)) = qtypes.split_first() {
+            for other in rest {
+                self.check_compatible(first, other).map_err(|e| match e {
+                    QuasiError::IncompatibleQTypes { a, b } => QuasiError::IncompatibleGate {
+                        gate: gate.name().to_string(),
+                        a,
+                        b,
+                    },
+                    e => e,
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,589 @@
+//! Compact binary snapshots of quasi-states.
+//!
+//! # Format (version 2)
+//!
+//! All integers and floats are little-endian.
+//!
+//! ```text
+//! header   magic    8 bytes  "QUASISNP"
+//!          version  u16      2
+//!          flags    u16      0 (reserved)
+//!          crc      u32      CRC-32 of the 12 bytes above
+//! record   tag      u8
+//!          payload  …        depends on tag
+//!          crc      u32      CRC-32 of tag and payload
+//! ```
+//!
+//! Records, in stream order:
+//!
+//! | tag    | name   | payload |
+//! |--------|--------|---------|
+//! | `0x01` | qtype  | `index u32`, `len u16`, UTF-8 name — interns the next qtype |
+//! | `0x02` | state  | `len u16`, UTF-8 id, `flags u8` (bit 0: observed), field record |
+//! | `0x03` | pair   | id `a`, id `b` (each `len u16`, UTF-8), `kind u8` (0 Φ, 1 Ψ) |
+//! | `0xFF` | end    | `count u64` — number of state records; must be last |
+//!
+//! The field record is fixed-width, 48 bytes: matter qtype index `u32`,
+//! antimatter qtype index `u32`, then matter value, matter phase,
+//! antimatter value, antimatter phase and coherence as `f64`.
+//!
+//! Qtype indices are assigned densely from 0 in the order qtype records
+//! appear, and a qtype is always declared before the first state that
+//! uses it, so both ends can stream. Likewise a pair record follows the
+//! state records of both its ids. A file without its end record is
+//! reported as truncated. Version 1 files still read; they have no pair
+//! records, so a `0x03` tag in one is an unknown tag.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read, Write};
+
+use crate::error::QuasiError;
+use crate::memory::qtoken::{BellKind, QToken};
+use crate::quasi_core::QuasiState;
+use crate::topology::field::QuasiField;
+
+/// File magic, the first eight bytes of every snapshot.
+pub const MAGIC: [u8; 8] = *b"QUASISNP";
+
+/// Format version written by this build and the newest one it reads.
+pub const SNAPSHOT_VERSION: u16 = 2;
+
+const TAG_QTYPE: u8 = 0x01;
+const TAG_STATE: u8 = 0x02;
+const TAG_PAIR: u8 = 0x03;
+const TAG_END: u8 = 0xFF;
+const FIELD_RECORD_LEN: usize = 48;
+
+/// Why a snapshot could not be written or read. Offsets are byte
+/// positions from the start of the stream.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    BadMagic {
+        found: [u8; 8],
+    },
+    UnsupportedVersion {
+        found: u16,
+        supported: u16,
+    },
+    /// The stream ended inside a record, or before the end record.
+    Truncated {
+        offset: u64,
+    },
+    ChecksumMismatch {
+        offset: u64,
+        expected: u32,
+        found: u32,
+    },
+    UnknownTag {
+        offset: u64,
+        tag: u8,
+    },
+    /// A qtype record did not carry the next dense index.
+    QTypeOutOfOrder {
+        offset: u64,
+        index: u32,
+        expected: u32,
+    },
+    /// A field referenced a qtype that had not been declared.
+    UnknownQType {
+        offset: u64,
+        index: u32,
+    },
+    InvalidUtf8 {
+        offset: u64,
+    },
+    /// A pair record names a state not written before it, or has an
+    /// unknown kind.
+    InvalidPair {
+        offset: u64,
+    },
+    /// A string is longer than its `u16` length prefix allows.
+    StringTooLong {
+        len: usize,
+    },
+    /// A decoded state failed validation.
+    InvalidState {
+        offset: u64,
+        source: QuasiError,
+    },
+    /// The end record's count disagrees with the records read.
+    CountMismatch {
+        declared: u64,
+        found: u64,
+    },
+    /// Bytes follow the end record.
+    TrailingData {
+        offset: u64,
+    },
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "snapshot I/O error: {}", e),
+            SnapshotError::BadMagic { found } => {
+                write!(f, "not a QUASI snapshot (magic {:02x?})", found)
+            }
+            SnapshotError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "snapshot format version {} is not supported (the newest supported version is {})",
+                found, supported
+            ),
+            SnapshotError::Truncated { offset } => {
+                write!(f, "snapshot truncated at byte {}", offset)
+            }
+            SnapshotError::ChecksumMismatch {
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "checksum mismatch in record at byte {}: stored {:08x}, computed {:08x}",
+                offset, expected, found
+            ),
+            SnapshotError::UnknownTag { offset, tag } => {
+                write!(f, "unknown record tag {:#04x} at byte {}", tag, offset)
+            }
+            SnapshotError::QTypeOutOfOrder {
+                offset,
+                index,
+                expected,
+            } => write!(
+                f,
+                "qtype record at byte {} declares index {}, expected {}",
+                offset, index, expected
+            ),
+            SnapshotError::UnknownQType { offset, index } => write!(
+                f,
+                "state record at byte {} uses undeclared qtype {}",
+                offset, index
+            ),
+            SnapshotError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 in record at byte {}", offset)
+            }
+            SnapshotError::InvalidPair { offset } => {
+                write!(f, "invalid pair record at byte {}", offset)
+            }
+            SnapshotError::StringTooLong { len } => {
+                write!(f, "string of {} bytes exceeds the 65535-byte limit", len)
+            }
+            SnapshotError::InvalidState { offset, source } => {
+                write!(f, "invalid state in record at byte {}: {}", offset, source)
+            }
+            SnapshotError::CountMismatch { declared, found } => write!(
+                f,
+                "end record declares {} states but {} were read",
+                declared, found
+            ),
+            SnapshotError::TrailingData { offset } => {
+                write!(f, "unexpected data after the end record at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(e) => Some(e),
+            SnapshotError::InvalidState { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+/// CRC-32 (IEEE 802.3, reflected, polynomial `0xEDB88320`).
+pub fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xEDB8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Streams states into a snapshot.
+///
+/// The header is written on construction; [`finish`](SnapshotWriter::finish)
+/// writes the end record. Dropping the writer without finishing leaves a
+/// file that readers reject as truncated.
+pub struct SnapshotWriter<W: Write> {
+    inner: W,
+    qtypes: HashMap<String, u32>,
+    count: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    pub fn new(mut inner: W) -> Result<Self, SnapshotError> {
+        let mut header = Vec::with_capacity(16);
+        header.extend_from_slice(&MAGIC);
+        header.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        let crc = crc32(&header);
+        header.extend_from_slice(&crc.to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(Self {
+            inner,
+            qtypes: HashMap::new(),
+            count: 0,
+        })
+    }
+
+    /// Append one state, declaring its qtypes first if they are new.
+    pub fn write_state(&mut self, state: &QuasiState) -> Result<(), SnapshotError> {
+        let matter = self.intern(&state.field.matter.qtype)?;
+        let antimatter = self.intern(&state.field.antimatter.qtype)?;
+        let mut payload = Vec::with_capacity(3 + state.id.len() + FIELD_RECORD_LEN);
+        put_str(&mut payload, &state.id)?;
+        payload.push(state.observed as u8);
+        let f = &state.field;
+        payload.extend_from_slice(&matter.to_le_bytes());
+        payload.extend_from_slice(&antimatter.to_le_bytes());
+        for x in [
+            f.matter.value,
+            f.matter.phase,
+            f.antimatter.value,
+            f.antimatter.phase,
+            f.coherence,
+        ] {
+            payload.extend_from_slice(&x.to_le_bytes());
+        }
+        self.record(TAG_STATE, &payload)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Append an entangled pair. Both states must already be written.
+    pub fn write_pair(&mut self, a: &str, b: &str, kind: BellKind) -> Result<(), SnapshotError> {
+        let mut payload = Vec::with_capacity(5 + a.len() + b.len());
+        put_str(&mut payload, a)?;
+        put_str(&mut payload, b)?;
+        payload.push(match kind {
+            BellKind::Phi => 0,
+            BellKind::Psi => 1,
+        });
+        self.record(TAG_PAIR, &payload)
+    }
+
+    /// Write the end record and hand back the underlying writer.
+    pub fn finish(mut self) -> Result<W, SnapshotError> {
+        let count = self.count.to_le_bytes();
+        self.record(TAG_END, &count)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn intern(&mut self, qtype: &str) -> Result<u32, SnapshotError> {
+        if let Some(&index) = self.qtypes.get(qtype) {
+            return Ok(index);
+        }
+        let index = self.qtypes.len() as u32;
+        let mut payload = index.to_le_bytes().to_vec();
+        put_str(&mut payload, qtype)?;
+        self.record(TAG_QTYPE, &payload)?;
+        self.qtypes.insert(qtype.to_string(), index);
+        Ok(index)
+    }
+
+    fn record(&mut self, tag: u8, payload: &[u8]) -> Result<(), SnapshotError> {
+        let mut bytes = Vec::with_capacity(payload.len() + 5);
+        bytes.push(tag);
+        bytes.extend_from_slice(payload);
+        let crc = crc32(&bytes);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        self.inner.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) -> Result<(), SnapshotError> {
+    let len = u16::try_from(s.len()).map_err(|_| SnapshotError::StringTooLong { len: s.len() })?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+/// Streams states out of a snapshot, validating as it goes.
+///
+/// Iteration yields each state in file order and ends after the end
+/// record; the first error ends iteration. Pair records are collected
+/// along the way into [`pairs`](SnapshotReader::pairs).
+pub struct SnapshotReader<R: Read> {
+    inner: R,
+    offset: u64,
+    version: u16,
+    qtypes: Vec<String>,
+    ids: HashSet<String>,
+    pairs: Vec<(String, String, BellKind)>,
+    count: u64,
+    done: bool,
+}
+
+impl<R: Read> SnapshotReader<R> {
+    /// Read and check the header.
+    pub fn new(mut inner: R) -> Result<Self, SnapshotError> {
+        let mut header = [0u8; 16];
+        read_exact_at(&mut inner, &mut header, 0)?;
+        let mut magic = [0u8; 8];
+        magic.copy_from_slice(&header[..8]);
+        if magic != MAGIC {
+            return Err(SnapshotError::BadMagic { found: magic });
+        }
+        let stored = u32::from_le_bytes(header[12..16].try_into().unwrap());
+        let computed = crc32(&header[..12]);
+        if stored != computed {
+            return Err(SnapshotError::ChecksumMismatch {
+                offset: 0,
+                expected: stored,
+                found: computed,
+            });
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version == 0 || version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: version,
+                supported: SNAPSHOT_VERSION,
+            });
+        }
+        Ok(Self {
+            inner,
+            offset: 16,
+            version,
+            qtypes: Vec::new(),
+            ids: HashSet::new(),
+            pairs: Vec::new(),
+            count: 0,
+            done: false,
+        })
+    }
+
+    /// Qtypes declared so far, by index.
+    pub fn qtypes(&self) -> &[String] {
+        &self.qtypes
+    }
+
+    /// Pairs read so far, as `(a, b, kind)`.
+    pub fn pairs(&self) -> &[(String, String, BellKind)] {
+        &self.pairs
+    }
+
+    fn next_state(&mut self) -> Result<Option<QuasiState>, SnapshotError> {
+        loop {
+            let start = self.offset;
+            let mut record = Vec::new();
+            let tag = self.take(&mut record, 1)?[0];
+            match tag {
+                TAG_QTYPE => {
+                    let index = u32::from_le_bytes(self.take(&mut record, 4)?.try_into().unwrap());
+                    let name = self.take_str(&mut record, start)?;
+                    self.check_crc(&record, start)?;
+                    let expected = self.qtypes.len() as u32;
+                    if index != expected {
+                        return Err(SnapshotError::QTypeOutOfOrder {
+                            offset: start,
+                            index,
+                            expected,
+                        });
+                    }
+                    self.qtypes.push(name);
+                }
+                TAG_STATE => {
+                    let id = self.take_str(&mut record, start)?;
+                    let observed = self.take(&mut record, 1)?[0] & 1 == 1;
+                    let field = self.take(&mut record, FIELD_RECORD_LEN)?.to_vec();
+                    self.check_crc(&record, start)?;
+                    let state = self.decode(id, observed, &field, start)?;
+                    self.ids.insert(state.id.clone());
+                    self.count += 1;
+                    return Ok(Some(state));
+                }
+                TAG_PAIR if self.version >= 2 => {
+                    let a = self.take_str(&mut record, start)?;
+                    let b = self.take_str(&mut record, start)?;
+                    let kind = self.take(&mut record, 1)?[0];
+                    self.check_crc(&record, start)?;
+                    let kind = match kind {
+                        0 => BellKind::Phi,
+                        1 => BellKind::Psi,
+                        _ => return Err(SnapshotError::InvalidPair { offset: start }),
+                    };
+                    if a == b || !self.ids.contains(&a) || !self.ids.contains(&b) {
+                        return Err(SnapshotError::InvalidPair { offset: start });
+                    }
+                    self.pairs.push((a, b, kind));
+                }
+                TAG_END => {
+                    let declared =
+                        u64::from_le_bytes(self.take(&mut record, 8)?.try_into().unwrap());
+                    self.check_crc(&record, start)?;
+                    if declared != self.count {
+                        return Err(SnapshotError::CountMismatch {
+                            declared,
+                            found: self.count,
+                        });
+                    }
+                    let mut probe = [0u8; 1];
+                    if self.inner.read(&mut probe)? != 0 {
+                        return Err(SnapshotError::TrailingData {
+                            offset: self.offset,
+                        });
+                    }
+                    return Ok(None);
+                }
+                tag => {
+                    return Err(SnapshotError::UnknownTag { offset: start, tag });
+                }
+            }
+        }
+    }
+
+    fn decode(
+        &self,
+        id: String,
+        observed: bool,
+        field: &[u8],
+        offset: u64,
+    ) -> Result<QuasiState, SnapshotError> {
+        let index = |at: usize| u32::from_le_bytes(field[at..at + 4].try_into().unwrap());
+        let float = |k: usize| f64::from_le_bytes(field[8 + 8 * k..16 + 8 * k].try_into().unwrap());
+        let qtype = |index: u32| {
+            self.qtypes
+                .get(index as usize)
+                .cloned()
+                .ok_or(SnapshotError::UnknownQType { offset, index })
+        };
+        let state = QuasiState {
+            id,
+            field: QuasiField {
+                matter: QToken {
+                    qtype: qtype(index(0))?,
+                    value: float(0),
+                    phase: float(1),
+                },
+                antimatter: QToken {
+                    qtype: qtype(index(4))?,
+                    value: float(2),
+                    phase: float(3),
+                },
+                coherence: float(4),
+            },
+            observed,
+        };
+        state
+            .validate()
+            .map_err(|source| SnapshotError::InvalidState { offset, source })?;
+        Ok(state)
+    }
+
+    /// Read `n` more bytes of the current record into `record`.
+    fn take<'a>(&mut self, record: &'a mut Vec<u8>, n: usize) -> Result<&'a [u8], SnapshotError> {
+        let start = record.len();
+        record.resize(start + n, 0);
+        read_exact_at(&mut self.inner, &mut record[start..], self.offset)?;
+        self.offset += n as u64;
+        Ok(&record[start..])
+    }
+
+    fn take_str(&mut self, record: &mut Vec<u8>, start: u64) -> Result<String, SnapshotError> {
+        let len = u16::from_le_bytes(self.take(record, 2)?.try_into().unwrap()) as usize;
+        let bytes = self.take(record, len)?.to_vec();
+        String::from_utf8(bytes).map_err(|_| SnapshotError::InvalidUtf8 { offset: start })
+    }
+
+    fn check_crc(&mut self, record: &[u8], start: u64) -> Result<(), SnapshotError> {
+        let mut stored = [0u8; 4];
+        read_exact_at(&mut self.inner, &mut stored, self.offset)?;
+        self.offset += 4;
+        let expected = u32::from_le_bytes(stored);
+        let found = crc32(record);
+        if expected != found {
+            return Err(SnapshotError::ChecksumMismatch {
+                offset: start,
+                expected,
+                found,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for SnapshotReader<R> {
+    type Item = Result<QuasiState, SnapshotError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_state() {
+            Ok(Some(state)) => Some(Ok(state)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// `read_exact`, reporting a short read as truncation at `offset`.
+fn read_exact_at<R: Read>(r: &mut R, buf: &mut [u8], offset: u64) -> Result<(), SnapshotError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match r.read(&mut buf[filled..]) {
+            Ok(0) => {
+                return Err(SnapshotError::Truncated {
+                    offset: offset + filled as u64,
+                })
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// Write `states` as a complete snapshot.
+pub fn write_snapshot<'a, W, I>(writer: W, states: I) -> Result<W, SnapshotError>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a QuasiState>,
+{
+    let mut writer = SnapshotWriter::new(writer)?;
+    for state in states {
+        writer.write_state(state)?;
+    }
+    writer.finish()
+}
+
+/// Read every state of a snapshot.
+pub fn read_snapshot<R: Read>(reader: R) -> Result<Vec<QuasiState>, SnapshotError> {
+    SnapshotReader::new(reader)?.collect()
+}
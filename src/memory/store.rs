@@ -0,0 +1,495 @@
+//! Crash-safe, on-disk [`QuasiMemory`].
+//!
+//! A [`MemoryStore`] owns a directory holding one generation of files:
+//!
+//! * `snapshot-<g>.snp` — every live state and entangled pair when
+//!   generation `g` began, in the [`snapshot`] format (absent for
+//!   generation 0);
+//! * `wal-<g>.log` — every mutation since, in the [`wal`]
+//!   format.
+//!
+//! Each mutation is appended to the log before it is applied. Compaction
+//! starts generation `g + 1`: its log is created first, then its snapshot
+//! is written and renamed into place, and only then are generation `g`'s
+//! files removed. Recovery takes the newest generation with a snapshot
+//! (or generation 0), replays its log up to the last intact record,
+//! truncates any torn tail and deletes leftovers from other generations.
+
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::QuasiError;
+use crate::gate::{Gate, UnitaryGate};
+use crate::memory::qmemory::{MemoryError, QuasiMemory};
+use crate::memory::qtoken::BellKind;
+use crate::memory::snapshot::{self, SnapshotError};
+use crate::memory::wal::{self, WalError, WalEvent, WalWriter};
+use crate::quasi_core::{Observation, QuasiState};
+use crate::rng::{QuasiRng, SplitMix64};
+
+/// Why a store operation failed.
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Snapshot(SnapshotError),
+    Wal(WalError),
+    /// The operation was refused by the in-memory store; nothing was logged.
+    Memory(MemoryError),
+    /// A state failed validation; nothing was logged.
+    Quasi(QuasiError),
+    /// A generation with a snapshot has no log.
+    MissingLog {
+        generation: u64,
+    },
+    /// A logged event does not apply to the recovered state.
+    Replay {
+        generation: u64,
+        source: MemoryError,
+    },
+}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "store I/O error: {}", e),
+            StoreError::Snapshot(e) => write!(f, "{}", e),
+            StoreError::Wal(e) => write!(f, "{}", e),
+            StoreError::Memory(e) => write!(f, "{}", e),
+            StoreError::Quasi(e) => write!(f, "{}", e),
+            StoreError::MissingLog { generation } => {
+                write!(f, "generation {} has a snapshot but no log", generation)
+            }
+            StoreError::Replay { generation, source } => write!(
+                f,
+                "cannot replay the log of generation {}: {}",
+                generation, source
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Io(e) => Some(e),
+            StoreError::Snapshot(e) => Some(e),
+            StoreError::Wal(e) => Some(e),
+            StoreError::Memory(e) => Some(e),
+            StoreError::Quasi(e) => Some(e),
+            StoreError::Replay { source, .. } => Some(source),
+            StoreError::MissingLog { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+impl From<SnapshotError> for StoreError {
+    fn from(e: SnapshotError) -> Self {
+        StoreError::Snapshot(e)
+    }
+}
+
+impl From<WalError> for StoreError {
+    fn from(e: WalError) -> Self {
+        StoreError::Wal(e)
+    }
+}
+
+impl From<QuasiError> for StoreError {
+    fn from(e: QuasiError) -> Self {
+        StoreError::Quasi(e)
+    }
+}
+
+impl From<MemoryError> for StoreError {
+    fn from(e: MemoryError) -> Self {
+        StoreError::Memory(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, StoreError>;
+
+/// What [`MemoryStore::open`] found on disk.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recovery {
+    pub generation: u64,
+    /// States loaded from the snapshot.
+    pub restored: usize,
+    /// Log events replayed on top of it.
+    pub replayed: usize,
+    /// Bytes of torn or corrupt log tail that were dropped.
+    pub discarded: u64,
+}
+
+/// A [`QuasiMemory`] persisted through a snapshot plus write-ahead log.
+pub struct MemoryStore {
+    dir: PathBuf,
+    generation: u64,
+    memory: QuasiMemory,
+    wal: WalWriter<File>,
+    compact_every: Option<u64>,
+    recovery: Recovery,
+}
+
+impl MemoryStore {
+    /// Open the store in `dir`, creating the directory if needed and
+    /// recovering whatever a previous process left behind.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let generation = latest_snapshot(&dir)?.unwrap_or(0);
+        let mut memory = QuasiMemory::new();
+        let mut recovery = Recovery {
+            generation,
+            ..Recovery::default()
+        };
+
+        let log = wal_path(&dir, generation);
+        let wal = if log.exists() {
+            let bytes = fs::read(&log)?;
+            let contents = wal::read_wal(&bytes)?;
+            memory = QuasiMemory::starting_at(contents.frame);
+            if generation > 0 {
+                let file = File::open(snapshot_path(&dir, generation))?;
+                let mut reader = snapshot::SnapshotReader::new(io::BufReader::new(file))?;
+                for state in reader.by_ref() {
+                    memory.allocate(state?)?;
+                    recovery.restored += 1;
+                }
+                for (a, b, kind) in reader.pairs() {
+                    memory.restore_pair(a, b, *kind)?;
+                }
+            }
+            for event in &contents.events {
+                replay(&mut memory, event)
+                    .map_err(|source| StoreError::Replay { generation, source })?;
+            }
+            recovery.replayed = contents.events.len();
+            recovery.discarded = contents.discarded;
+            if contents.discarded > 0 {
+                let file = OpenOptions::new().write(true).open(&log)?;
+                file.set_len(contents.valid_len)?;
+                file.sync_data()?;
+            }
+            WalWriter::resume(
+                OpenOptions::new().append(true).open(&log)?,
+                contents.events.len() as u64,
+            )
+        } else if generation == 0 {
+            create_log(&dir, 0, 0)?
+        } else {
+            return Err(StoreError::MissingLog { generation });
+        };
+        remove_other_generations(&dir, generation)?;
+
+        Ok(Self {
+            dir,
+            generation,
+            memory,
+            wal,
+            compact_every: None,
+            recovery,
+        })
+    }
+
+    /// Compact automatically once the log holds `records` events.
+    pub fn compact_every(mut self, records: u64) -> Self {
+        self.compact_every = Some(records.max(1));
+        self
+    }
+
+    /// What was recovered when the store was opened.
+    pub fn recovery(&self) -> &Recovery {
+        &self.recovery
+    }
+
+    /// The current generation; compaction increments it.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Read access to the recovered, up-to-date memory.
+    pub fn memory(&self) -> &QuasiMemory {
+        &self.memory
+    }
+
+    /// Store a new, valid state.
+    pub fn allocate(&mut self, state: QuasiState) -> Result<()> {
+        state.validate()?;
+        if self.memory.contains(&state.id) {
+            return Err(MemoryError::Occupied { id: state.id }.into());
+        }
+        self.log(&WalEvent::Allocate(state.clone()))?;
+        self.memory.allocate(state)?;
+        self.maybe_compact()
+    }
+
+    /// Replace a live state with a valid one.
+    pub fn update(&mut self, state: QuasiState) -> Result<()> {
+        state.validate()?;
+        self.require(&state.id)?;
+        self.log(&WalEvent::Update(state.clone()))?;
+        self.memory.update(state)?;
+        self.maybe_compact()
+    }
+
+    pub fn free(&mut self, id: &str) -> Result<QuasiState> {
+        self.require(id)?;
+        self.log(&WalEvent::Free { id: id.to_string() })?;
+        let state = self.memory.free(id)?;
+        self.maybe_compact()?;
+        Ok(state)
+    }
+
+    /// Collapse a stored state with an entropy-seeded generator.
+    pub fn observe(&mut self, id: &str) -> Result<Observation> {
+        self.observe_with(id, &mut SplitMix64::from_entropy())
+    }
+
+    /// Collapse a stored state, drawing from `rng`. The outcome is logged
+    /// before the state changes.
+    pub fn observe_with<R: QuasiRng + ?Sized>(
+        &mut self,
+        id: &str,
+        rng: &mut R,
+    ) -> Result<Observation> {
+        let branch = self.require(id)?.sample_branch(rng);
+        self.log(&WalEvent::Observe {
+            id: id.to_string(),
+            branch,
+        })?;
+        let observation = self.memory.collapse(id, branch)?;
+        self.maybe_compact()?;
+        Ok(observation)
+    }
+
+    pub fn invert(&mut self, id: &str) -> Result<()> {
+        self.require(id)?;
+        self.log(&WalEvent::Invert { id: id.to_string() })?;
+        self.memory.invert(id)?;
+        self.maybe_compact()
+    }
+
+    /// Apply a one-state gate; the log records its matrix, so any
+    /// single-state gate replays. A gate whose matrix is not unitary is
+    /// refused with [`WalError::NonUnitaryGate`] before anything is
+    /// logged, as is one the memory itself would refuse.
+    ///
+    /// # Panics
+    /// If the gate does not act on exactly one state.
+    pub fn apply_gate(&mut self, id: &str, gate: &dyn Gate) -> Result<()> {
+        assert_eq!(
+            gate.arity(),
+            1,
+            "gate `{}` is not single-state",
+            gate.name()
+        );
+        self.memory.check_gate(id, gate)?;
+        self.log(&WalEvent::Gate {
+            id: id.to_string(),
+            name: gate.name().to_string(),
+            matrix: gate.matrix(),
+        })?;
+        self.memory.apply_gate(id, gate)?;
+        self.maybe_compact()
+    }
+
+    /// Entangle two stored states into a Bell-type pair; see
+    /// [`QuasiMemory::entangle`].
+    pub fn entangle(&mut self, a: &str, b: &str, kind: BellKind) -> Result<()> {
+        self.memory.check_entangle(a, b)?;
+        self.log(&WalEvent::Entangle {
+            a: a.to_string(),
+            b: b.to_string(),
+            kind,
+        })?;
+        self.memory.entangle(a, b, kind)?;
+        self.maybe_compact()
+    }
+
+    /// Dissolve every pair involving a stored state.
+    pub fn disentangle(&mut self, id: &str) -> Result<()> {
+        self.require(id)?;
+        self.log(&WalEvent::Disentangle { id: id.to_string() })?;
+        self.memory.disentangle(id);
+        self.maybe_compact()
+    }
+
+    /// Seal the open frame. Returns the sealed frame's number.
+    pub fn commit_frame(&mut self) -> Result<u64> {
+        self.log(&WalEvent::Commit)?;
+        let frame = self.memory.commit_frame();
+        self.maybe_compact()?;
+        Ok(frame)
+    }
+
+    /// Flush the log to stable storage.
+    pub fn sync(&mut self) -> Result<()> {
+        self.wal.sync()?;
+        Ok(())
+    }
+
+    /// Fold the log into a fresh snapshot and start a new generation.
+    ///
+    /// Frames before the open one stop being readable.
+    pub fn compact(&mut self) -> Result<()> {
+        let next = self.generation + 1;
+        let wal = create_log(&self.dir, next, self.memory.frame())?;
+
+        let path = snapshot_path(&self.dir, next);
+        let tmp = tmp_path(&path);
+        let file = File::create(&tmp)?;
+        let mut writer = snapshot::SnapshotWriter::new(BufWriter::new(file))?;
+        for state in self.memory.iter() {
+            writer.write_state(state)?;
+        }
+        for (a, b, kind) in self.memory.entanglement().pairs() {
+            writer.write_pair(a, b, kind)?;
+        }
+        writer
+            .finish()?
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&tmp, &path)?;
+        sync_dir(&self.dir)?;
+
+        self.wal = wal;
+        self.generation = next;
+        remove_other_generations(&self.dir, next)?;
+        self.memory.retain_from(self.memory.frame());
+        Ok(())
+    }
+
+    fn require(&self, id: &str) -> Result<&QuasiState> {
+        self.memory
+            .get(id)
+            .ok_or_else(|| MemoryError::Unallocated { id: id.to_string() }.into())
+    }
+
+    fn log(&mut self, event: &WalEvent) -> Result<()> {
+        self.wal.append(event)?;
+        Ok(())
+    }
+
+    fn maybe_compact(&mut self) -> Result<()> {
+        match self.compact_every {
+            Some(limit) if self.wal.records() >= limit => self.compact(),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Apply one logged event to `memory`.
+fn replay(memory: &mut QuasiMemory, event: &WalEvent) -> std::result::Result<(), MemoryError> {
+    match event {
+        WalEvent::Allocate(state) => memory.allocate(state.clone()),
+        WalEvent::Update(state) => memory.update(state.clone()),
+        WalEvent::Free { id } => memory.free(id).map(drop),
+        WalEvent::Observe { id, branch } => memory.collapse(id, *branch).map(drop),
+        WalEvent::Invert { id } => memory.invert(id),
+        WalEvent::Gate { id, name, matrix } => {
+            memory.apply_gate(id, &UnitaryGate::new(name, matrix.clone()))
+        }
+        WalEvent::Commit => {
+            memory.commit_frame();
+            Ok(())
+        }
+        WalEvent::Entangle { a, b, kind } => memory.entangle(a, b, *kind),
+        WalEvent::Disentangle { id } => {
+            memory.disentangle(id);
+            Ok(())
+        }
+    }
+}
+
+fn snapshot_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("snapshot-{}.snp", generation))
+}
+
+fn wal_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("wal-{}.log", generation))
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+/// Parse `snapshot-<g>.snp` / `wal-<g>.log` into `g`.
+fn generation_of(name: &str) -> Option<u64> {
+    let rest = name
+        .strip_prefix("snapshot-")
+        .and_then(|r| r.strip_suffix(".snp"))
+        .or_else(|| {
+            name.strip_prefix("wal-")
+                .and_then(|r| r.strip_suffix(".log"))
+        })?;
+    rest.parse().ok()
+}
+
+/// The newest generation with a complete (renamed) snapshot.
+fn latest_snapshot(dir: &Path) -> Result<Option<u64>> {
+    let mut latest = None;
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let Some(name) = name.to_str() else { continue };
+        if name.starts_with("snapshot-") {
+            if let Some(g) = generation_of(name) {
+                latest = latest.max(Some(g));
+            }
+        }
+    }
+    Ok(latest)
+}
+
+/// Delete snapshots, logs and temporaries of every other generation.
+fn remove_other_generations(dir: &Path, keep: u64) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else { continue };
+        let stale = match name.strip_suffix(".tmp") {
+            Some(base) => generation_of(base).is_some(),
+            None => generation_of(name).is_some_and(|g| g != keep),
+        };
+        if stale {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    sync_dir(dir)
+}
+
+/// Write a fresh log through a temporary file so its header is never torn.
+fn create_log(dir: &Path, generation: u64, frame: u64) -> Result<WalWriter<File>> {
+    let path = wal_path(dir, generation);
+    let tmp = tmp_path(&path);
+    let mut file = File::create(&tmp)?;
+    file.write_all(&wal::header(frame))?;
+    file.sync_all()?;
+    fs::rename(&tmp, &path)?;
+    sync_dir(dir)?;
+    let file = OpenOptions::new().append(true).open(&path)?;
+    Ok(WalWriter::resume(file, 0))
+}
+
+/// Make renames in `dir` durable where the platform allows it.
+fn sync_dir(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
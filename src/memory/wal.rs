@@ -0,0 +1,539 @@
+//! Write-ahead log of quasi-memory events.
+//!
+//! # Format (version 2)
+//!
+//! All integers and floats are little-endian.
+//!
+//! ```text
+//! header   magic    8 bytes  "QUASIWAL"
+//!          version  u16      2
+//!          flags    u16      0 (reserved)
+//!          frame    u64      open frame when the log was started
+//!          crc      u32      CRC-32 of the 20 bytes above
+//! record   len      u32      length of tag and payload
+//!          tag      u8
+//!          payload  …        depends on tag
+//!          crc      u32      CRC-32 of len, tag and payload
+//! ```
+//!
+//! | tag    | event    | payload |
+//! |--------|----------|---------|
+//! | `0x01` | allocate | state |
+//! | `0x02` | update   | state |
+//! | `0x03` | free     | id |
+//! | `0x04` | observe  | id, `branch u8` (0 matter, 1 antimatter) |
+//! | `0x05` | invert   | id |
+//! | `0x06` | gate     | id, gate name, 2×2 matrix as eight `f64` (row-major, re then im) |
+//! | `0x07` | commit   | — seals the open frame |
+//! | `0x08` | entangle | id `a`, id `b`, `kind u8` (0 Φ, 1 Ψ) |
+//! | `0x09` | disentangle | id |
+//!
+//! Strings are a `u16` byte length and UTF-8. A state is its id, `flags
+//! u8` (bit 0: observed), matter qtype, antimatter qtype, then matter
+//! value, matter phase, antimatter value, antimatter phase and coherence
+//! as `f64`.
+//!
+//! Version 1 logs still read; they predate the entanglement records, so
+//! a `0x08` or `0x09` tag in one is an unknown tag.
+//!
+//! Observations log their outcome rather than a seed, so replay is
+//! deterministic. A crash can leave a partly written record at the end of
+//! the log; [`read_wal`] stops at the first record that is short or fails
+//! its checksum and reports where the valid prefix ends.
+
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
+
+use crate::complex::Complex;
+use crate::error::QuasiError;
+use crate::matrix::Matrix;
+use crate::memory::qtoken::{BellKind, QToken};
+use crate::memory::snapshot::crc32;
+use crate::quasi_core::{Branch, QuasiState};
+use crate::topology::field::QuasiField;
+
+/// File magic, the first eight bytes of every log.
+pub const MAGIC: [u8; 8] = *b"QUASIWAL";
+
+/// Format version written by this build and the newest one it reads.
+pub const WAL_VERSION: u16 = 2;
+
+/// Length of the header in bytes.
+pub const HEADER_LEN: usize = 24;
+
+const TAG_ALLOCATE: u8 = 0x01;
+const TAG_UPDATE: u8 = 0x02;
+const TAG_FREE: u8 = 0x03;
+const TAG_OBSERVE: u8 = 0x04;
+const TAG_INVERT: u8 = 0x05;
+const TAG_GATE: u8 = 0x06;
+const TAG_COMMIT: u8 = 0x07;
+const TAG_ENTANGLE: u8 = 0x08;
+const TAG_DISENTANGLE: u8 = 0x09;
+
+/// Tolerance of the unitarity check on gate matrices, both when encoding
+/// and when reading.
+const UNITARY_TOL: f64 = 1e-9;
+
+/// One logged mutation of a [`QuasiMemory`](super::qmemory::QuasiMemory).
+#[derive(Clone, Debug)]
+pub enum WalEvent {
+    Allocate(QuasiState),
+    Update(QuasiState),
+    Free {
+        id: String,
+    },
+    Observe {
+        id: String,
+        branch: Branch,
+    },
+    Invert {
+        id: String,
+    },
+    Gate {
+        id: String,
+        name: String,
+        matrix: Matrix,
+    },
+    Commit,
+    Entangle {
+        a: String,
+        b: String,
+        kind: BellKind,
+    },
+    Disentangle {
+        id: String,
+    },
+}
+
+/// Why a log could not be written or read. Offsets are byte positions
+/// from the start of the log.
+#[derive(Debug)]
+pub enum WalError {
+    Io(io::Error),
+    BadMagic {
+        found: [u8; 8],
+    },
+    UnsupportedVersion {
+        found: u16,
+        supported: u16,
+    },
+    /// The header is short or fails its checksum.
+    BadHeader,
+    /// A string is longer than its `u16` length prefix allows.
+    StringTooLong {
+        len: usize,
+    },
+    /// A gate event does not carry a 2×2 matrix.
+    UnsupportedGate {
+        name: String,
+    },
+    /// A gate event's matrix is not unitary, so replay would reject it.
+    NonUnitaryGate {
+        name: String,
+    },
+    /// A record passed its checksum but does not decode.
+    InvalidRecord {
+        offset: u64,
+        reason: String,
+    },
+    /// A decoded state failed validation.
+    InvalidState {
+        offset: u64,
+        source: QuasiError,
+    },
+}
+
+impl Display for WalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalError::Io(e) => write!(f, "log I/O error: {}", e),
+            WalError::BadMagic { found } => {
+                write!(f, "not a QUASI write-ahead log (magic {:02x?})", found)
+            }
+            WalError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "log format version {} is not supported (the newest supported version is {})",
+                found, supported
+            ),
+            WalError::BadHeader => write!(f, "log header is truncated or corrupt"),
+            WalError::StringTooLong { len } => {
+                write!(f, "string of {} bytes exceeds the 65535-byte limit", len)
+            }
+            WalError::UnsupportedGate { name } => {
+                write!(f, "gate `{}` is not a single-state gate", name)
+            }
+            WalError::NonUnitaryGate { name } => {
+                write!(f, "gate `{}` does not have a unitary matrix", name)
+            }
+            WalError::InvalidRecord { offset, reason } => {
+                write!(f, "invalid log record at byte {}: {}", offset, reason)
+            }
+            WalError::InvalidState { offset, source } => {
+                write!(
+                    f,
+                    "invalid state in log record at byte {}: {}",
+                    offset, source
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for WalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WalError::Io(e) => Some(e),
+            WalError::InvalidState { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WalError {
+    fn from(e: io::Error) -> Self {
+        WalError::Io(e)
+    }
+}
+
+/// Encode a log header for a log that starts at `frame`.
+pub fn header(frame: u64) -> [u8; HEADER_LEN] {
+    let mut out = [0u8; HEADER_LEN];
+    out[..8].copy_from_slice(&MAGIC);
+    out[8..10].copy_from_slice(&WAL_VERSION.to_le_bytes());
+    out[12..20].copy_from_slice(&frame.to_le_bytes());
+    let crc = crc32(&out[..20]);
+    out[20..].copy_from_slice(&crc.to_le_bytes());
+    out
+}
+
+/// Encode one event as a complete record.
+pub fn encode(event: &WalEvent) -> Result<Vec<u8>, WalError> {
+    let mut body = Vec::new();
+    match event {
+        WalEvent::Allocate(state) => {
+            body.push(TAG_ALLOCATE);
+            put_state(&mut body, state)?;
+        }
+        WalEvent::Update(state) => {
+            body.push(TAG_UPDATE);
+            put_state(&mut body, state)?;
+        }
+        WalEvent::Free { id } => {
+            body.push(TAG_FREE);
+            put_str(&mut body, id)?;
+        }
+        WalEvent::Observe { id, branch } => {
+            body.push(TAG_OBSERVE);
+            put_str(&mut body, id)?;
+            body.push(match branch {
+                Branch::Matter => 0,
+                Branch::Antimatter => 1,
+            });
+        }
+        WalEvent::Invert { id } => {
+            body.push(TAG_INVERT);
+            put_str(&mut body, id)?;
+        }
+        WalEvent::Gate { id, name, matrix } => {
+            if matrix.dim() != 2 {
+                return Err(WalError::UnsupportedGate { name: name.clone() });
+            }
+            if !matrix.is_unitary(UNITARY_TOL) {
+                return Err(WalError::NonUnitaryGate { name: name.clone() });
+            }
+            body.push(TAG_GATE);
+            put_str(&mut body, id)?;
+            put_str(&mut body, name)?;
+            for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                let z = matrix[(i, j)];
+                body.extend_from_slice(&z.re.to_le_bytes());
+                body.extend_from_slice(&z.im.to_le_bytes());
+            }
+        }
+        WalEvent::Commit => body.push(TAG_COMMIT),
+        WalEvent::Entangle { a, b, kind } => {
+            body.push(TAG_ENTANGLE);
+            put_str(&mut body, a)?;
+            put_str(&mut body, b)?;
+            body.push(match kind {
+                BellKind::Phi => 0,
+                BellKind::Psi => 1,
+            });
+        }
+        WalEvent::Disentangle { id } => {
+            body.push(TAG_DISENTANGLE);
+            put_str(&mut body, id)?;
+        }
+    }
+    let mut record = Vec::with_capacity(body.len() + 8);
+    record.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    record.extend_from_slice(&body);
+    let crc = crc32(&record);
+    record.extend_from_slice(&crc.to_le_bytes());
+    Ok(record)
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) -> Result<(), WalError> {
+    let len = u16::try_from(s.len()).map_err(|_| WalError::StringTooLong { len: s.len() })?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn put_state(out: &mut Vec<u8>, state: &QuasiState) -> Result<(), WalError> {
+    put_str(out, &state.id)?;
+    out.push(state.observed as u8);
+    let f = &state.field;
+    put_str(out, &f.matter.qtype)?;
+    put_str(out, &f.antimatter.qtype)?;
+    for x in [
+        f.matter.value,
+        f.matter.phase,
+        f.antimatter.value,
+        f.antimatter.phase,
+        f.coherence,
+    ] {
+        out.extend_from_slice(&x.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Appends records to a log.
+///
+/// Every append is flushed to the operating system, so a process crash
+/// loses nothing that was acknowledged; call [`sync`](WalWriter::sync) to
+/// survive power loss as well.
+pub struct WalWriter<W: Write> {
+    inner: W,
+    records: u64,
+}
+
+impl<W: Write> WalWriter<W> {
+    /// Start a new log at `frame`, writing its header.
+    pub fn create(mut inner: W, frame: u64) -> Result<Self, WalError> {
+        inner.write_all(&header(frame))?;
+        inner.flush()?;
+        Ok(Self { inner, records: 0 })
+    }
+
+    /// Continue a log whose header and `records` valid records are
+    /// already in `inner`, positioned at its end.
+    pub fn resume(inner: W, records: u64) -> Self {
+        Self { inner, records }
+    }
+
+    pub fn append(&mut self, event: &WalEvent) -> Result<(), WalError> {
+        let record = encode(event)?;
+        self.inner.write_all(&record)?;
+        self.inner.flush()?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Records in the log.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
+
+impl WalWriter<std::fs::File> {
+    /// Flush the log to stable storage.
+    pub fn sync(&mut self) -> Result<(), WalError> {
+        self.inner.sync_data()?;
+        Ok(())
+    }
+}
+
+/// The readable part of a log.
+#[derive(Clone, Debug)]
+pub struct WalContents {
+    /// Open frame when the log was started.
+    pub frame: u64,
+    pub events: Vec<WalEvent>,
+    /// Length in bytes of the header and every intact record.
+    pub valid_len: u64,
+    /// Bytes after `valid_len` — a torn or corrupt tail.
+    pub discarded: u64,
+}
+
+/// Decode a whole log, stopping at the first short or corrupt record.
+///
+/// A bad header is an error; a bad record only ends the log. Records
+/// that pass their checksum but do not decode are errors, since no crash
+/// produces them.
+pub fn read_wal(bytes: &[u8]) -> Result<WalContents, WalError> {
+    if bytes.len() >= 8 && bytes[..8] != MAGIC {
+        let mut found = [0u8; 8];
+        found.copy_from_slice(&bytes[..8]);
+        return Err(WalError::BadMagic { found });
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(WalError::BadHeader);
+    }
+    let stored = u32::from_le_bytes(bytes[20..24].try_into().unwrap());
+    if stored != crc32(&bytes[..20]) {
+        return Err(WalError::BadHeader);
+    }
+    let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+    if version == 0 || version > WAL_VERSION {
+        return Err(WalError::UnsupportedVersion {
+            found: version,
+            supported: WAL_VERSION,
+        });
+    }
+    let frame = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+
+    let mut events = Vec::new();
+    let mut pos = HEADER_LEN;
+    while let Some(len) = bytes
+        .get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+    {
+        let end = pos + 4 + len;
+        let Some(crc) = bytes
+            .get(end..end + 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        else {
+            break;
+        };
+        if len == 0 || crc != crc32(&bytes[pos..end]) {
+            break;
+        }
+        let mut body = Body {
+            bytes: &bytes[pos + 4..end],
+            offset: pos as u64,
+            version,
+        };
+        events.push(body.event()?);
+        if !body.bytes.is_empty() {
+            return Err(body.invalid("trailing bytes in record"));
+        }
+        pos = end + 4;
+    }
+    Ok(WalContents {
+        frame,
+        events,
+        valid_len: pos as u64,
+        discarded: (bytes.len() - pos) as u64,
+    })
+}
+
+/// Cursor over one checksummed record body.
+struct Body<'a> {
+    bytes: &'a [u8],
+    offset: u64,
+    version: u16,
+}
+
+impl Body<'_> {
+    fn invalid(&self, reason: &str) -> WalError {
+        WalError::InvalidRecord {
+            offset: self.offset,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], WalError> {
+        if self.bytes.len() < n {
+            return Err(self.invalid("record ends early"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, WalError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn float(&mut self) -> Result<f64, WalError> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, WalError> {
+        let len = u16::from_le_bytes(self.take(2)?.try_into().unwrap()) as usize;
+        let bytes = self.take(len)?.to_vec();
+        String::from_utf8(bytes).map_err(|_| self.invalid("invalid UTF-8"))
+    }
+
+    fn state(&mut self) -> Result<QuasiState, WalError> {
+        let id = self.string()?;
+        let observed = self.byte()? & 1 == 1;
+        let matter_qtype = self.string()?;
+        let antimatter_qtype = self.string()?;
+        let state = QuasiState {
+            id,
+            field: QuasiField {
+                matter: QToken {
+                    qtype: matter_qtype,
+                    value: self.float()?,
+                    phase: self.float()?,
+                },
+                antimatter: QToken {
+                    qtype: antimatter_qtype,
+                    value: self.float()?,
+                    phase: self.float()?,
+                },
+                coherence: self.float()?,
+            },
+            observed,
+        };
+        state.validate().map_err(|source| WalError::InvalidState {
+            offset: self.offset,
+            source,
+        })?;
+        Ok(state)
+    }
+
+    fn event(&mut self) -> Result<WalEvent, WalError> {
+        Ok(match self.byte()? {
+            TAG_ALLOCATE => WalEvent::Allocate(self.state()?),
+            TAG_UPDATE => WalEvent::Update(self.state()?),
+            TAG_FREE => WalEvent::Free { id: self.string()? },
+            TAG_OBSERVE => {
+                let id = self.string()?;
+                let branch = match self.byte()? {
+                    0 => Branch::Matter,
+                    1 => Branch::Antimatter,
+                    _ => return Err(self.invalid("unknown branch")),
+                };
+                WalEvent::Observe { id, branch }
+            }
+            TAG_INVERT => WalEvent::Invert { id: self.string()? },
+            TAG_GATE => {
+                let id = self.string()?;
+                let name = self.string()?;
+                let mut matrix = Matrix::zeros(2);
+                for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                    matrix[(i, j)] = Complex::new(self.float()?, self.float()?);
+                }
+                if !matrix.is_unitary(UNITARY_TOL) {
+                    return Err(self.invalid("gate matrix is not unitary"));
+                }
+                WalEvent::Gate { id, name, matrix }
+            }
+            TAG_COMMIT => WalEvent::Commit,
+            TAG_ENTANGLE if self.version >= 2 => {
+                let a = self.string()?;
+                let b = self.string()?;
+                let kind = match self.byte()? {
+                    0 => BellKind::Phi,
+                    1 => BellKind::Psi,
+                    _ => return Err(self.invalid("unknown pair kind")),
+                };
+                WalEvent::Entangle { a, b, kind }
+            }
+            TAG_DISENTANGLE if self.version >= 2 => WalEvent::Disentangle { id: self.string()? },
+            tag => return Err(self.invalid(&format!("unknown tag {:#04x}", tag))),
+        })
+    }
+}
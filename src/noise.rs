@@ -0,0 +1,168 @@
+//! Kraus-operator noise channels — the instability beneath the iceberg.
+//!
+//! A [`KrausChannel`] maps `ρ → Σ Kᵢ ρ Kᵢ†`. Channels act on single
+//! states; a [`NoiseModel`] attaches them to gates so an
+//! [`Executor`](crate::circuit::Executor) degrades every target after
+//! each gate.
+
+use std::collections::HashMap;
+
+use crate::complex::Complex;
+use crate::gate::{Gate, X, Y, Z};
+use crate::matrix::Matrix;
+
+/// A completely positive, trace-preserving single-state channel.
+#[derive(Clone, Debug)]
+pub struct KrausChannel {
+    name: String,
+    operators: Vec<Matrix>,
+}
+
+impl KrausChannel {
+    /// Channel from explicit 2×2 Kraus operators.
+    ///
+    /// Panics unless `Σ Kᵢ†Kᵢ = I`.
+    pub fn new(name: &str, operators: Vec<Matrix>) -> Self {
+        assert!(
+            operators.iter().all(|k| k.dim() == 2),
+            "Kraus operators must be 2×2"
+        );
+        let completeness = operators
+            .iter()
+            .fold(Matrix::zeros(2), |acc, k| &acc + &(&k.dagger() * k));
+        assert!(
+            completeness.max_abs_diff(&Matrix::identity(2)) <= 1e-9,
+            "Kraus operators must satisfy Σ K†K = I"
+        );
+        Self {
+            name: name.to_string(),
+            operators,
+        }
+    }
+
+    /// With probability `p` the state is replaced by the maximally mixed
+    /// state; `p` is clamped to `[0, 1]`.
+    pub fn depolarizing(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        let k = |w: f64, m: Matrix| m.scale(Complex::real(w.sqrt()));
+        Self::new(
+            "depolarizing",
+            vec![
+                k(1.0 - 0.75 * p, Matrix::identity(2)),
+                k(p / 4.0, X.matrix()),
+                k(p / 4.0, Y.matrix()),
+                k(p / 4.0, Z.matrix()),
+            ],
+        )
+    }
+
+    /// Inverts the state (Pauli-X) with probability `p`, clamped to
+    /// `[0, 1]`.
+    pub fn bit_flip(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        Self::new(
+            "bit_flip",
+            vec![
+                Matrix::identity(2).scale(Complex::real((1.0 - p).sqrt())),
+                X.matrix().scale(Complex::real(p.sqrt())),
+            ],
+        )
+    }
+
+    /// Flips the antimatter phase (Pauli-Z) with probability `p`, clamped
+    /// to `[0, 1]`.
+    pub fn phase_flip(p: f64) -> Self {
+        let p = p.clamp(0.0, 1.0);
+        Self::new(
+            "phase_flip",
+            vec![
+                Matrix::identity(2).scale(Complex::real((1.0 - p).sqrt())),
+                Z.matrix().scale(Complex::real(p.sqrt())),
+            ],
+        )
+    }
+
+    /// Antimatter relaxes into matter with probability `gamma`.
+    pub fn amplitude_damping(gamma: f64) -> Self {
+        let g = gamma.clamp(0.0, 1.0);
+        Self::new(
+            "amplitude_damping",
+            vec![
+                Matrix::from_real([[1.0, 0.0], [0.0, (1.0 - g).sqrt()]]),
+                Matrix::from_real([[0.0, g.sqrt()], [0.0, 0.0]]),
+            ],
+        )
+    }
+
+    /// Coherence decays by `√(1 - lambda)` with populations untouched.
+    pub fn phase_damping(lambda: f64) -> Self {
+        let l = lambda.clamp(0.0, 1.0);
+        Self::new(
+            "phase_damping",
+            vec![
+                Matrix::from_real([[1.0, 0.0], [0.0, (1.0 - l).sqrt()]]),
+                Matrix::from_real([[0.0, 0.0], [0.0, l.sqrt()]]),
+            ],
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn operators(&self) -> &[Matrix] {
+        &self.operators
+    }
+
+    /// `Σ Kᵢ ρ Kᵢ†` for a 2×2 density matrix.
+    pub fn apply_to_density(&self, rho: &Matrix) -> Matrix {
+        self.operators
+            .iter()
+            .fold(Matrix::zeros(rho.dim()), |acc, k| {
+                &acc + &(&(k * rho) * &k.dagger())
+            })
+    }
+}
+
+/// Noise attached to gates by mnemonic, with an optional default.
+///
+/// After a gate runs, every channel configured for it (or the default
+/// channels, when none are) is applied to each of its targets in order.
+#[derive(Clone, Debug, Default)]
+pub struct NoiseModel {
+    default: Vec<KrausChannel>,
+    per_gate: HashMap<String, Vec<KrausChannel>>,
+}
+
+impl NoiseModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a channel applied after gates without their own noise.
+    pub fn with_default(mut self, channel: KrausChannel) -> Self {
+        self.default.push(channel);
+        self
+    }
+
+    /// Add a channel applied after every gate named `gate`.
+    pub fn with_gate(mut self, gate: &str, channel: KrausChannel) -> Self {
+        self.per_gate
+            .entry(gate.to_string())
+            .or_default()
+            .push(channel);
+        self
+    }
+
+    /// Channels that follow a gate with mnemonic `gate`.
+    pub fn channels_for(&self, gate: &str) -> &[KrausChannel] {
+        self.per_gate
+            .get(gate)
+            .map(Vec::as_slice)
+            .unwrap_or(&self.default)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.default.is_empty() && self.per_gate.values().all(Vec::is_empty)
+    }
+}
@@ -0,0 +1,756 @@
+//! OpenQASM 3 import and export.
+//!
+//! The supported subset covers what a [`Circuit`] can express:
+//!
+//! * `OPENQASM 3;` and `include "stdgates.inc";`
+//! * `qubit[n] q;` / `qubit q;` and `bit[n] c;` / `bit c;` declarations —
+//!   several registers are laid out one after another
+//! * the standard gates known to [`gate::standard`], with `pi`/`π`
+//!   arithmetic in angle arguments and whole-register broadcasting
+//! * `c[0] = measure q[0];`, `c = measure q;` and `measure q[0] -> c[0];`
+//! * `if (…) stmt` and `if (…) { … }`, where the condition is `c == n`,
+//!   `c[k] == b`, `c[k]`, `!c[k]` (a one-bit register may drop the
+//!   index), or a `&&` chain of those
+//! * `barrier` statements, accepted and ignored
+//!
+//! [`export`] writes one `q` and one `c` register. Any circuit built from
+//! standard gates survives `export` → [`parse`] unchanged.
+
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::circuit::{Circuit, Condition, Operation, MAX_CONDITION_BITS};
+use crate::gate::{self, Gate};
+
+/// A QASM program could not be read, or a circuit could not be written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QasmError {
+    /// Malformed or unsupported input at a 1-based line and column.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The circuit uses something QASM output cannot express.
+    Export { message: String },
+}
+
+impl Display for QasmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QasmError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}: {}", line, column, message),
+            QasmError::Export { message } => write!(f, "cannot export circuit: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for QasmError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Int(u64),
+    Float(f64),
+    Str(String),
+    Sym(&'static str),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    line: usize,
+    column: usize,
+}
+
+const SYMBOLS: [&str; 16] = [
+    "==", "->", "&&", ";", ",", "[", "]", "(", ")", "{", "}", "=", "+", "-", "*", "/",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, QasmError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+    let err = |line, column, message: String| QasmError::Parse {
+        line,
+        column,
+        message,
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let (start_line, start_column) = (line, column);
+        let advance = |n: usize, i: &mut usize, line: &mut usize, column: &mut usize| {
+            for _ in 0..n {
+                if chars[*i] == '\n' {
+                    *line += 1;
+                    *column = 1;
+                } else {
+                    *column += 1;
+                }
+                *i += 1;
+            }
+        };
+        if c.is_whitespace() {
+            advance(1, &mut i, &mut line, &mut column);
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                advance(1, &mut i, &mut line, &mut column);
+            }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            advance(2, &mut i, &mut line, &mut column);
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                advance(1, &mut i, &mut line, &mut column);
+            }
+            if i >= chars.len() {
+                return Err(err(start_line, start_column, "unterminated comment".into()));
+            }
+            advance(2, &mut i, &mut line, &mut column);
+            continue;
+        }
+        let tok = if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                ident.push(chars[i]);
+                advance(1, &mut i, &mut line, &mut column);
+            }
+            Tok::Ident(if ident == "π" { "pi".into() } else { ident })
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()))
+        {
+            let mut text = String::new();
+            let mut float = false;
+            while i < chars.len() {
+                let d = chars[i];
+                let exponent_sign =
+                    (d == '+' || d == '-') && matches!(text.chars().last(), Some('e' | 'E'));
+                if d.is_ascii_digit() || exponent_sign {
+                    text.push(d);
+                } else if d == '.' || d == 'e' || d == 'E' {
+                    float = true;
+                    text.push(d);
+                } else {
+                    break;
+                }
+                advance(1, &mut i, &mut line, &mut column);
+            }
+            let bad = || {
+                err(
+                    start_line,
+                    start_column,
+                    format!("malformed number `{}`", text),
+                )
+            };
+            if float {
+                Tok::Float(text.parse().map_err(|_| bad())?)
+            } else {
+                Tok::Int(text.parse().map_err(|_| bad())?)
+            }
+        } else if c == '"' {
+            advance(1, &mut i, &mut line, &mut column);
+            let mut s = String::new();
+            while i < chars.len() && chars[i] != '"' && chars[i] != '\n' {
+                s.push(chars[i]);
+                advance(1, &mut i, &mut line, &mut column);
+            }
+            if chars.get(i) != Some(&'"') {
+                return Err(err(start_line, start_column, "unterminated string".into()));
+            }
+            advance(1, &mut i, &mut line, &mut column);
+            Tok::Str(s)
+        } else if let Some(sym) = SYMBOLS.iter().find(|s| {
+            s.chars()
+                .enumerate()
+                .all(|(k, sc)| chars.get(i + k) == Some(&sc))
+        }) {
+            advance(sym.len(), &mut i, &mut line, &mut column);
+            Tok::Sym(sym)
+        } else if c == '!' {
+            advance(1, &mut i, &mut line, &mut column);
+            Tok::Sym("!")
+        } else {
+            return Err(err(line, column, format!("unexpected character `{}`", c)));
+        };
+        tokens.push(Token {
+            tok,
+            line: start_line,
+            column: start_column,
+        });
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Debug)]
+struct Register {
+    name: String,
+    offset: usize,
+    size: usize,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    qubits: Vec<Register>,
+    bits: Vec<Register>,
+    ops: Vec<Operation>,
+}
+
+/// Parse an OpenQASM 3 program into a circuit.
+pub fn parse(source: &str) -> Result<Circuit, QasmError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        qubits: Vec::new(),
+        bits: Vec::new(),
+        ops: Vec::new(),
+    };
+    parser.program()?;
+    let size = |regs: &[Register]| regs.last().map_or(0, |r| r.offset + r.size);
+    let mut circuit = Circuit::new(size(&parser.qubits), size(&parser.bits));
+    for op in parser.ops {
+        circuit.push(op);
+    }
+    Ok(circuit)
+}
+
+impl Parser {
+    fn program(&mut self) -> Result<(), QasmError> {
+        if self.eat_ident("OPENQASM") {
+            let major = match self.next()? {
+                Tok::Int(v) => v as f64,
+                Tok::Float(v) => v,
+                _ => return Err(self.error_prev("expected a version number")),
+            };
+            if major.trunc() != 3.0 {
+                return Err(self.error_prev("only OpenQASM 3 is supported"));
+            }
+            self.expect(";")?;
+        }
+        while self.pos < self.tokens.len() {
+            self.statement(true)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, top_level: bool) -> Result<(), QasmError> {
+        let name = match self.peek() {
+            Some(Tok::Ident(name)) => name.clone(),
+            Some(_) => return Err(self.error_here("expected a statement")),
+            None => return Err(self.error_here("unexpected end of input")),
+        };
+        match name.as_str() {
+            "include" => {
+                self.require_top_level(top_level, "include")?;
+                self.pos += 1;
+                match self.next()? {
+                    Tok::Str(file) if file == "stdgates.inc" => {}
+                    Tok::Str(file) => {
+                        return Err(self.error_prev(&format!("cannot include `{}`", file)))
+                    }
+                    _ => return Err(self.error_prev("expected a file name")),
+                }
+                self.expect(";")
+            }
+            "qubit" | "bit" => {
+                self.require_top_level(top_level, &name)?;
+                self.pos += 1;
+                self.declaration(name == "qubit")
+            }
+            "measure" => {
+                self.pos += 1;
+                let qubits = self.qubit_arg()?;
+                self.expect("->")?;
+                let bits = self.bit_arg()?;
+                self.expect(";")?;
+                self.measurements(&qubits, &bits)
+            }
+            "if" => {
+                self.pos += 1;
+                self.conditional()
+            }
+            "barrier" => {
+                self.pos += 1;
+                while !self.eat(";") {
+                    self.next()?;
+                }
+                Ok(())
+            }
+            _ if self.find(&self.bits, &name).is_some() => {
+                let bits = self.bit_arg()?;
+                self.expect("=")?;
+                if !self.eat_ident("measure") {
+                    return Err(self.error_here("expected `measure`"));
+                }
+                let qubits = self.qubit_arg()?;
+                self.expect(";")?;
+                self.measurements(&qubits, &bits)
+            }
+            _ => self.gate_call(),
+        }
+    }
+
+    fn require_top_level(&self, top_level: bool, what: &str) -> Result<(), QasmError> {
+        if top_level {
+            Ok(())
+        } else {
+            Err(self.error_here(&format!("`{}` is only allowed at top level", what)))
+        }
+    }
+
+    fn declaration(&mut self, quantum: bool) -> Result<(), QasmError> {
+        let size = if self.eat("[") {
+            let n = self.int()? as usize;
+            self.expect("]")?;
+            n
+        } else {
+            1
+        };
+        let name = self.ident()?;
+        if self.find(&self.qubits, &name).is_some() || self.find(&self.bits, &name).is_some() {
+            return Err(self.error_prev(&format!("`{}` is already declared", name)));
+        }
+        self.expect(";")?;
+        let regs = if quantum {
+            &mut self.qubits
+        } else {
+            &mut self.bits
+        };
+        let offset = regs.last().map_or(0, |r| r.offset + r.size);
+        regs.push(Register { name, offset, size });
+        Ok(())
+    }
+
+    fn measurements(&mut self, qubits: &[usize], bits: &[usize]) -> Result<(), QasmError> {
+        if qubits.len() != bits.len() {
+            return Err(self.error_prev("measure operands differ in size"));
+        }
+        for (&index, &bit) in qubits.iter().zip(bits) {
+            self.ops.push(Operation::Measure { index, bit });
+        }
+        Ok(())
+    }
+
+    fn gate_call(&mut self) -> Result<(), QasmError> {
+        let name = self.ident()?;
+        let name = match name.as_str() {
+            "cnot" | "CX" => "cx".to_string(),
+            "phase" => "p".to_string(),
+            _ => name,
+        };
+        let mut params = Vec::new();
+        if self.eat("(") {
+            loop {
+                params.push(self.expr()?);
+                if self.eat(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        let gate: Arc<dyn Gate> = match gate::standard(&name, &params) {
+            Some(gate) => Arc::from(gate),
+            None => return Err(self.error_prev(&format!("unknown gate `{}`", name))),
+        };
+        let mut args = vec![self.qubit_arg()?];
+        while self.eat(",") {
+            args.push(self.qubit_arg()?);
+        }
+        self.expect(";")?;
+        if args.len() != gate.arity() {
+            return Err(self.error_prev(&format!(
+                "gate `{}` takes {} qubit arguments",
+                name,
+                gate.arity()
+            )));
+        }
+        let width = args.iter().map(Vec::len).max().unwrap_or(1);
+        if args.iter().any(|a| a.len() != 1 && a.len() != width) {
+            return Err(self.error_prev("broadcast registers differ in size"));
+        }
+        for k in 0..width {
+            let targets: Vec<usize> = args
+                .iter()
+                .map(|a| if a.len() == 1 { a[0] } else { a[k] })
+                .collect();
+            if (1..targets.len()).any(|i| targets[..i].contains(&targets[i])) {
+                return Err(self.error_prev("gate arguments must be distinct qubits"));
+            }
+            self.ops.push(Operation::Gate {
+                gate: gate.clone(),
+                targets,
+            });
+        }
+        Ok(())
+    }
+
+    fn conditional(&mut self) -> Result<(), QasmError> {
+        self.expect("(")?;
+        let condition = self.condition()?;
+        self.expect(")")?;
+        let outer = std::mem::take(&mut self.ops);
+        let body = if self.eat("{") {
+            while !self.eat("}") {
+                self.statement(false)?;
+            }
+            Ok(())
+        } else {
+            self.statement(false)
+        };
+        let inner = std::mem::replace(&mut self.ops, outer);
+        body?;
+        for op in inner {
+            self.ops.push(Operation::Conditional {
+                condition: condition.clone(),
+                op: Box::new(op),
+            });
+        }
+        Ok(())
+    }
+
+    fn condition(&mut self) -> Result<Condition, QasmError> {
+        let mut terms: Vec<(usize, bool)> = Vec::new();
+        loop {
+            let negated = self.eat("!");
+            let name = self.ident()?;
+            let reg = self
+                .find(&self.bits, &name)
+                .ok_or_else(|| self.error_prev(&format!("`{}` is not a bit register", name)))?;
+            let single = if self.eat("[") {
+                Some(self.index_into(&reg)?)
+            } else if reg.size == 1 {
+                Some(reg.offset)
+            } else {
+                None
+            };
+            if let Some(k) = single {
+                let value = if !negated && self.eat("==") {
+                    match self.int()? {
+                        0 => false,
+                        1 => true,
+                        _ => return Err(self.error_prev("a bit compares to 0 or 1")),
+                    }
+                } else {
+                    !negated
+                };
+                terms.push((k, value));
+            } else {
+                if negated {
+                    return Err(self.error_prev("`!` applies to a single bit"));
+                }
+                self.expect("==")?;
+                let value = self.int()?;
+                if reg.size < 64 && value >> reg.size != 0 {
+                    return Err(self.error_prev("value does not fit the register"));
+                }
+                for i in 0..reg.size {
+                    let bit = value.checked_shr(i as u32).is_some_and(|v| v & 1 == 1);
+                    terms.push((reg.offset + i, bit));
+                }
+            }
+            if !self.eat("&&") {
+                break;
+            }
+        }
+        let mut bits = Vec::new();
+        let mut value = 0u64;
+        for (bit, v) in terms {
+            match bits.iter().position(|&b| b == bit) {
+                Some(i) if ((value >> i) & 1 == 1) != v => {
+                    return Err(self.error_prev("contradictory condition"))
+                }
+                Some(_) => {}
+                None => {
+                    if bits.len() == MAX_CONDITION_BITS {
+                        return Err(self.error_prev("conditions are limited to 64 bits"));
+                    }
+                    value |= (v as u64) << bits.len();
+                    bits.push(bit);
+                }
+            }
+        }
+        Ok(Condition::register(bits, value))
+    }
+
+    fn qubit_arg(&mut self) -> Result<Vec<usize>, QasmError> {
+        let name = self.ident()?;
+        let reg = self
+            .find(&self.qubits, &name)
+            .ok_or_else(|| self.error_prev(&format!("`{}` is not a qubit register", name)))?;
+        self.reg_arg(&reg)
+    }
+
+    fn bit_arg(&mut self) -> Result<Vec<usize>, QasmError> {
+        let name = self.ident()?;
+        let reg = self
+            .find(&self.bits, &name)
+            .ok_or_else(|| self.error_prev(&format!("`{}` is not a bit register", name)))?;
+        self.reg_arg(&reg)
+    }
+
+    fn reg_arg(&mut self, reg: &Register) -> Result<Vec<usize>, QasmError> {
+        if self.eat("[") {
+            Ok(vec![self.index_into(reg)?])
+        } else {
+            Ok((reg.offset..reg.offset + reg.size).collect())
+        }
+    }
+
+    /// Parse `k]` after `reg[`, returning the flat index.
+    fn index_into(&mut self, reg: &Register) -> Result<usize, QasmError> {
+        let k = self.int()? as usize;
+        if k >= reg.size {
+            return Err(self.error_prev(&format!(
+                "index {} out of range for `{}[{}]`",
+                k, reg.name, reg.size
+            )));
+        }
+        self.expect("]")?;
+        Ok(reg.offset + k)
+    }
+
+    fn find(&self, regs: &[Register], name: &str) -> Option<Register> {
+        regs.iter().find(|r| r.name == name).cloned()
+    }
+
+    fn expr(&mut self) -> Result<f64, QasmError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat("+") {
+                value += self.term()?;
+            } else if self.eat("-") {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, QasmError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat("*") {
+                value *= self.unary()?;
+            } else if self.eat("/") {
+                value /= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, QasmError> {
+        if self.eat("-") {
+            return Ok(-self.unary()?);
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("(") {
+            let value = self.expr()?;
+            self.expect(")")?;
+            return Ok(value);
+        }
+        match self.next()? {
+            Tok::Int(v) => Ok(v as f64),
+            Tok::Float(v) => Ok(v),
+            Tok::Ident(name) if name == "pi" => Ok(PI),
+            Tok::Ident(name) if name == "tau" => Ok(2.0 * PI),
+            _ => Err(self.error_prev("expected a number")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn next(&mut self) -> Result<Tok, QasmError> {
+        let tok = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error_here("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(tok)
+    }
+
+    fn eat(&mut self, sym: &str) -> bool {
+        if matches!(self.peek(), Some(Tok::Sym(s)) if *s == sym) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Tok::Ident(s)) if s == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), QasmError> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            Err(self.error_here(&format!("expected `{}`", sym)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, QasmError> {
+        match self.next()? {
+            Tok::Ident(name) => Ok(name),
+            _ => Err(self.error_prev("expected an identifier")),
+        }
+    }
+
+    fn int(&mut self) -> Result<u64, QasmError> {
+        match self.next()? {
+            Tok::Int(v) => Ok(v),
+            _ => Err(self.error_prev("expected an integer")),
+        }
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> QasmError {
+        let (line, column) = match self.tokens.get(pos).or(self.tokens.last()) {
+            Some(t) => (t.line, t.column),
+            None => (1, 1),
+        };
+        QasmError::Parse {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    fn error_here(&self, message: &str) -> QasmError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_prev(&self, message: &str) -> QasmError {
+        self.error_at(self.pos.saturating_sub(1), message)
+    }
+}
+
+/// Write a circuit as OpenQASM 3 over registers `q` and `c`.
+///
+/// Fails for gates that are not standard, including custom gates that
+/// reuse a standard name with a different matrix.
+pub fn export(circuit: &Circuit) -> Result<String, QasmError> {
+    let mut out = String::from("OPENQASM 3.0;\ninclude \"stdgates.inc\";\n");
+    if circuit.num_states() > 0 {
+        out.push_str(&format!("qubit[{}] q;\n", circuit.num_states()));
+    }
+    if circuit.num_bits() > 0 {
+        out.push_str(&format!("bit[{}] c;\n", circuit.num_bits()));
+    }
+    for op in circuit.operations() {
+        out.push_str(&statement(op, circuit.num_bits())?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn statement(op: &Operation, num_bits: usize) -> Result<String, QasmError> {
+    Ok(match op {
+        Operation::Gate { gate, targets } => {
+            let params = gate.params();
+            let standard =
+                gate::standard(gate.name(), &params).ok_or_else(|| QasmError::Export {
+                    message: format!("gate `{}` has no OpenQASM equivalent", gate.name()),
+                })?;
+            if standard.matrix().max_abs_diff(&gate.matrix()) > 1e-12 {
+                return Err(QasmError::Export {
+                    message: format!("gate `{}` differs from the standard gate", gate.name()),
+                });
+            }
+            if let Some(p) = params.iter().find(|p| !p.is_finite()) {
+                return Err(QasmError::Export {
+                    message: format!("gate `{}` has non-finite angle {}", gate.name(), p),
+                });
+            }
+            let args: Vec<String> = targets.iter().map(|t| format!("q[{}]", t)).collect();
+            if params.is_empty() {
+                format!("{} {};", gate.name(), args.join(", "))
+            } else {
+                let angles: Vec<String> = params.iter().map(|&p| angle(p)).collect();
+                format!(
+                    "{}({}) {};",
+                    gate.name(),
+                    angles.join(", "),
+                    args.join(", ")
+                )
+            }
+        }
+        Operation::Measure { index, bit } => format!("c[{}] = measure q[{}];", bit, index),
+        Operation::Conditional { condition, op } => {
+            format!(
+                "if ({}) {}",
+                condition_text(condition, num_bits),
+                statement(op, num_bits)?
+            )
+        }
+    })
+}
+
+fn condition_text(condition: &Condition, num_bits: usize) -> String {
+    let whole = condition.bits.len() == num_bits
+        && num_bits > 1
+        && condition.bits.iter().enumerate().all(|(i, &b)| i == b);
+    if whole {
+        return format!("c == {}", condition.value);
+    }
+    condition
+        .bits
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let bit = condition.value.checked_shr(i as u32).unwrap_or(0) & 1;
+            format!("c[{}] == {}", b, bit)
+        })
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// Render an angle, as a small multiple of `pi` when that reads back
+/// bit-for-bit, otherwise as a shortest round-trip decimal.
+fn angle(theta: f64) -> String {
+    for d in 1..=8u32 {
+        for k in 1..=16i32 {
+            for k in [k, -k] {
+                let text = match (k, d) {
+                    (1, 1) => "pi".to_string(),
+                    (-1, 1) => "-pi".to_string(),
+                    (1, _) => format!("pi/{}", d),
+                    (-1, _) => format!("-pi/{}", d),
+                    (_, 1) => format!("{}*pi", k),
+                    _ => format!("{}*pi/{}", k, d),
+                };
+                if eval_angle(&text).map(f64::to_bits) == Some(theta.to_bits()) {
+                    return text;
+                }
+            }
+        }
+    }
+    format!("{:?}", theta)
+}
+
+/// Evaluate a constant angle expression such as `3*pi/4`.
+pub(crate) fn eval_angle(text: &str) -> Option<f64> {
+    let mut parser = Parser {
+        tokens: tokenize(text).ok()?,
+        pos: 0,
+        qubits: Vec::new(),
+        bits: Vec::new(),
+        ops: Vec::new(),
+    };
+    parser.expr().ok()
+}
@@ -0,0 +1,815 @@
+//! Quantum error correction — repetition and surface codes.
+//!
+//! A [`StabilizerCode`] lists its data states, its X- and Z-type
+//! stabilizers and its logical operators. [`StabilizerCode::extraction`]
+//! appends one round of syndrome extraction to a [`Circuit`]: a single
+//! ancilla after the data is entangled with each stabilizer's support,
+//! measured, and reset, so a distance-3 surface code needs ten register
+//! entries.
+//!
+//! A [`MemoryExperiment`] prepares a logical state, runs noisy rounds on
+//! the [`StateVectorBackend`], and decodes each shot with a
+//! [`MatchingDecoder`]: a minimum-weight perfect matching of the
+//! detection events over the space-time graph of the checks. Its
+//! [`LogicalErrorRate`] is what noise does to the encoded state rather
+//! than to one physical state.
+//!
+//! Noise is phenomenological: before every round each data state passes
+//! through a depolarizing channel, and each ancilla is bit-flipped before
+//! it is read. Extraction gates themselves are noiseless.
+//!
+//! Experiments run on a full state vector, so only small codes can be
+//! simulated: [`MemoryExperiment::run`] refuses codes needing more than
+//! [`MAX_STATES`] register entries. That admits the distance-3 surface
+//! code and repetition codes of up to 19 states, but not the distance-5
+//! surface code (26 entries); larger codes can still be built, decoded
+//! and turned into circuits.
+
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+
+use crate::circuit::{Circuit, Condition, Executor, Operation, StateVectorBackend};
+use crate::gate::{CNot, UnitaryGate, H, X};
+use crate::matrix::Matrix;
+use crate::noise::{KrausChannel, NoiseModel};
+
+/// Gate name the data noise is attached to.
+const IDLE: &str = "idle";
+/// Gate name the readout noise is attached to.
+const READOUT: &str = "readout";
+/// Largest register [`MemoryExperiment::run`] simulates. The state
+/// vector holds `2^n` amplitudes, 16 MiB at this size.
+pub const MAX_STATES: usize = 20;
+/// Largest number of detection events matched exactly; larger sets are
+/// matched greedily.
+const MAX_EXACT: usize = 20;
+
+/// The type of a stabilizer, and the basis of a memory experiment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    X,
+    Z,
+}
+
+impl Display for Pauli {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pauli::X => write!(f, "X"),
+            Pauli::Z => write!(f, "Z"),
+        }
+    }
+}
+
+/// A product of one Pauli over a set of data states.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stabilizer {
+    pub pauli: Pauli,
+    pub support: Vec<usize>,
+}
+
+/// A CSS stabilizer code over `num_data` data states.
+#[derive(Clone, Debug)]
+pub struct StabilizerCode {
+    name: String,
+    num_data: usize,
+    distance: usize,
+    stabilizers: Vec<Stabilizer>,
+    logical_x: Vec<usize>,
+    logical_z: Vec<usize>,
+}
+
+/// True if X on one support and Z on the other anticommute, i.e. the
+/// supports share an odd number of states.
+fn anticommute(a: &[usize], b: &[usize]) -> bool {
+    a.iter().filter(|q| b.contains(q)).count() % 2 == 1
+}
+
+impl StabilizerCode {
+    /// A code from its parts.
+    ///
+    /// # Panics
+    /// If a support names a missing data state, an X and a Z stabilizer
+    /// anticommute, a logical operator anticommutes with a stabilizer of
+    /// the other type, or the logical X and Z commute.
+    pub fn new(
+        name: &str,
+        num_data: usize,
+        distance: usize,
+        stabilizers: Vec<Stabilizer>,
+        logical_x: Vec<usize>,
+        logical_z: Vec<usize>,
+    ) -> Self {
+        let supports = stabilizers
+            .iter()
+            .map(|s| &s.support)
+            .chain([&logical_x, &logical_z]);
+        for support in supports {
+            assert!(
+                support.iter().all(|&q| q < num_data),
+                "support names a missing data state"
+            );
+        }
+        for x in stabilizers.iter().filter(|s| s.pauli == Pauli::X) {
+            for z in stabilizers.iter().filter(|s| s.pauli == Pauli::Z) {
+                assert!(
+                    !anticommute(&x.support, &z.support),
+                    "X and Z stabilizers must commute"
+                );
+            }
+        }
+        for s in &stabilizers {
+            let other = match s.pauli {
+                Pauli::X => &logical_z,
+                Pauli::Z => &logical_x,
+            };
+            assert!(
+                !anticommute(&s.support, other),
+                "logical operators must commute with the stabilizers"
+            );
+        }
+        assert!(
+            anticommute(&logical_x, &logical_z),
+            "logical X and Z must anticommute"
+        );
+        Self {
+            name: name.to_string(),
+            num_data,
+            distance,
+            stabilizers,
+            logical_x,
+            logical_z,
+        }
+    }
+
+    /// The bit-flip repetition code on `n` states: Z checks on each
+    /// neighbouring pair. It protects the Z basis only.
+    ///
+    /// # Panics
+    /// If `n < 2`.
+    pub fn repetition(n: usize) -> Self {
+        assert!(n >= 2, "a repetition code needs at least two states");
+        let stabilizers = (0..n - 1)
+            .map(|i| Stabilizer {
+                pauli: Pauli::Z,
+                support: vec![i, i + 1],
+            })
+            .collect();
+        Self::new(
+            &format!("repetition-{}", n),
+            n,
+            n,
+            stabilizers,
+            (0..n).collect(),
+            vec![0],
+        )
+    }
+
+    /// The rotated surface code of odd distance `d` on a `d × d` grid,
+    /// data state `r·d + c` at row `r`, column `c`.
+    ///
+    /// Weight-two X checks sit on the top and bottom edges and weight-two
+    /// Z checks on the left and right, so logical X runs down column 0
+    /// and logical Z along row 0.
+    ///
+    /// # Panics
+    /// If `d` is even or less than 3.
+    pub fn surface(d: usize) -> Self {
+        assert!(
+            d >= 3 && d % 2 == 1,
+            "surface code distance must be odd and at least 3"
+        );
+        let d = d as isize;
+        let mut stabilizers = Vec::new();
+        for i in -1..d {
+            for j in -1..d {
+                let pauli = if (i + j).rem_euclid(2) == 0 {
+                    Pauli::X
+                } else {
+                    Pauli::Z
+                };
+                let on_edge = |k: isize| k == -1 || k == d - 1;
+                let keep = match (on_edge(i), on_edge(j)) {
+                    (false, false) => true,
+                    (true, false) => pauli == Pauli::X,
+                    (false, true) => pauli == Pauli::Z,
+                    (true, true) => false,
+                };
+                if !keep {
+                    continue;
+                }
+                let support = [(i, j), (i, j + 1), (i + 1, j), (i + 1, j + 1)]
+                    .into_iter()
+                    .filter(|&(r, c)| (0..d).contains(&r) && (0..d).contains(&c))
+                    .map(|(r, c)| (r * d + c) as usize)
+                    .collect();
+                stabilizers.push(Stabilizer { pauli, support });
+            }
+        }
+        let n = (d * d) as usize;
+        let d = d as usize;
+        Self::new(
+            &format!("surface-{}", d),
+            n,
+            d,
+            stabilizers,
+            (0..d).map(|r| r * d).collect(),
+            (0..d).collect(),
+        )
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn num_data(&self) -> usize {
+        self.num_data
+    }
+
+    /// Register entries a circuit needs: the data, then one ancilla.
+    pub fn num_states(&self) -> usize {
+        self.num_data + 1
+    }
+
+    /// Register index of the ancilla.
+    pub fn ancilla(&self) -> usize {
+        self.num_data
+    }
+
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    pub fn stabilizers(&self) -> &[Stabilizer] {
+        &self.stabilizers
+    }
+
+    /// Indices into [`stabilizers`](Self::stabilizers) of one type.
+    pub fn checks(&self, pauli: Pauli) -> Vec<usize> {
+        (0..self.stabilizers.len())
+            .filter(|&k| self.stabilizers[k].pauli == pauli)
+            .collect()
+    }
+
+    /// Support of the logical operator of one type.
+    pub fn logical(&self, pauli: Pauli) -> &[usize] {
+        match pauli {
+            Pauli::X => &self.logical_x,
+            Pauli::Z => &self.logical_z,
+        }
+    }
+
+    /// Append one round of syndrome extraction, storing stabilizer `k`'s
+    /// outcome in bit `first_bit + k`. The ancilla is reset after every
+    /// measurement, and read through a `readout` gate that noise can be
+    /// attached to.
+    pub fn extraction(&self, circuit: &mut Circuit, first_bit: usize) {
+        let a = self.ancilla();
+        let readout = UnitaryGate::new(READOUT, Matrix::identity(2));
+        for (k, stabilizer) in self.stabilizers.iter().enumerate() {
+            let bit = first_bit + k;
+            match stabilizer.pauli {
+                Pauli::Z => {
+                    for &q in &stabilizer.support {
+                        circuit.gate(CNot, &[q, a]);
+                    }
+                }
+                Pauli::X => {
+                    circuit.gate(H, &[a]);
+                    for &q in &stabilizer.support {
+                        circuit.gate(CNot, &[a, q]);
+                    }
+                    circuit.gate(H, &[a]);
+                }
+            }
+            circuit.gate(readout.clone(), &[a]);
+            circuit.measure(a, bit);
+            circuit.gate_if(Condition::bit(bit, true), X, &[a]);
+        }
+    }
+
+    /// Check outcomes of one type implied by a data readout in that basis.
+    pub fn syndrome(&self, pauli: Pauli, data: &[bool]) -> Vec<bool> {
+        self.checks(pauli)
+            .into_iter()
+            .map(|k| parity(&self.stabilizers[k].support, data))
+            .collect()
+    }
+}
+
+fn parity(support: &[usize], bits: &[bool]) -> bool {
+    support.iter().filter(|&&q| bits[q]).count() % 2 == 1
+}
+
+/// `ln((1 − p)/p)`, the matching weight of an error with probability `p`.
+fn weight(p: f64) -> f64 {
+    let p = p.clamp(1e-12, 0.499);
+    ((1.0 - p) / p).ln()
+}
+
+/// One edge of the decoding graph.
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    a: usize,
+    b: usize,
+    weight: f64,
+    /// The data state a space edge flips; `None` for a readout error.
+    data: Option<usize>,
+}
+
+/// Minimum-weight perfect matching over a code's space-time check graph.
+///
+/// Nodes are the checks of one type in each of `rounds + 1` layers (the
+/// last one inferred from the final data readout) plus a boundary. A
+/// data error joins the checks it flips within a layer, or one check to
+/// the boundary; a readout error joins a check to itself one layer on.
+#[derive(Clone, Debug)]
+pub struct MatchingDecoder {
+    pauli: Pauli,
+    rounds: usize,
+    num_data: usize,
+    num_checks: usize,
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl MatchingDecoder {
+    /// Decoder for the `pauli`-type checks of `code` over `rounds` rounds
+    /// with data-flip probability `data_error` and readout-flip
+    /// probability `measurement_error` per round.
+    ///
+    /// # Panics
+    /// If a data state lies in more than two checks of that type.
+    pub fn new(
+        code: &StabilizerCode,
+        pauli: Pauli,
+        rounds: usize,
+        data_error: f64,
+        measurement_error: f64,
+    ) -> Self {
+        let checks = code.checks(pauli);
+        let m = checks.len();
+        let boundary = (rounds + 1) * m;
+        let mut edges = Vec::new();
+        for q in 0..code.num_data() {
+            let touching: Vec<usize> = (0..m)
+                .filter(|&c| code.stabilizers[checks[c]].support.contains(&q))
+                .collect();
+            assert!(
+                touching.len() <= 2,
+                "data state {} is in more than two checks",
+                q
+            );
+            for layer in 0..rounds {
+                let node = |c: usize| layer * m + c;
+                let (a, b) = match touching[..] {
+                    [c] => (node(c), boundary),
+                    [c, d] => (node(c), node(d)),
+                    _ => continue,
+                };
+                edges.push(Edge {
+                    a,
+                    b,
+                    weight: weight(data_error),
+                    data: Some(q),
+                });
+            }
+        }
+        for layer in 0..rounds {
+            for c in 0..m {
+                edges.push(Edge {
+                    a: layer * m + c,
+                    b: (layer + 1) * m + c,
+                    weight: weight(measurement_error),
+                    data: None,
+                });
+            }
+        }
+        let mut adjacency = vec![Vec::new(); boundary + 1];
+        for (e, edge) in edges.iter().enumerate() {
+            adjacency[edge.a].push(e);
+            adjacency[edge.b].push(e);
+        }
+        Self {
+            pauli,
+            rounds,
+            num_data: code.num_data(),
+            num_checks: m,
+            edges,
+            adjacency,
+        }
+    }
+
+    pub fn pauli(&self) -> Pauli {
+        self.pauli
+    }
+
+    /// Detection events from per-round check outcomes and the syndrome of
+    /// the final data readout: each layer's change from the one before,
+    /// starting from all checks satisfied.
+    pub fn detections(&self, rounds: &[Vec<bool>], last: &[bool]) -> Vec<usize> {
+        let mut previous = vec![false; self.num_checks];
+        let mut events = Vec::new();
+        for (layer, outcomes) in rounds.iter().map(Vec::as_slice).chain([last]).enumerate() {
+            for c in 0..self.num_checks {
+                if outcomes[c] != previous[c] {
+                    events.push(layer * self.num_checks + c);
+                }
+            }
+            previous = outcomes.to_vec();
+        }
+        events
+    }
+
+    /// The data states to flip to explain `events`, as a mask over the
+    /// data.
+    pub fn decode(&self, events: &[usize]) -> Vec<bool> {
+        let boundary = (self.rounds + 1) * self.num_checks;
+        let trees: Vec<(Vec<f64>, Vec<Option<usize>>)> =
+            events.iter().map(|&e| self.shortest_paths(e)).collect();
+        let k = events.len();
+        let dist = |i: usize, j: usize| trees[i].0[events[j]];
+        let to_boundary = |i: usize| trees[i].0[boundary];
+        let pairs = if k <= MAX_EXACT {
+            match_exact(k, &dist, &to_boundary)
+        } else {
+            match_greedy(k, &dist, &to_boundary)
+        };
+        let mut correction = vec![false; self.num_data];
+        for (i, partner) in pairs {
+            let end = partner.map_or(boundary, |j| events[j]);
+            let parents = &trees[i].1;
+            let mut node = end;
+            while let Some(e) = parents[node] {
+                let edge = self.edges[e];
+                if let Some(q) = edge.data {
+                    correction[q] ^= true;
+                }
+                node = if edge.a == node { edge.b } else { edge.a };
+            }
+        }
+        correction
+    }
+
+    /// Dijkstra from `source`: distances and the edge into each node.
+    fn shortest_paths(&self, source: usize) -> (Vec<f64>, Vec<Option<usize>>) {
+        let n = self.adjacency.len();
+        let mut dist = vec![f64::INFINITY; n];
+        let mut parent = vec![None; n];
+        let mut heap = BinaryHeap::new();
+        dist[source] = 0.0;
+        heap.push(Reverse(source, 0.0));
+        while let Some(Reverse(node, d)) = heap.pop() {
+            if d > dist[node] {
+                continue;
+            }
+            for &e in &self.adjacency[node] {
+                let edge = self.edges[e];
+                let next = if edge.a == node { edge.b } else { edge.a };
+                let nd = d + edge.weight;
+                if nd < dist[next] {
+                    dist[next] = nd;
+                    parent[next] = Some(e);
+                    heap.push(Reverse(next, nd));
+                }
+            }
+        }
+        (dist, parent)
+    }
+}
+
+/// Heap entry ordered by smallest distance first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Reverse(usize, f64);
+
+impl Eq for Reverse {}
+
+impl PartialOrd for Reverse {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Reverse {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.1.total_cmp(&self.1)
+    }
+}
+
+/// Exact minimum-weight matching of `k` events, each paired with another
+/// or with the boundary, by dynamic programming over subsets.
+fn match_exact(
+    k: usize,
+    dist: &dyn Fn(usize, usize) -> f64,
+    to_boundary: &dyn Fn(usize) -> f64,
+) -> Vec<(usize, Option<usize>)> {
+    let full = (1usize << k) - 1;
+    // best[mask]: cheapest matching of the events in `mask`, and the
+    // partner chosen for its lowest event.
+    let mut best = vec![(0.0, None); full + 1];
+    for mask in 1..=full {
+        let i = mask.trailing_zeros() as usize;
+        let rest = mask & !(1 << i);
+        let mut choice = (to_boundary(i) + best[rest].0, None);
+        let mut others = rest;
+        while others != 0 {
+            let j = others.trailing_zeros() as usize;
+            others &= others - 1;
+            let cost = dist(i, j) + best[rest & !(1 << j)].0;
+            if cost < choice.0 {
+                choice = (cost, Some(j));
+            }
+        }
+        best[mask] = choice;
+    }
+    let mut pairs = Vec::new();
+    let mut mask = full;
+    while mask != 0 {
+        let i = mask.trailing_zeros() as usize;
+        let partner = best[mask].1;
+        mask &= !(1 << i);
+        if let Some(j) = partner {
+            mask &= !(1 << j);
+        }
+        pairs.push((i, partner));
+    }
+    pairs
+}
+
+/// Repeatedly take the cheapest remaining pair or boundary match.
+fn match_greedy(
+    k: usize,
+    dist: &dyn Fn(usize, usize) -> f64,
+    to_boundary: &dyn Fn(usize) -> f64,
+) -> Vec<(usize, Option<usize>)> {
+    let mut open: Vec<usize> = (0..k).collect();
+    let mut pairs = Vec::new();
+    while !open.is_empty() {
+        let mut choice = (f64::INFINITY, 0, None);
+        for (x, &i) in open.iter().enumerate() {
+            if to_boundary(i) < choice.0 {
+                choice = (to_boundary(i), x, None);
+            }
+            for (y, &j) in open.iter().enumerate().skip(x + 1) {
+                if dist(i, j) < choice.0 {
+                    choice = (dist(i, j), x, Some(y));
+                }
+            }
+        }
+        let (_, x, y) = choice;
+        let i = open[x];
+        let partner = y.map(|y| open[y]);
+        if let Some(y) = y {
+            open.remove(y);
+        }
+        open.remove(x);
+        pairs.push((i, partner));
+    }
+    pairs
+}
+
+/// Logical failures of a memory experiment at one noise strength.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogicalErrorRate {
+    /// Depolarizing probability per data state per round.
+    pub physical: f64,
+    /// Readout-flip probability per check measurement.
+    pub measurement: f64,
+    pub shots: usize,
+    pub failures: usize,
+}
+
+impl LogicalErrorRate {
+    /// Fraction of shots whose decoded logical value was wrong.
+    pub fn rate(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.shots as f64
+        }
+    }
+}
+
+impl Display for LogicalErrorRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p = {:.4}, q = {:.4}: {} / {} logical errors ({:.4})",
+            self.physical,
+            self.measurement,
+            self.failures,
+            self.shots,
+            self.rate()
+        )
+    }
+}
+
+/// Store a logical state through noisy rounds and count decoding
+/// failures.
+#[derive(Clone, Debug)]
+pub struct MemoryExperiment {
+    code: StabilizerCode,
+    basis: Pauli,
+    rounds: usize,
+    data_error: f64,
+    measurement_error: f64,
+    shots: usize,
+    seed: Option<u64>,
+}
+
+impl MemoryExperiment {
+    /// A noiseless, single-shot, one-round experiment storing logical
+    /// `|0⟩` (the Z basis).
+    pub fn new(code: StabilizerCode) -> Self {
+        Self {
+            code,
+            basis: Pauli::Z,
+            rounds: 1,
+            data_error: 0.0,
+            measurement_error: 0.0,
+            shots: 1,
+            seed: None,
+        }
+    }
+
+    /// Store logical `|0⟩` for `Pauli::Z` or `|+⟩` for `Pauli::X`. The
+    /// code needs checks of the same type; a repetition code only
+    /// protects the Z basis.
+    pub fn basis(mut self, basis: Pauli) -> Self {
+        self.basis = basis;
+        self
+    }
+
+    /// # Panics
+    /// If `rounds` is 0.
+    pub fn rounds(mut self, rounds: usize) -> Self {
+        assert!(rounds > 0, "a memory experiment needs at least one round");
+        self.rounds = rounds;
+        self
+    }
+
+    /// Depolarizing probability applied to each data state before every
+    /// round.
+    ///
+    /// # Panics
+    /// If `p` is not in `[0, 1]`.
+    pub fn data_error(mut self, p: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&p),
+            "data error probability must be in [0, 1]"
+        );
+        self.data_error = p;
+        self
+    }
+
+    /// Probability that each check outcome is read flipped.
+    ///
+    /// # Panics
+    /// If `q` is not in `[0, 1]`.
+    pub fn measurement_error(mut self, q: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "measurement error probability must be in [0, 1]"
+        );
+        self.measurement_error = q;
+        self
+    }
+
+    pub fn shots(mut self, shots: usize) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    /// Seed the generator so runs are reproducible.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn code(&self) -> &StabilizerCode {
+        &self.code
+    }
+
+    /// The experiment as a circuit. Bit `r·S + k` holds stabilizer `k`'s
+    /// outcome in round `r`, where `S` is the number of stabilizers; the
+    /// final data readout follows.
+    pub fn circuit(&self) -> Circuit {
+        let code = &self.code;
+        let s = code.stabilizers().len();
+        let data_bits = self.rounds * s;
+        let mut circuit = Circuit::new(code.num_states(), data_bits + code.num_data());
+        let idle = UnitaryGate::new(IDLE, Matrix::identity(2));
+        if self.basis == Pauli::X {
+            for q in 0..code.num_data() {
+                circuit.gate(H, &[q]);
+            }
+        }
+        for round in 0..self.rounds {
+            for q in 0..code.num_data() {
+                circuit.gate(idle.clone(), &[q]);
+            }
+            code.extraction(&mut circuit, round * s);
+        }
+        for q in 0..code.num_data() {
+            if self.basis == Pauli::X {
+                circuit.gate(H, &[q]);
+            }
+            circuit.push(Operation::Measure {
+                index: q,
+                bit: data_bits + q,
+            });
+        }
+        circuit
+    }
+
+    /// Data and readout noise, attached to the circuit's `idle` and
+    /// `readout` gates.
+    pub fn noise_model(&self) -> NoiseModel {
+        NoiseModel::new()
+            .with_gate(IDLE, KrausChannel::depolarizing(self.data_error))
+            .with_gate(READOUT, KrausChannel::bit_flip(self.measurement_error))
+    }
+
+    /// A decoder weighted for this experiment's noise. A depolarizing
+    /// channel of strength `p` flips a state in either basis with
+    /// probability `p/2`.
+    pub fn decoder(&self) -> MatchingDecoder {
+        MatchingDecoder::new(
+            &self.code,
+            self.basis,
+            self.rounds,
+            self.data_error / 2.0,
+            self.measurement_error,
+        )
+    }
+
+    /// True if decoding one shot's bits recovers the stored logical
+    /// value.
+    pub fn decodes(&self, decoder: &MatchingDecoder, bits: &[bool]) -> bool {
+        let code = &self.code;
+        let s = code.stabilizers().len();
+        let checks = code.checks(self.basis);
+        let rounds: Vec<Vec<bool>> = (0..self.rounds)
+            .map(|r| checks.iter().map(|&k| bits[r * s + k]).collect())
+            .collect();
+        let data = &bits[self.rounds * s..];
+        let last = code.syndrome(self.basis, data);
+        let correction = decoder.decode(&decoder.detections(&rounds, &last));
+        let corrected: Vec<bool> = data.iter().zip(&correction).map(|(d, c)| d ^ c).collect();
+        !parity(code.logical(self.basis), &corrected)
+    }
+
+    /// Run every shot and decode it.
+    ///
+    /// # Panics
+    /// If the code needs more than [`MAX_STATES`] register entries, or
+    /// has no checks of the experiment's basis and so could not detect
+    /// any error in it.
+    pub fn run(&self) -> LogicalErrorRate {
+        let code = &self.code;
+        assert!(
+            code.num_states() <= MAX_STATES,
+            "{} needs {} register entries; memory experiments simulate at most {}",
+            code.name(),
+            code.num_states(),
+            MAX_STATES
+        );
+        assert!(
+            !code.checks(self.basis).is_empty(),
+            "{} has no {} checks, so it cannot protect the {} basis",
+            code.name(),
+            self.basis,
+            self.basis
+        );
+        let mut executor = Executor::new(StateVectorBackend::new())
+            .shots(self.shots)
+            .noise(self.noise_model());
+        if let Some(seed) = self.seed {
+            executor = executor.seed(seed);
+        }
+        let result = executor.run(&self.circuit());
+        let decoder = self.decoder();
+        let failures = result
+            .shots
+            .iter()
+            .filter(|bits| !self.decodes(&decoder, bits))
+            .count();
+        LogicalErrorRate {
+            physical: self.data_error,
+            measurement: self.measurement_error,
+            shots: self.shots,
+            failures,
+        }
+    }
+
+    /// Logical error rate at each physical error rate, using it for both
+    /// data and readout noise.
+    ///
+    /// # Panics
+    /// If a rate is not in `[0, 1]`, or as [`run`](Self::run).
+    pub fn sweep(&self, physical: &[f64]) -> Vec<LogicalErrorRate> {
+        physical
+            .iter()
+            .map(|&p| self.clone().data_error(p).measurement_error(p).run())
+            .collect()
+    }
+}
@@ -0,0 +1,373 @@
+//! quasi_core.rs
+//! Quantum Superpositional Infrastructure (QUASI)
+//! Author: OBINexus / Namdi
+//! License: OBINexus Public License (OPL)
+//!
+//! Each QuasiState represents a quantum duality of matter/antimatter
+//! encoded in a topological “iceberg” model — visible surface and hidden depth.
+
+use std::fmt::{Display, Formatter};
+
+use crate::complex::Complex;
+use crate::error::{check_range, QuasiError, Result, MAX_MAGNITUDE};
+use crate::gate::Gate;
+use crate::matrix::Matrix;
+use crate::memory::qtoken::QToken;
+use crate::memory::qtype::{check_declared, Operation, QTypeRegistry};
+use crate::noise::KrausChannel;
+use crate::rng::{QuasiRng, SplitMix64};
+use crate::topology::field::QuasiField;
+
+/// One of the two outcomes a [`QuasiState`] can collapse into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Branch {
+    Matter,
+    Antimatter,
+}
+
+impl Display for Branch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Branch::Matter => write!(f, "matter"),
+            Branch::Antimatter => write!(f, "antimatter"),
+        }
+    }
+}
+
+/// Result of collapsing a state: the branch that was realised and its value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Observation {
+    pub branch: Branch,
+    pub value: f64,
+}
+
+/// How fallible transitions treat a state that has already collapsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransitionPolicy {
+    /// Collapsed states may be observed and inverted again.
+    #[default]
+    Lenient,
+    /// Observing or inverting a collapsed state is an error.
+    Strict,
+}
+
+/// The primary computational entity — a state existing in superposition.
+///
+/// With the `serde` feature it serializes through the versioned
+/// `schema` module's `StateDocument`.
+#[derive(Clone, Debug)]
+pub struct QuasiState {
+    pub id: String,
+    pub field: QuasiField,
+    pub observed: bool,
+}
+
+impl QuasiState {
+    /// Initialize a new superposed quantum state.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn new(id: &str, qtype: &str, matter: f64, antimatter: f64) -> Self {
+        if let Err(e) = check_declared(qtype) {
+            panic!("{}", e);
+        }
+        Self::unchecked(id, qtype, matter, antimatter)
+    }
+
+    /// [`new`](QuasiState::new) without the qtype check, for views of
+    /// register entries that carry no type.
+    pub(crate) fn unchecked(id: &str, qtype: &str, matter: f64, antimatter: f64) -> Self {
+        let field = QuasiField {
+            matter: QToken::unchecked(qtype, matter),
+            antimatter: QToken::unchecked(qtype, antimatter),
+            coherence: QuasiField::pure_coherence(matter.into(), antimatter.into()),
+        };
+        Self {
+            id: id.to_string(),
+            field,
+            observed: false,
+        }
+    }
+
+    /// Like [`new`](QuasiState::new), rejecting an empty id, an
+    /// undeclared qtype and values that are non-finite or beyond
+    /// [`MAX_MAGNITUDE`].
+    pub fn try_new(id: &str, qtype: &str, matter: f64, antimatter: f64) -> Result<Self> {
+        if id.is_empty() {
+            return Err(QuasiError::EmptyId);
+        }
+        check_declared(qtype)?;
+        check_range("matter", matter, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+        check_range("antimatter", antimatter, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+        Ok(Self::new(id, qtype, matter, antimatter))
+    }
+
+    /// Like [`try_new`](QuasiState::try_new), also requiring `qtype` to be
+    /// registered and both values to lie in its range.
+    pub fn typed(
+        registry: &QTypeRegistry,
+        id: &str,
+        qtype: &str,
+        matter: f64,
+        antimatter: f64,
+    ) -> Result<Self> {
+        let state = Self::try_new(id, qtype, matter, antimatter)?;
+        registry.check_field(&state.field)?;
+        Ok(state)
+    }
+
+    /// Like [`from_amplitudes`](QuasiState::from_amplitudes), with the
+    /// checks of [`try_new`](QuasiState::try_new) on every component.
+    pub fn try_from_amplitudes(
+        id: &str,
+        qtype: &str,
+        matter: Complex,
+        antimatter: Complex,
+    ) -> Result<Self> {
+        if id.is_empty() {
+            return Err(QuasiError::EmptyId);
+        }
+        check_declared(qtype)?;
+        for (field, z) in [("matter", matter), ("antimatter", antimatter)] {
+            check_range(field, z.re, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+            check_range(field, z.im, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+        }
+        Ok(Self::from_amplitudes(id, qtype, matter, antimatter))
+    }
+
+    /// Check the id and field, e.g. after editing public fields directly.
+    pub fn validate(&self) -> Result<()> {
+        if self.id.is_empty() {
+            return Err(QuasiError::EmptyId);
+        }
+        self.field.validate()
+    }
+
+    /// Initialize a superposed state in amplitude mode.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn from_amplitudes(id: &str, qtype: &str, matter: Complex, antimatter: Complex) -> Self {
+        Self {
+            id: id.to_string(),
+            field: QuasiField::from_amplitudes(qtype, matter, antimatter),
+            observed: false,
+        }
+    }
+
+    /// `[matter, antimatter]` as complex amplitudes.
+    pub fn amplitudes(&self) -> [Complex; 2] {
+        self.field.amplitudes()
+    }
+
+    /// Coherently superpose `other` onto this state.
+    ///
+    /// Amplitudes are summed branch by branch, so equal phases reinforce
+    /// and opposite phases cancel. The result keeps this state's id and
+    /// type and is unobserved.
+    pub fn interfere(&self, other: &QuasiState) -> QuasiState {
+        let [m1, a1] = self.amplitudes();
+        let [m2, a2] = other.amplitudes();
+        QuasiState::from_amplitudes(&self.id, &self.field.matter.qtype, m1 + m2, a1 + a2)
+    }
+
+    /// [`interfere`](QuasiState::interfere) for registered types: both
+    /// states must allow interference and have compatible types, and the
+    /// result must stay in range.
+    pub fn interfere_typed(
+        &self,
+        registry: &QTypeRegistry,
+        other: &QuasiState,
+    ) -> Result<QuasiState> {
+        registry.check_operation(self, Operation::Interfere)?;
+        registry.check_operation(other, Operation::Interfere)?;
+        registry.check_compatible(&self.field.matter.qtype, &other.field.matter.qtype)?;
+        let state = self.interfere(other);
+        registry.check_field(&state.field)?;
+        Ok(state)
+    }
+
+    /// Collapse the quantum superposition — observation defines truth.
+    ///
+    /// Draws from a freshly seeded generator; use [`observe_with`] for
+    /// reproducible experiments.
+    ///
+    /// [`observe_with`]: QuasiState::observe_with
+    pub fn observe(&mut self) -> Observation {
+        self.observe_with(&mut SplitMix64::from_entropy())
+    }
+
+    /// Collapse using the supplied generator.
+    ///
+    /// The branch is sampled from the field's Born weights. The losing
+    /// token is zeroed and coherence drops to 0, so repeated observation
+    /// keeps returning the same outcome.
+    pub fn observe_with<R: QuasiRng + ?Sized>(&mut self, rng: &mut R) -> Observation {
+        let branch = self.sample_branch(rng);
+        self.collapse(branch)
+    }
+
+    /// Draw a branch from the Born weights without collapsing.
+    pub(crate) fn sample_branch<R: QuasiRng + ?Sized>(&self, rng: &mut R) -> Branch {
+        let (p_matter, _) = self.field.born_weights();
+        if rng.next_f64() < p_matter {
+            Branch::Matter
+        } else {
+            Branch::Antimatter
+        }
+    }
+
+    /// Collapse onto a known branch, as [`observe_with`] does after
+    /// sampling. Used to replay recorded outcomes.
+    ///
+    /// [`observe_with`]: QuasiState::observe_with
+    pub fn collapse(&mut self, branch: Branch) -> Observation {
+        let value = match branch {
+            Branch::Matter => {
+                self.field.antimatter.set_amplitude(Complex::ZERO);
+                self.field.matter.value
+            }
+            Branch::Antimatter => {
+                self.field.matter.set_amplitude(Complex::ZERO);
+                self.field.antimatter.value
+            }
+        };
+        self.field.coherence = 0.0;
+        self.observed = true;
+        Observation { branch, value }
+    }
+
+    /// Fallible [`observe`](QuasiState::observe).
+    pub fn try_observe(&mut self, policy: TransitionPolicy) -> Result<Observation> {
+        self.try_observe_with(policy, &mut SplitMix64::from_entropy())
+    }
+
+    /// Fallible [`observe_with`](QuasiState::observe_with).
+    ///
+    /// Fails on an invalid field, or on a collapsed state under
+    /// [`TransitionPolicy::Strict`]. The state is untouched on error.
+    pub fn try_observe_with<R: QuasiRng + ?Sized>(
+        &mut self,
+        policy: TransitionPolicy,
+        rng: &mut R,
+    ) -> Result<Observation> {
+        self.check_transition(policy)?;
+        Ok(self.observe_with(rng))
+    }
+
+    /// Fallible [`invert`](QuasiState::invert), with the same checks as
+    /// [`try_observe_with`](QuasiState::try_observe_with).
+    pub fn try_invert(&mut self, policy: TransitionPolicy) -> Result<()> {
+        self.check_transition(policy)?;
+        self.invert();
+        Ok(())
+    }
+
+    fn check_transition(&self, policy: TransitionPolicy) -> Result<()> {
+        self.validate()?;
+        if policy == TransitionPolicy::Strict && self.observed {
+            return Err(QuasiError::AlreadyObserved {
+                id: self.id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Measure current coherence (how stable the state is)
+    ///
+    /// This is `2|ρ₀₁|` of the state's density matrix.
+    pub fn measure_coherence(&self) -> f64 {
+        self.field.coherence
+    }
+
+    /// Perform a quantum inversion — swap matter ↔ antimatter
+    ///
+    /// Equivalent to applying [`gate::X`](crate::gate::X).
+    pub fn invert(&mut self) {
+        std::mem::swap(&mut self.field.matter, &mut self.field.antimatter);
+    }
+
+    /// Apply a single-state gate.
+    ///
+    /// Pure states transform their amplitudes directly; partially
+    /// decohered states transform their density matrix as `UρU†`.
+    ///
+    /// Panics if `gate` acts on more than one state; use a
+    /// [`QuasiRegister`](crate::register::QuasiRegister) for those.
+    pub fn apply(&mut self, gate: &dyn Gate) {
+        assert_eq!(
+            gate.arity(),
+            1,
+            "gate `{}` is not single-state",
+            gate.name()
+        );
+        self.apply_unitary(&gate.matrix());
+    }
+
+    /// Apply a 2×2 unitary, through the density matrix when mixed.
+    pub(crate) fn apply_unitary(&mut self, u: &Matrix) {
+        if self.field.is_pure(1e-12) {
+            let out = u.apply(&self.amplitudes());
+            self.field.set_amplitudes([out[0], out[1]]);
+        } else {
+            let rho = &(u * &self.field.density_matrix()) * &u.dagger();
+            self.field.set_density(&rho);
+        }
+    }
+
+    /// Initialize a state from a 2×2 density matrix with unit scale.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn from_density(id: &str, qtype: &str, rho: &Matrix) -> Self {
+        let mut state = Self::new(id, qtype, 1.0, 0.0);
+        state.field.set_density(rho);
+        state
+    }
+
+    /// Normalized density matrix of the state.
+    pub fn density_matrix(&self) -> Matrix {
+        self.field.density_matrix()
+    }
+
+    /// Pass the state through a noise channel.
+    ///
+    /// The channel acts on the density matrix, so the loss shows up in
+    /// [`measure_coherence`](QuasiState::measure_coherence).
+    pub fn apply_channel(&mut self, channel: &KrausChannel) {
+        let rho = channel.apply_to_density(&self.field.density_matrix());
+        self.field.set_density(&rho);
+    }
+
+    /// Partially decohere: off-diagonal terms shrink by `1 - strength`.
+    pub fn decohere(&mut self, strength: f64) {
+        self.field.decohere(strength);
+    }
+}
+
+impl Display for QuasiState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let obs_state = if self.observed {
+            "Observed"
+        } else {
+            "Superposed"
+        };
+        let (matter, antimatter) = if self.field.is_amplitude_mode() {
+            let [m, a] = self.amplitudes();
+            (format!("{:.3}", m), format!("{:.3}", a))
+        } else {
+            (
+                format!("{:.3}", self.field.matter.value),
+                format!("{:.3}", self.field.antimatter.value),
+            )
+        };
+        write!(
+            f,
+            "🧊 QuasiState [{}]\nType: {}\nMatter: {}\nAntimatter: {}\nCoherence: {:.3}\nState: {}",
+            self.id, self.field.matter.qtype, matter, antimatter, self.field.coherence, obs_state
+        )
+    }
+}
@@ -0,0 +1,357 @@
+//! Multi-state registers — N quasi-states as one joint state vector.
+//!
+//! Basis index `b` addresses the joint branch in which state `k` is in
+//! antimatter when bit `k` of `b` is set and in matter otherwise, so the
+//! vector for N states holds `2^N` amplitudes.
+
+use crate::complex::Complex;
+use crate::error::Result;
+use crate::evolution::Hamiltonian;
+use crate::gate::Gate;
+use crate::matrix::Matrix;
+use crate::memory::qtype::QTypeRegistry;
+use crate::noise::KrausChannel;
+use crate::quasi_core::{Branch, Observation, QuasiState};
+use crate::rng::{QuasiRng, SplitMix64};
+
+/// A joint register of quasi-states.
+#[derive(Clone, Debug)]
+pub struct QuasiRegister {
+    pub(crate) ids: Vec<String>,
+    pub(crate) qtypes: Vec<String>,
+    pub(crate) scales: Vec<f64>,
+    pub(crate) observed: Vec<bool>,
+    amplitudes: Vec<Complex>,
+}
+
+impl QuasiRegister {
+    /// Register of `n` states, all in the matter branch.
+    pub fn new(n: usize) -> Self {
+        let mut amplitudes = vec![Complex::ZERO; 1 << n];
+        amplitudes[0] = Complex::ONE;
+        Self {
+            ids: (0..n).map(|k| format!("q{}", k)).collect(),
+            qtypes: vec![String::new(); n],
+            scales: vec![1.0; n],
+            observed: vec![false; n],
+            amplitudes,
+        }
+    }
+
+    /// Tensor product of the given states, in order.
+    ///
+    /// Each state's amplitudes are normalized before the product is
+    /// taken; its original norm is kept so observed values are reported
+    /// on the state's own scale.
+    pub fn from_states(states: &[QuasiState]) -> Self {
+        let mut register = Self {
+            ids: Vec::new(),
+            qtypes: Vec::new(),
+            scales: Vec::new(),
+            observed: Vec::new(),
+            amplitudes: vec![Complex::ONE],
+        };
+        for state in states {
+            register.push(state);
+        }
+        register
+    }
+
+    /// Append `state` as the new highest-index entry.
+    pub fn push(&mut self, state: &QuasiState) {
+        let norm = state.field.norm();
+        let [m, a] = if norm > 0.0 {
+            let [m, a] = state.amplitudes();
+            [m / norm, a / norm]
+        } else {
+            [Complex::ONE, Complex::ZERO]
+        };
+        let mut amplitudes = Vec::with_capacity(self.amplitudes.len() * 2);
+        amplitudes.extend(self.amplitudes.iter().map(|&z| z * m));
+        amplitudes.extend(self.amplitudes.iter().map(|&z| z * a));
+        self.amplitudes = amplitudes;
+        self.ids.push(state.id.clone());
+        self.qtypes.push(state.field.matter.qtype.clone());
+        self.scales.push(if norm > 0.0 { norm } else { 1.0 });
+        self.observed.push(state.observed);
+    }
+
+    /// Tensor product `self ⊗ other`; `other`'s entries follow `self`'s.
+    pub fn tensor(&self, other: &QuasiRegister) -> QuasiRegister {
+        let mut amplitudes = Vec::with_capacity(self.amplitudes.len() * other.amplitudes.len());
+        for &hi in &other.amplitudes {
+            amplitudes.extend(self.amplitudes.iter().map(|&lo| lo * hi));
+        }
+        let join = |a: &[String], b: &[String]| a.iter().chain(b).cloned().collect();
+        QuasiRegister {
+            ids: join(&self.ids, &other.ids),
+            qtypes: join(&self.qtypes, &other.qtypes),
+            scales: self.scales.iter().chain(&other.scales).copied().collect(),
+            observed: self
+                .observed
+                .iter()
+                .chain(&other.observed)
+                .copied()
+                .collect(),
+            amplitudes,
+        }
+    }
+
+    /// Number of states in the register.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Ids of the member states, by index.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Index of the state with the given id.
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|s| s == id)
+    }
+
+    /// The joint amplitudes, `2^N` of them.
+    pub fn amplitudes(&self) -> &[Complex] {
+        &self.amplitudes
+    }
+
+    /// Mutable joint amplitudes, for gate and channel implementations.
+    pub fn amplitudes_mut(&mut self) -> &mut [Complex] {
+        &mut self.amplitudes
+    }
+
+    /// Whether the state at `index` has been observed.
+    pub fn is_observed(&self, index: usize) -> bool {
+        self.observed[index]
+    }
+
+    /// Apply `gate` to the states at `targets`, in gate-target order.
+    ///
+    /// Panics if the number of targets differs from the gate's arity or a
+    /// target is repeated or out of range.
+    pub fn apply(&mut self, gate: &dyn Gate, targets: &[usize]) {
+        check_targets(self.len(), gate, targets);
+        apply_local(&gate.matrix(), targets, &mut self.amplitudes);
+    }
+
+    /// [`apply`](QuasiRegister::apply), first checking the targets' qtypes
+    /// against `registry`: each must allow gates, and all must be
+    /// compatible. States from [`new`](QuasiRegister::new) have no qtype
+    /// and are rejected.
+    pub fn apply_typed(
+        &mut self,
+        registry: &QTypeRegistry,
+        gate: &dyn Gate,
+        targets: &[usize],
+    ) -> Result<()> {
+        check_targets(self.len(), gate, targets);
+        let qtypes: Vec<&str> = targets.iter().map(|&t| self.qtypes[t].as_str()).collect();
+        registry.check_gate(gate, &qtypes)?;
+        apply_local(&gate.matrix(), targets, &mut self.amplitudes);
+        Ok(())
+    }
+
+    /// Unitary evolution of the whole register under `hamiltonian`.
+    ///
+    /// Relaxation needs a mixed state; evolve a
+    /// [`DensityMatrix`](crate::density::DensityMatrix) for T1/T2.
+    pub fn evolve(&mut self, hamiltonian: &Hamiltonian, t: f64) {
+        assert_eq!(
+            hamiltonian.arity(),
+            self.len(),
+            "Hamiltonian must act on the whole register"
+        );
+        let targets: Vec<usize> = (0..self.len()).collect();
+        apply_local(&hamiltonian.propagator(t), &targets, &mut self.amplitudes);
+    }
+
+    /// Pass the state at `index` through `channel` along one trajectory.
+    ///
+    /// A pure register cannot hold a mixture, so one Kraus operator is
+    /// sampled with probability `‖Kᵢψ‖²` and applied; averaged over many
+    /// shots this reproduces the channel.
+    pub fn apply_channel_with<R: QuasiRng + ?Sized>(
+        &mut self,
+        channel: &KrausChannel,
+        index: usize,
+        rng: &mut R,
+    ) {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let total: f64 = self.amplitudes.iter().map(|z| z.norm_sqr()).sum();
+        let mut draw = rng.next_f64() * total;
+        let operators = channel.operators();
+        for (i, k) in operators.iter().enumerate() {
+            let mut branch = self.amplitudes.clone();
+            apply_local(k, &[index], &mut branch);
+            let weight: f64 = branch.iter().map(|z| z.norm_sqr()).sum();
+            if draw < weight || i + 1 == operators.len() {
+                if weight > 0.0 {
+                    let norm = (weight / total).sqrt();
+                    self.amplitudes = branch.into_iter().map(|z| z / norm).collect();
+                }
+                return;
+            }
+            draw -= weight;
+        }
+    }
+
+    /// Joint Born probabilities of every basis branch.
+    pub fn probabilities(&self) -> Vec<f64> {
+        let total: f64 = self.amplitudes.iter().map(|z| z.norm_sqr()).sum();
+        self.amplitudes
+            .iter()
+            .map(|z| z.norm_sqr() / total)
+            .collect()
+    }
+
+    /// Marginal `(p_matter, p_antimatter)` of the state at `index`.
+    pub fn marginal(&self, index: usize) -> (f64, f64) {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let mask = 1usize << index;
+        let (mut p0, mut p1) = (0.0, 0.0);
+        for (b, z) in self.amplitudes.iter().enumerate() {
+            if b & mask == 0 {
+                p0 += z.norm_sqr();
+            } else {
+                p1 += z.norm_sqr();
+            }
+        }
+        let total = p0 + p1;
+        if total > 0.0 {
+            (p0 / total, p1 / total)
+        } else {
+            (0.5, 0.5)
+        }
+    }
+
+    /// Observe the state at `index` with a freshly seeded generator.
+    pub fn observe(&mut self, index: usize) -> Observation {
+        self.observe_with(index, &mut SplitMix64::from_entropy())
+    }
+
+    /// Observe the state at `index`, collapsing the joint vector.
+    ///
+    /// Branches inconsistent with the outcome are zeroed and the rest
+    /// renormalized, so the marginals of every other index update. The
+    /// reported value is the chosen branch's token value in the reduced
+    /// view ([`state`](Self::state)) taken before collapse, so like
+    /// [`QuasiState::observe`] it keeps the branch's sign relative to
+    /// matter.
+    pub fn observe_with<R: QuasiRng + ?Sized>(&mut self, index: usize, rng: &mut R) -> Observation {
+        let (p0, _) = self.marginal(index);
+        let view = self.state(index);
+        let (branch, token, bit) = if rng.next_f64() < p0 {
+            (Branch::Matter, &view.field.matter, 0)
+        } else {
+            (Branch::Antimatter, &view.field.antimatter, 1)
+        };
+        self.collapse(index, bit);
+        self.observed[index] = true;
+        Observation {
+            branch,
+            value: token.value,
+        }
+    }
+
+    /// Project the state at `index` onto `bit` and renormalize.
+    pub(crate) fn collapse(&mut self, index: usize, bit: usize) {
+        let mask = 1usize << index;
+        let mut total = 0.0;
+        for (b, z) in self.amplitudes.iter_mut().enumerate() {
+            if (b & mask != 0) as usize != bit {
+                *z = Complex::ZERO;
+            } else {
+                total += z.norm_sqr();
+            }
+        }
+        if total > 0.0 {
+            let norm = total.sqrt();
+            for z in &mut self.amplitudes {
+                *z = *z / norm;
+            }
+        }
+    }
+
+    /// Reduced 2×2 density matrix of the state at `index`.
+    pub fn reduced_density(&self, index: usize) -> Matrix {
+        assert!(index < self.len(), "register index {} out of range", index);
+        let mask = 1usize << index;
+        let mut rho = Matrix::zeros(2);
+        let mut total = 0.0;
+        for (b, &z) in self.amplitudes.iter().enumerate() {
+            total += z.norm_sqr();
+            if b & mask == 0 {
+                let w = self.amplitudes[b | mask];
+                rho[(0, 0)] += Complex::real(z.norm_sqr());
+                rho[(1, 1)] += Complex::real(w.norm_sqr());
+                rho[(0, 1)] += z * w.conj();
+            }
+        }
+        rho[(1, 0)] = rho[(0, 1)].conj();
+        if total > 0.0 {
+            rho = rho.scale(Complex::real(1.0 / total));
+        }
+        rho
+    }
+
+    /// Reduced view of the state at `index` on its original scale.
+    ///
+    /// Entanglement with the rest of the register shows up as lost
+    /// coherence in the view.
+    pub fn state(&self, index: usize) -> QuasiState {
+        let mut state = QuasiState::unchecked(
+            &self.ids[index],
+            &self.qtypes[index],
+            self.scales[index],
+            0.0,
+        );
+        state.field.set_density(&self.reduced_density(index));
+        state.observed = self.observed[index];
+        state
+    }
+}
+
+/// Panic unless `targets` suits `gate` on a register of `n` states.
+pub(crate) fn check_targets(n: usize, gate: &dyn Gate, targets: &[usize]) {
+    assert_eq!(
+        targets.len(),
+        gate.arity(),
+        "gate `{}` expects {} targets",
+        gate.name(),
+        gate.arity()
+    );
+    for (i, &t) in targets.iter().enumerate() {
+        assert!(t < n, "register index {} out of range", t);
+        assert!(!targets[..i].contains(&t), "repeated gate target {}", t);
+    }
+}
+
+/// Apply the local operator `op` to `targets` of a joint amplitude vector.
+pub(crate) fn apply_local(op: &Matrix, targets: &[usize], amplitudes: &mut [Complex]) {
+    let mask: usize = targets.iter().map(|&t| 1usize << t).sum();
+    let mut local = vec![Complex::ZERO; op.dim()];
+    for base in 0..amplitudes.len() {
+        if base & mask != 0 {
+            continue;
+        }
+        let index = |l: usize| {
+            targets
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| l >> j & 1 == 1)
+                .fold(base, |b, (_, &t)| b | 1 << t)
+        };
+        for (l, z) in local.iter_mut().enumerate() {
+            *z = amplitudes[index(l)];
+        }
+        for (l, z) in op.apply(&local).into_iter().enumerate() {
+            amplitudes[index(l)] = z;
+        }
+    }
+}
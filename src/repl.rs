@@ -0,0 +1,421 @@
+//! Interactive session over named quasi-states.
+//!
+//! A [`Session`] holds states by id and executes one command line at a
+//! time, returning the text to print. It knows nothing about terminals;
+//! the `quasi repl` command adds line editing, history and completion
+//! on top of [`Session::execute`] and [`Session::complete`].
+//!
+//! ```text
+//! new <id> <matter> <antimatter> [qtype]   create a state
+//! show [id]                                print one state, or all of them
+//! list                                     list state ids
+//! invert <id>                              swap matter and antimatter
+//! apply <gate>[(angles)] <id>              apply a one-state gate, e.g. `apply rx(pi/2) a`
+//! observe <id>                             collapse a state
+//! coherence <id>                           print a state's coherence
+//! drop <id>                                forget a state
+//! seed <n>                                 reseed the observation RNG
+//! :save <file>                             write every state as a snapshot
+//! :load <file>                             read a snapshot, replacing states with the same id
+//! :help                                    list commands
+//! :quit                                    leave the shell
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+use crate::error::QuasiError;
+use crate::gate;
+use crate::memory::snapshot::{self, SnapshotError};
+use crate::qasm;
+use crate::quasi_core::QuasiState;
+use crate::rng::SplitMix64;
+
+/// Command help, as printed by `:help`.
+pub const HELP: &str = "\
+new <id> <matter> <antimatter> [qtype]   create a state
+show [id]                                print one state, or all of them
+list                                     list state ids
+invert <id>                              swap matter and antimatter
+apply <gate>[(angles)] <id>              apply a one-state gate, e.g. `apply rx(pi/2) a`
+observe <id>                             collapse a state
+coherence <id>                           print a state's coherence
+drop <id>                                forget a state
+seed <n>                                 reseed the observation RNG
+:save <file>                             write every state as a snapshot
+:load <file>                             read a snapshot, replacing states with the same id
+:help                                    list commands
+:quit                                    leave the shell";
+
+/// Every command word, in completion order.
+pub const COMMANDS: [&str; 13] = [
+    "new",
+    "show",
+    "list",
+    "invert",
+    "apply",
+    "observe",
+    "coherence",
+    "drop",
+    "seed",
+    ":save",
+    ":load",
+    ":help",
+    ":quit",
+];
+
+/// One-state gate mnemonics accepted by `apply`, in [`gate::STANDARD`] order.
+fn gate_names() -> impl Iterator<Item = &'static str> {
+    gate::STANDARD.iter().filter_map(|&(name, angles)| {
+        gate::standard(name, &vec![0.0; angles])
+            .filter(|gate| gate.arity() == 1)
+            .map(|_| name)
+    })
+}
+
+/// Why a command line could not be executed.
+#[derive(Debug)]
+pub enum ReplError {
+    /// Malformed command line.
+    Usage(String),
+    UnknownState(String),
+    DuplicateState(String),
+    Quasi(QuasiError),
+    Snapshot(SnapshotError),
+}
+
+impl Display for ReplError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplError::Usage(message) => write!(f, "{}", message),
+            ReplError::UnknownState(id) => write!(f, "no state named `{}`", id),
+            ReplError::DuplicateState(id) => {
+                write!(f, "state `{}` already exists (drop it first)", id)
+            }
+            ReplError::Quasi(e) => write!(f, "{}", e),
+            ReplError::Snapshot(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ReplError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplError::Quasi(e) => Some(e),
+            ReplError::Snapshot(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<QuasiError> for ReplError {
+    fn from(e: QuasiError) -> Self {
+        ReplError::Quasi(e)
+    }
+}
+
+impl From<SnapshotError> for ReplError {
+    fn from(e: SnapshotError) -> Self {
+        ReplError::Snapshot(e)
+    }
+}
+
+/// What the caller should do after a command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reply {
+    /// Print this text (possibly empty).
+    Text(String),
+    /// End the session.
+    Quit,
+}
+
+/// Named states plus the RNG used to observe them.
+#[derive(Debug)]
+pub struct Session {
+    states: BTreeMap<String, QuasiState>,
+    rng: SplitMix64,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// An empty session seeded from entropy.
+    pub fn new() -> Self {
+        Self::with_rng(SplitMix64::from_entropy())
+    }
+
+    /// An empty session with a fixed seed, for reproducible observations.
+    pub fn seeded(seed: u64) -> Self {
+        Self::with_rng(SplitMix64::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: SplitMix64) -> Self {
+        Self {
+            states: BTreeMap::new(),
+            rng,
+        }
+    }
+
+    /// State ids in sorted order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.states.keys().map(String::as_str)
+    }
+
+    pub fn state(&self, id: &str) -> Option<&QuasiState> {
+        self.states.get(id)
+    }
+
+    /// Run one command line. Blank lines and `#` comments do nothing.
+    pub fn execute(&mut self, line: &str) -> Result<Reply, ReplError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Reply::Text(String::new()));
+        }
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        let text = match command {
+            "new" => self.new_state(&args)?,
+            "show" => match args.as_slice() {
+                [] => self
+                    .states
+                    .values()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+                [id] => self.get(id)?.to_string(),
+                _ => return Err(usage("show [id]")),
+            },
+            "list" => {
+                expect(&args, 0, "list")?;
+                self.ids().collect::<Vec<_>>().join("\n")
+            }
+            "invert" => {
+                let [id] = expect(&args, 1, "invert <id>")? else {
+                    unreachable!()
+                };
+                let state = self.get_mut(id)?;
+                state.invert();
+                state.to_string()
+            }
+            "apply" => self.apply(rest)?,
+            "observe" => {
+                let [id] = expect(&args, 1, "observe <id>")? else {
+                    unreachable!()
+                };
+                let state = self
+                    .states
+                    .get_mut(*id)
+                    .ok_or_else(|| ReplError::UnknownState(id.to_string()))?;
+                let outcome = state.observe_with(&mut self.rng);
+                format!("{} ({:.3})\n{}", outcome.branch, outcome.value, state)
+            }
+            "coherence" => {
+                let [id] = expect(&args, 1, "coherence <id>")? else {
+                    unreachable!()
+                };
+                format!("{:.6}", self.get(id)?.measure_coherence())
+            }
+            "drop" => {
+                let [id] = expect(&args, 1, "drop <id>")? else {
+                    unreachable!()
+                };
+                self.states
+                    .remove(*id)
+                    .ok_or_else(|| ReplError::UnknownState(id.to_string()))?;
+                String::new()
+            }
+            "seed" => {
+                let [seed] = expect(&args, 1, "seed <n>")? else {
+                    unreachable!()
+                };
+                let seed = seed
+                    .parse()
+                    .map_err(|_| ReplError::Usage(format!("invalid seed `{}`", seed)))?;
+                self.rng = SplitMix64::seed_from_u64(seed);
+                String::new()
+            }
+            ":save" => {
+                let path = file_argument(rest, ":save <file>")?;
+                let file = File::create(path).map_err(SnapshotError::from)?;
+                let mut writer =
+                    snapshot::write_snapshot(BufWriter::new(file), self.states.values())?;
+                writer.flush().map_err(SnapshotError::from)?;
+                format!(
+                    "saved {} state{}",
+                    self.states.len(),
+                    plural(self.states.len())
+                )
+            }
+            ":load" => {
+                let path = file_argument(rest, ":load <file>")?;
+                let file = File::open(path).map_err(SnapshotError::from)?;
+                let loaded = snapshot::read_snapshot(BufReader::new(file))?;
+                let count = loaded.len();
+                for state in loaded {
+                    self.states.insert(state.id.clone(), state);
+                }
+                format!("loaded {} state{}", count, plural(count))
+            }
+            ":help" | "help" => HELP.to_string(),
+            ":quit" | ":q" | "quit" | "exit" => return Ok(Reply::Quit),
+            other => {
+                return Err(ReplError::Usage(format!(
+                    "unknown command `{}` (try :help)",
+                    other
+                )))
+            }
+        };
+        Ok(Reply::Text(text))
+    }
+
+    /// Completion candidates for the word ending at byte `pos` of `line`:
+    /// command words first, gate names after `apply`, state ids elsewhere.
+    /// Returns the byte offset where the word starts and the candidates.
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let head = &line[..pos];
+        let start = head
+            .rfind(char::is_whitespace)
+            .map(|i| i + head[i..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(0);
+        let prefix = &head[start..];
+        let words: Vec<&str> = head[..start].split_whitespace().collect();
+        let candidates: Vec<String> = match words.as_slice() {
+            [] => matching(COMMANDS.iter().copied(), prefix),
+            [":save" | ":load", ..] => Vec::new(),
+            ["apply"] => matching(gate_names(), prefix),
+            ["new", ..] => Vec::new(),
+            _ => matching(self.ids(), prefix),
+        };
+        (start, candidates)
+    }
+
+    fn new_state(&mut self, args: &[&str]) -> Result<String, ReplError> {
+        let (id, matter, antimatter, qtype) = match *args {
+            [id, matter, antimatter] => (id, matter, antimatter, "energy"),
+            [id, matter, antimatter, qtype] => (id, matter, antimatter, qtype),
+            _ => return Err(usage("new <id> <matter> <antimatter> [qtype]")),
+        };
+        if self.states.contains_key(id) {
+            return Err(ReplError::DuplicateState(id.to_string()));
+        }
+        let number = |name: &str, v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| ReplError::Usage(format!("{} must be a number, got `{}`", name, v)))
+        };
+        let state = QuasiState::try_new(
+            id,
+            qtype,
+            number("matter", matter)?,
+            number("antimatter", antimatter)?,
+        )?;
+        let text = state.to_string();
+        self.states.insert(id.to_string(), state);
+        Ok(text)
+    }
+
+    /// `apply <gate>[(angles)] <id>`; the angle list may contain spaces.
+    fn apply(&mut self, rest: &str) -> Result<String, ReplError> {
+        const USAGE: &str = "apply <gate>[(angles)] <id>";
+        let (name, params, tail) = match rest.find('(') {
+            Some(open) if !rest[..open].contains(char::is_whitespace) => {
+                let close = rest[open..]
+                    .find(')')
+                    .map(|i| open + i)
+                    .ok_or_else(|| usage(USAGE))?;
+                let params = rest[open + 1..close]
+                    .split(',')
+                    .map(|text| {
+                        qasm::eval_angle(text.trim()).ok_or_else(|| {
+                            ReplError::Usage(format!("invalid angle `{}`", text.trim()))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                (&rest[..open], params, &rest[close + 1..])
+            }
+            _ => match rest.split_once(char::is_whitespace) {
+                Some((name, tail)) => (name, Vec::new(), tail),
+                None => return Err(usage(USAGE)),
+            },
+        };
+        let words: Vec<&str> = tail.split_whitespace().collect();
+        let [id] = expect(&words, 1, USAGE)? else {
+            unreachable!()
+        };
+        let gate = gate::standard(name, &params).ok_or_else(|| {
+            ReplError::Usage(format!(
+                "unknown gate `{}` with {} angle{}",
+                name,
+                params.len(),
+                plural(params.len())
+            ))
+        })?;
+        if gate.arity() != 1 {
+            return Err(ReplError::Usage(format!(
+                "`{}` acts on {} states; the shell applies one-state gates",
+                name,
+                gate.arity()
+            )));
+        }
+        let state = self.get_mut(id)?;
+        state.apply(gate.as_ref());
+        Ok(state.to_string())
+    }
+
+    fn get(&self, id: &str) -> Result<&QuasiState, ReplError> {
+        self.states
+            .get(id)
+            .ok_or_else(|| ReplError::UnknownState(id.to_string()))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut QuasiState, ReplError> {
+        self.states
+            .get_mut(id)
+            .ok_or_else(|| ReplError::UnknownState(id.to_string()))
+    }
+}
+
+fn usage(form: &str) -> ReplError {
+    ReplError::Usage(format!("usage: {}", form))
+}
+
+/// Exactly `n` arguments, or a usage error quoting `form`.
+fn expect<'a, 'b>(args: &'a [&'b str], n: usize, form: &str) -> Result<&'a [&'b str], ReplError> {
+    if args.len() == n {
+        Ok(args)
+    } else {
+        Err(usage(form))
+    }
+}
+
+/// The rest of the line as a path, so file names may contain spaces.
+fn file_argument<'a>(rest: &'a str, form: &str) -> Result<&'a str, ReplError> {
+    if rest.is_empty() {
+        Err(usage(form))
+    } else {
+        Ok(rest)
+    }
+}
+
+fn matching<'a>(words: impl Iterator<Item = &'a str>, prefix: &str) -> Vec<String> {
+    words
+        .filter(|w| w.starts_with(prefix))
+        .map(str::to_string)
+        .collect()
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
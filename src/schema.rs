@@ -0,0 +1,135 @@
+//! Versioned JSON schema for quasi-states (requires the `serde` feature).
+//!
+//! A [`QuasiState`] serializes as a flat, versioned document:
+//!
+//! ```
+//! # use quasi::QuasiState;
+//! let state = QuasiState::new("iceberg_01", "energy", 42.0, -41.8);
+//! assert_eq!(
+//!     serde_json::to_value(&state).unwrap(),
+//!     serde_json::json!({
+//!         "version": 1,
+//!         "id": "iceberg_01",
+//!         "qtype": "energy",
+//!         "matter": 42.0,
+//!         "antimatter": -41.8,
+//!         "coherence": 0.9999886080131236,
+//!         "observed": false
+//!     })
+//! );
+//! ```
+//!
+//! `matter_phase` and `antimatter_phase` (radians) are added only for
+//! states in amplitude mode. Unknown fields are ignored so newer writers
+//! stay readable; documents with a newer `version`, or the invalid
+//! version 0, are rejected.
+//!
+//! Values are written with full `f64` precision; reading them back
+//! bit-for-bit through `serde_json` needs its `float_roundtrip` feature.
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::QuasiError;
+use crate::memory::qtoken::QToken;
+use crate::quasi_core::QuasiState;
+use crate::topology::field::QuasiField;
+
+/// Version written by this build and the newest one it reads.
+pub const STATE_SCHEMA_VERSION: u32 = 1;
+
+/// The on-the-wire shape of a [`QuasiState`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateDocument {
+    pub version: u32,
+    pub id: String,
+    pub qtype: String,
+    pub matter: f64,
+    pub antimatter: f64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub matter_phase: f64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub antimatter_phase: f64,
+    pub coherence: f64,
+    pub observed: bool,
+}
+
+fn is_zero(x: &f64) -> bool {
+    *x == 0.0
+}
+
+impl TryFrom<&QuasiState> for StateDocument {
+    type Error = QuasiError;
+
+    /// Fails with [`QuasiError::IncompatibleQTypes`] when the two tokens
+    /// carry different qtypes, which the flat schema cannot represent.
+    fn try_from(state: &QuasiState) -> Result<Self, QuasiError> {
+        let QuasiField {
+            matter,
+            antimatter,
+            coherence,
+        } = &state.field;
+        if matter.qtype != antimatter.qtype {
+            return Err(QuasiError::IncompatibleQTypes {
+                a: matter.qtype.clone(),
+                b: antimatter.qtype.clone(),
+            });
+        }
+        Ok(Self {
+            version: STATE_SCHEMA_VERSION,
+            id: state.id.clone(),
+            qtype: matter.qtype.clone(),
+            matter: matter.value,
+            antimatter: antimatter.value,
+            matter_phase: matter.phase,
+            antimatter_phase: antimatter.phase,
+            coherence: *coherence,
+            observed: state.observed,
+        })
+    }
+}
+
+impl TryFrom<StateDocument> for QuasiState {
+    type Error = QuasiError;
+
+    fn try_from(doc: StateDocument) -> Result<Self, QuasiError> {
+        if doc.version == 0 || doc.version > STATE_SCHEMA_VERSION {
+            return Err(QuasiError::UnsupportedVersion {
+                found: doc.version,
+                supported: STATE_SCHEMA_VERSION,
+            });
+        }
+        let token = |value, phase| QToken {
+            qtype: doc.qtype.clone(),
+            value,
+            phase,
+        };
+        let state = QuasiState {
+            id: doc.id.clone(),
+            field: QuasiField {
+                matter: token(doc.matter, doc.matter_phase),
+                antimatter: token(doc.antimatter, doc.antimatter_phase),
+                coherence: doc.coherence,
+            },
+            observed: doc.observed,
+        };
+        state.validate()?;
+        Ok(state)
+    }
+}
+
+impl Serialize for QuasiState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StateDocument::try_from(self)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for QuasiState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let doc = StateDocument::deserialize(deserializer)?;
+        QuasiState::try_from(doc).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,409 @@
+//! Anyons and braid-group gates.
+//!
+//! In topological mode a [`QuasiState`](crate::QuasiState) is read as a
+//! qubit encoded in three anyons of one [`AnyonModel`]. The matter branch
+//! is the first pair fusing to the vacuum, the antimatter branch that
+//! pair fusing to the model's other channel (`τ` for Fibonacci anyons,
+//! `ψ` for Ising anyons). Exchanging neighbouring anyons acts on that
+//! fusion space: `σ₁` by the R-matrix, `σ₂` by `F·R·F`.
+//!
+//! A [`BraidWord`] is a product of these exchanges, and a [`BraidGate`]
+//! applies one like any other [`Gate`]. [`BraidCompiler`] searches braid
+//! words exhaustively for the best approximation of a target unitary.
+//! Ising braids only reach the Clifford group; Fibonacci braids are dense
+//! in SU(2), so longer searches keep improving.
+
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+
+use crate::complex::Complex;
+use crate::gate::Gate;
+use crate::matrix::Matrix;
+
+/// An anyon's topological charge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Charge {
+    /// The trivial charge, `1`.
+    Vacuum,
+    /// The Fibonacci anyon `τ`.
+    Tau,
+    /// The Ising anyon `σ`.
+    Sigma,
+    /// The Ising fermion `ψ`.
+    Psi,
+}
+
+impl Display for Charge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Charge::Vacuum => "1",
+            Charge::Tau => "τ",
+            Charge::Sigma => "σ",
+            Charge::Psi => "ψ",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// A family of anyons with its fusion rules and braiding data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnyonModel {
+    /// Charges `{1, τ}` with `τ × τ = 1 + τ`.
+    Fibonacci,
+    /// Charges `{1, σ, ψ}` with `σ × σ = 1 + ψ`, `σ × ψ = σ`, `ψ × ψ = 1`.
+    Ising,
+}
+
+impl AnyonModel {
+    pub fn charges(self) -> &'static [Charge] {
+        match self {
+            AnyonModel::Fibonacci => &[Charge::Vacuum, Charge::Tau],
+            AnyonModel::Ising => &[Charge::Vacuum, Charge::Sigma, Charge::Psi],
+        }
+    }
+
+    /// The charges `a × b` can fuse to; empty if either is not in the
+    /// model.
+    pub fn fuse(self, a: Charge, b: Charge) -> Vec<Charge> {
+        use Charge::*;
+        if !self.charges().contains(&a) || !self.charges().contains(&b) {
+            return Vec::new();
+        }
+        match (a, b) {
+            (Vacuum, x) | (x, Vacuum) => vec![x],
+            (Tau, Tau) => vec![Vacuum, Tau],
+            (Sigma, Sigma) => vec![Vacuum, Psi],
+            (Sigma, Psi) | (Psi, Sigma) => vec![Sigma],
+            (Psi, Psi) => vec![Vacuum],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The anyon a qubit is encoded in.
+    pub fn computational(self) -> Charge {
+        match self {
+            AnyonModel::Fibonacci => Charge::Tau,
+            AnyonModel::Ising => Charge::Sigma,
+        }
+    }
+
+    /// Fusion channels of the first pair: the vacuum for the matter
+    /// branch, then the antimatter branch's channel.
+    pub fn channels(self) -> [Charge; 2] {
+        let c = self.computational();
+        match self.fuse(c, c)[..] {
+            [a, b] => [a, b],
+            _ => unreachable!("computational anyons fuse two ways"),
+        }
+    }
+
+    /// Exchange phases of the first pair, one per channel.
+    pub fn r_matrix(self) -> Matrix {
+        match self {
+            AnyonModel::Fibonacci => {
+                Matrix::diagonal(&[Complex::cis(-4.0 * PI / 5.0), Complex::cis(3.0 * PI / 5.0)])
+            }
+            AnyonModel::Ising => {
+                Matrix::diagonal(&[Complex::cis(-PI / 8.0), Complex::cis(3.0 * PI / 8.0)])
+            }
+        }
+    }
+
+    /// Change of fusion basis from `(12)3` to `1(23)`; its own inverse.
+    pub fn f_matrix(self) -> Matrix {
+        match self {
+            AnyonModel::Fibonacci => {
+                let phi = (1.0 + 5f64.sqrt()) / 2.0;
+                let (a, b) = (1.0 / phi, phi.sqrt().recip());
+                Matrix::from_real([[a, b], [b, -a]])
+            }
+            AnyonModel::Ising => {
+                let h = std::f64::consts::FRAC_1_SQRT_2;
+                Matrix::from_real([[h, h], [h, -h]])
+            }
+        }
+    }
+
+    /// The unitary of `σ₁` or `σ₂`, inverted if `letter` is negative.
+    ///
+    /// # Panics
+    /// If `|letter|` is not 1 or 2.
+    pub fn generator(self, letter: i8) -> Matrix {
+        let r = self.r_matrix();
+        let m = match letter.unsigned_abs() {
+            1 => r,
+            2 => {
+                let f = self.f_matrix();
+                &(&f * &r) * &f
+            }
+            _ => panic!("three-anyon braids have generators 1 and 2, got {}", letter),
+        };
+        if letter < 0 {
+            m.dagger()
+        } else {
+            m
+        }
+    }
+}
+
+impl Display for AnyonModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyonModel::Fibonacci => write!(f, "fibonacci"),
+            AnyonModel::Ising => write!(f, "ising"),
+        }
+    }
+}
+
+/// A word in the three-strand braid group.
+///
+/// Letter `k` is `σₖ` and `-k` is `σₖ⁻¹`. Letters are applied left to
+/// right in time.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BraidWord {
+    letters: Vec<i8>,
+}
+
+impl BraidWord {
+    /// The empty braid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Panics
+    /// If a letter is not `±1` or `±2`.
+    pub fn from_letters(letters: &[i8]) -> Self {
+        assert!(
+            letters.iter().all(|l| matches!(l.unsigned_abs(), 1 | 2)),
+            "three-anyon braids have generators 1 and 2"
+        );
+        Self {
+            letters: letters.to_vec(),
+        }
+    }
+
+    /// Append a letter.
+    pub fn then(mut self, letter: i8) -> Self {
+        assert!(
+            matches!(letter.unsigned_abs(), 1 | 2),
+            "three-anyon braids have generators 1 and 2"
+        );
+        self.letters.push(letter);
+        self
+    }
+
+    pub fn letters(&self) -> &[i8] {
+        &self.letters
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// The braid undoing this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            letters: self.letters.iter().rev().map(|l| -l).collect(),
+        }
+    }
+
+    /// The word with adjacent `σₖσₖ⁻¹` pairs cancelled.
+    pub fn reduced(&self) -> Self {
+        let mut letters: Vec<i8> = Vec::with_capacity(self.len());
+        for &l in &self.letters {
+            if letters.last() == Some(&-l) {
+                letters.pop();
+            } else {
+                letters.push(l);
+            }
+        }
+        Self { letters }
+    }
+
+    /// The braid's action on the fusion space of `model`.
+    pub fn unitary(&self, model: AnyonModel) -> Matrix {
+        self.letters
+            .iter()
+            .fold(Matrix::identity(2), |u, &l| &model.generator(l) * &u)
+    }
+}
+
+impl Display for BraidWord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "ε");
+        }
+        for (i, l) in self.letters.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let index = if l.unsigned_abs() == 1 { "₁" } else { "₂" };
+            write!(f, "σ{}{}", index, if *l < 0 { "⁻¹" } else { "" })?;
+        }
+        Ok(())
+    }
+}
+
+/// A braid applied as a one-state gate.
+#[derive(Clone, Debug)]
+pub struct BraidGate {
+    pub model: AnyonModel,
+    pub word: BraidWord,
+    matrix: Matrix,
+}
+
+impl BraidGate {
+    pub fn new(model: AnyonModel, word: BraidWord) -> Self {
+        let matrix = word.unitary(model);
+        Self {
+            model,
+            word,
+            matrix,
+        }
+    }
+}
+
+impl Gate for BraidGate {
+    fn name(&self) -> &str {
+        "braid"
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn matrix(&self) -> Matrix {
+        self.matrix.clone()
+    }
+}
+
+/// Distance between two 2×2 unitaries that ignores global phase:
+/// `√(1 − |tr(U†V)|/2)`, 0 when they agree up to phase.
+pub fn phase_distance(u: &Matrix, v: &Matrix) -> f64 {
+    let overlap = (&u.dagger() * v).trace().abs() / 2.0;
+    (1.0 - overlap).max(0.0).sqrt()
+}
+
+/// The best braid found for a target.
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    pub word: BraidWord,
+    pub unitary: Matrix,
+    /// [`phase_distance`] from the target.
+    pub distance: f64,
+}
+
+/// Exhaustive search for braid words approximating a unitary.
+#[derive(Clone, Debug)]
+pub struct BraidCompiler {
+    model: AnyonModel,
+    max_length: usize,
+    tolerance: f64,
+}
+
+impl BraidCompiler {
+    /// Search words of up to 10 letters, stopping at distance `1e-9`.
+    pub fn new(model: AnyonModel) -> Self {
+        Self {
+            model,
+            max_length: 10,
+            tolerance: 1e-9,
+        }
+    }
+
+    /// Longest word to try. The search visits up to `4·3^(n−1)` words
+    /// of length `n`.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Stop as soon as a word is this close.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn model(&self) -> AnyonModel {
+        self.model
+    }
+
+    /// The shortest word closest to `target`, searched breadth-first.
+    ///
+    /// Words that reach a unitary already seen (up to phase) are not
+    /// extended, so finite braid images such as Ising's end the search
+    /// early.
+    ///
+    /// # Panics
+    /// If `target` is not a 2×2 unitary.
+    pub fn compile(&self, target: &Matrix) -> Compiled {
+        assert!(
+            target.dim() == 2 && target.is_unitary(1e-9),
+            "braid targets must be 2×2 unitaries"
+        );
+        let generators: Vec<(i8, Matrix)> = [1, -1, 2, -2]
+            .into_iter()
+            .map(|l| (l, self.model.generator(l)))
+            .collect();
+        let identity = Matrix::identity(2);
+        let mut best = Compiled {
+            word: BraidWord::new(),
+            distance: phase_distance(target, &identity),
+            unitary: identity.clone(),
+        };
+        let mut seen = HashSet::from([phase_key(&identity)]);
+        let mut frontier = vec![(BraidWord::new(), identity)];
+        for _ in 0..self.max_length {
+            if best.distance <= self.tolerance || frontier.is_empty() {
+                break;
+            }
+            let mut next = Vec::new();
+            for (word, u) in &frontier {
+                for (l, g) in &generators {
+                    if word.letters.last() == Some(&-l) {
+                        continue;
+                    }
+                    let v = g * u;
+                    if !seen.insert(phase_key(&v)) {
+                        continue;
+                    }
+                    let word = word.clone().then(*l);
+                    let distance = phase_distance(target, &v);
+                    if distance < best.distance {
+                        best = Compiled {
+                            word: word.clone(),
+                            unitary: v.clone(),
+                            distance,
+                        };
+                    }
+                    next.push((word, v));
+                }
+            }
+            frontier = next;
+        }
+        best
+    }
+}
+
+/// Hashable fingerprint of a 2×2 unitary up to global phase.
+fn phase_key(u: &Matrix) -> [i64; 8] {
+    // Rotate the largest first-column entry onto the positive reals.
+    let pivot = if u[(0, 0)].abs() >= u[(1, 0)].abs() {
+        u[(0, 0)]
+    } else {
+        u[(1, 0)]
+    };
+    let phase = pivot.conj().scale(1.0 / pivot.abs());
+    let mut key = [0; 8];
+    for (i, (r, c)) in [(0, 0), (0, 1), (1, 0), (1, 1)].into_iter().enumerate() {
+        let z = u[(r, c)] * phase;
+        key[2 * i] = (z.re * 1e8).round() as i64;
+        key[2 * i + 1] = (z.im * 1e8).round() as i64;
+    }
+    key
+}
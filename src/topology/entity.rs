@@ -0,0 +1,293 @@
+//! Bound composite structures.
+//!
+//! A [`QuasiEntity`] binds several [`QuasiState`]s under one identity and
+//! guards two invariants:
+//!
+//! * its **charge**, the total matter minus antimatter over all states, is
+//!   conserved — fixed when the entity is bound, shared out exactly by
+//!   [`split`](QuasiEntity::split) and summed by
+//!   [`merge`](QuasiEntity::merge);
+//! * its **joint coherence** ([`QuasiField::joint_coherence`] over every
+//!   state) never falls below the entity's minimum.
+//!
+//! Every operation checks both and leaves the entity untouched when it
+//! fails.
+
+use std::fmt::{Display, Formatter};
+
+use crate::error::{check_range, QuasiError};
+use crate::quasi_core::QuasiState;
+use crate::topology::field::QuasiField;
+
+/// Relative tolerance when comparing charges.
+const CHARGE_TOL: f64 = 1e-9;
+
+/// Why an entity operation was refused.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntityError {
+    /// An entity must hold at least one state.
+    Empty { entity: String },
+    /// Two states in one entity share an id.
+    DuplicateState { id: String },
+    /// The entity holds no state with this id.
+    UnknownState { id: String },
+    /// The operation would change the entity's charge.
+    ChargeNotConserved {
+        entity: String,
+        expected: f64,
+        actual: f64,
+    },
+    /// The joint coherence would fall below the entity's minimum.
+    Decoherent {
+        entity: String,
+        coherence: f64,
+        min: f64,
+    },
+    /// A state or parameter is invalid.
+    Quasi(QuasiError),
+}
+
+impl Display for EntityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntityError::Empty { entity } => write!(f, "entity `{}` would hold no states", entity),
+            EntityError::DuplicateState { id } => {
+                write!(f, "state `{}` appears more than once", id)
+            }
+            EntityError::UnknownState { id } => write!(f, "no bound state `{}`", id),
+            EntityError::ChargeNotConserved {
+                entity,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "entity `{}` must keep charge {} (would be {})",
+                entity, expected, actual
+            ),
+            EntityError::Decoherent {
+                entity,
+                coherence,
+                min,
+            } => write!(
+                f,
+                "entity `{}` would fall to joint coherence {:.4} (minimum {:.4})",
+                entity, coherence, min
+            ),
+            EntityError::Quasi(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for EntityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EntityError::Quasi(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<QuasiError> for EntityError {
+    fn from(e: QuasiError) -> Self {
+        EntityError::Quasi(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, EntityError>;
+
+/// Total matter minus antimatter over `states`.
+fn charge_of(states: &[QuasiState]) -> f64 {
+    states
+        .iter()
+        .map(|s| s.field.matter.value - s.field.antimatter.value)
+        .sum()
+}
+
+fn joint_coherence_of(states: &[QuasiState]) -> f64 {
+    QuasiField::joint_coherence(states.iter().map(|s| &s.field))
+}
+
+/// Several states bound under one identity.
+#[derive(Clone, Debug)]
+pub struct QuasiEntity {
+    pub id: String,
+    states: Vec<QuasiState>,
+    charge: f64,
+    min_coherence: f64,
+}
+
+impl QuasiEntity {
+    /// Bind `states` into an entity whose joint coherence must stay at or
+    /// above `min_coherence`. The states' current charge becomes the
+    /// conserved one.
+    pub fn bind(id: &str, states: Vec<QuasiState>, min_coherence: f64) -> Result<Self> {
+        if id.is_empty() {
+            return Err(QuasiError::EmptyId.into());
+        }
+        check_range("min coherence", min_coherence, 0.0, 1.0)?;
+        for (i, state) in states.iter().enumerate() {
+            state.validate()?;
+            if states[..i].iter().any(|s| s.id == state.id) {
+                return Err(EntityError::DuplicateState {
+                    id: state.id.clone(),
+                });
+            }
+        }
+        let entity = Self {
+            id: id.to_string(),
+            charge: charge_of(&states),
+            states,
+            min_coherence,
+        };
+        entity.check(&entity.states, entity.charge)?;
+        Ok(entity)
+    }
+
+    /// The conserved total matter minus antimatter.
+    pub fn charge(&self) -> f64 {
+        self.charge
+    }
+
+    pub fn min_coherence(&self) -> f64 {
+        self.min_coherence
+    }
+
+    /// Coherence of all bound fields taken together.
+    pub fn joint_coherence(&self) -> f64 {
+        joint_coherence_of(&self.states)
+    }
+
+    pub fn states(&self) -> &[QuasiState] {
+        &self.states
+    }
+
+    pub fn state(&self, id: &str) -> Option<&QuasiState> {
+        self.states.iter().find(|s| s.id == id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.state(id).is_some()
+    }
+
+    /// Check both invariants against the current states.
+    pub fn validate(&self) -> Result<()> {
+        self.check(&self.states, self.charge)
+    }
+
+    /// Change one bound state through `f`.
+    ///
+    /// The change is kept only if the state stays valid and both
+    /// invariants still hold.
+    pub fn transform<F: FnOnce(&mut QuasiState)>(&mut self, id: &str, f: F) -> Result<()> {
+        let index = self.index_of(id)?;
+        let mut states = self.states.clone();
+        f(&mut states[index]);
+        states[index].id = id.to_string();
+        states[index].validate()?;
+        self.check(&states, self.charge)?;
+        self.states = states;
+        Ok(())
+    }
+
+    /// Move the states named by `ids` into a new entity called `id`,
+    /// with the same minimum coherence.
+    ///
+    /// The charge is shared out exactly: the new entity takes its states'
+    /// charge and this one keeps the rest. Both parts must be non-empty
+    /// and coherent enough on their own.
+    pub fn split(&mut self, ids: &[&str], id: &str) -> Result<QuasiEntity> {
+        if id.is_empty() {
+            return Err(QuasiError::EmptyId.into());
+        }
+        for (i, name) in ids.iter().enumerate() {
+            self.index_of(name)?;
+            if ids[..i].contains(name) {
+                return Err(EntityError::DuplicateState {
+                    id: name.to_string(),
+                });
+            }
+        }
+        let (moved, kept): (Vec<_>, Vec<_>) = self
+            .states
+            .iter()
+            .cloned()
+            .partition(|s| ids.contains(&s.id.as_str()));
+        let part = Self {
+            id: id.to_string(),
+            charge: charge_of(&moved),
+            states: moved,
+            min_coherence: self.min_coherence,
+        };
+        part.validate()?;
+        let charge = self.charge - part.charge;
+        self.check(&kept, charge)?;
+        self.states = kept;
+        self.charge = charge;
+        Ok(part)
+    }
+
+    /// Absorb a copy of `other`'s states.
+    ///
+    /// The charges add, and the stricter of the two minimum coherences
+    /// applies to the merged entity. State ids must not overlap.
+    pub fn merge(&mut self, other: &QuasiEntity) -> Result<()> {
+        if let Some(s) = other.states.iter().find(|s| self.contains(&s.id)) {
+            return Err(EntityError::DuplicateState { id: s.id.clone() });
+        }
+        let mut merged = self.clone();
+        merged.states.extend(other.states.iter().cloned());
+        merged.charge += other.charge;
+        merged.min_coherence = self.min_coherence.max(other.min_coherence);
+        merged.validate()?;
+        *self = merged;
+        Ok(())
+    }
+
+    fn index_of(&self, id: &str) -> Result<usize> {
+        self.states
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| EntityError::UnknownState { id: id.to_string() })
+    }
+
+    /// Check `states` against `charge` and the minimum coherence.
+    fn check(&self, states: &[QuasiState], charge: f64) -> Result<()> {
+        if states.is_empty() {
+            return Err(EntityError::Empty {
+                entity: self.id.clone(),
+            });
+        }
+        let actual = charge_of(states);
+        if (actual - charge).abs() > CHARGE_TOL * charge.abs().max(1.0) {
+            return Err(EntityError::ChargeNotConserved {
+                entity: self.id.clone(),
+                expected: charge,
+                actual,
+            });
+        }
+        let coherence = joint_coherence_of(states);
+        if coherence < self.min_coherence {
+            return Err(EntityError::Decoherent {
+                entity: self.id.clone(),
+                coherence,
+                min: self.min_coherence,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Display for QuasiEntity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Entity {}: {} state{}, charge {}, joint coherence {:.4} (min {:.4})",
+            self.id,
+            self.states.len(),
+            if self.states.len() == 1 { "" } else { "s" },
+            self.charge,
+            self.joint_coherence(),
+            self.min_coherence
+        )
+    }
+}
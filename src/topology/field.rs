@@ -0,0 +1,431 @@
+//! Quantum field manifold mapping.
+//!
+//! A [`FieldManifold`] is a weighted graph of [`QuasiField`] nodes, each
+//! with a curvature. Coherence diffuses along edges by the discrete
+//! Laplacian while curvature damps (positive) or pumps (negative) it:
+//! `dcᵢ/dt = D·Σⱼ wᵢⱼ(cⱼ − cᵢ) − κᵢ·cᵢ`.
+
+use std::collections::BTreeMap;
+
+use crate::complex::Complex;
+use crate::error::{check_range, Result, MAX_MAGNITUDE};
+use crate::matrix::Matrix;
+use crate::memory::qtoken::QToken;
+use crate::memory::qtype::check_declared;
+
+/// Represents a dual field (matter ↔ antimatter) in topological space.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuasiField {
+    pub matter: QToken,
+    pub antimatter: QToken,
+    pub coherence: f64, // 0.0 - 1.0 range, quantum symmetry measure
+}
+
+impl QuasiField {
+    /// Build a field in amplitude mode from two complex amplitudes.
+    ///
+    /// # Panics
+    /// If `qtype` has not been [declared](crate::memory::qtype::declare).
+    pub fn from_amplitudes(qtype: &str, matter: Complex, antimatter: Complex) -> Self {
+        let mut field = Self {
+            matter: QToken::from_amplitude(qtype, matter),
+            antimatter: QToken::from_amplitude(qtype, antimatter),
+            coherence: 0.0,
+        };
+        field.refresh_coherence();
+        field
+    }
+
+    /// Coherence `2|m·a*| / (|m|² + |a|²)` of a pure amplitude pair.
+    ///
+    /// This is twice the off-diagonal magnitude of the pair's density
+    /// matrix — 1 for an even superposition, 0 for a single branch.
+    pub fn pure_coherence(matter: Complex, antimatter: Complex) -> f64 {
+        let total = matter.norm_sqr() + antimatter.norm_sqr();
+        if total > 0.0 {
+            (2.0 * (matter * antimatter.conj()).abs() / total).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Coherence `2|Σ nₖ·ρ₀₁ₖ| / Σ nₖ` of several fields taken together,
+    /// where `nₖ` is each field's squared norm. Fields with opposite
+    /// phases cancel; no fields, or only empty ones, give 0.
+    pub fn joint_coherence<'a>(fields: impl IntoIterator<Item = &'a QuasiField>) -> f64 {
+        let mut total = 0.0;
+        let mut off = Complex::ZERO;
+        for field in fields {
+            let weight = field.norm().powi(2);
+            total += weight;
+            off += field.density_matrix()[(0, 1)].scale(weight);
+        }
+        if total > 0.0 {
+            (2.0 * off.abs() / total).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Check that both qtypes are declared and that every token and the
+    /// coherence hold finite, in-range values.
+    pub fn validate(&self) -> Result<()> {
+        check_declared(&self.matter.qtype)?;
+        check_declared(&self.antimatter.qtype)?;
+        check_range("matter", self.matter.value, -MAX_MAGNITUDE, MAX_MAGNITUDE)?;
+        check_range(
+            "antimatter",
+            self.antimatter.value,
+            -MAX_MAGNITUDE,
+            MAX_MAGNITUDE,
+        )?;
+        check_range("matter phase", self.matter.phase, f64::MIN, f64::MAX)?;
+        check_range(
+            "antimatter phase",
+            self.antimatter.phase,
+            f64::MIN,
+            f64::MAX,
+        )?;
+        check_range("coherence", self.coherence, 0.0, 1.0)?;
+        Ok(())
+    }
+
+    /// Reset `coherence` to the pure-state value of the current amplitudes.
+    pub fn refresh_coherence(&mut self) {
+        let [m, a] = self.amplitudes();
+        self.coherence = Self::pure_coherence(m, a);
+    }
+
+    /// The largest coherence the current populations allow.
+    pub fn max_coherence(&self) -> f64 {
+        let [m, a] = self.amplitudes();
+        Self::pure_coherence(m, a)
+    }
+
+    /// True unless some coherence has been lost to decoherence.
+    pub fn is_pure(&self, tol: f64) -> bool {
+        self.coherence >= self.max_coherence() - tol
+    }
+
+    /// Normalized 2×2 density matrix of the field.
+    ///
+    /// Populations come from the Born weights; the off-diagonal term has
+    /// magnitude `coherence / 2` and the tokens' relative phase.
+    pub fn density_matrix(&self) -> Matrix {
+        let (p0, p1) = self.born_weights();
+        let [m, a] = self.amplitudes();
+        let rel = m * a.conj();
+        let off = if rel.abs() > 0.0 {
+            rel.scale(self.coherence / (2.0 * rel.abs()))
+        } else {
+            Complex::ZERO
+        };
+        Matrix::from_rows(vec![
+            vec![Complex::real(p0), off],
+            vec![off.conj(), Complex::real(p1)],
+        ])
+    }
+
+    /// Load a 2×2 density matrix, keeping the field's overall scale.
+    ///
+    /// Token magnitudes follow the populations and `coherence` becomes
+    /// `2|ρ₀₁|`. The matter phase is kept; the antimatter phase is set
+    /// from the off-diagonal term, or kept when that term vanishes.
+    pub fn set_density(&mut self, rho: &Matrix) {
+        assert_eq!(rho.dim(), 2, "field density matrix must be 2×2");
+        let (p0, p1) = (rho[(0, 0)].re.max(0.0), rho[(1, 1)].re.max(0.0));
+        let total = p0 + p1;
+        let (p0, p1) = if total > 0.0 {
+            (p0 / total, p1 / total)
+        } else {
+            (0.5, 0.5)
+        };
+        let scale = match self.norm() {
+            n if n > 0.0 => n,
+            _ => 1.0,
+        };
+        let [m, a] = self.amplitudes();
+        let matter_phase = m.arg();
+        let off = rho[(0, 1)] / if total > 0.0 { total } else { 1.0 };
+        let antimatter_phase = if off.abs() > 0.0 {
+            matter_phase - off.arg()
+        } else {
+            a.arg()
+        };
+        self.matter
+            .set_amplitude(Complex::from_polar(p0.sqrt() * scale, matter_phase));
+        self.antimatter
+            .set_amplitude(Complex::from_polar(p1.sqrt() * scale, antimatter_phase));
+        self.coherence = (2.0 * off.abs()).min(self.max_coherence());
+    }
+
+    /// Scale the off-diagonal terms by `1 - strength`.
+    ///
+    /// `strength` is clamped to `[0, 1]`; 1 leaves a classical mixture.
+    pub fn decohere(&mut self, strength: f64) {
+        self.coherence *= 1.0 - strength.clamp(0.0, 1.0);
+    }
+
+    /// True once either branch carries a non-zero phase.
+    pub fn is_amplitude_mode(&self) -> bool {
+        self.matter.phase != 0.0 || self.antimatter.phase != 0.0
+    }
+
+    /// `[matter, antimatter]` as complex amplitudes.
+    pub fn amplitudes(&self) -> [Complex; 2] {
+        [self.matter.amplitude(), self.antimatter.amplitude()]
+    }
+
+    /// Overwrite both amplitudes and refresh coherence.
+    pub fn set_amplitudes(&mut self, [matter, antimatter]: [Complex; 2]) {
+        self.matter.set_amplitude(matter);
+        self.antimatter.set_amplitude(antimatter);
+        self.refresh_coherence();
+    }
+
+    /// Euclidean norm of the amplitude pair.
+    pub fn norm(&self) -> f64 {
+        let [m, a] = self.amplitudes();
+        (m.norm_sqr() + a.norm_sqr()).sqrt()
+    }
+
+    /// Rescale so that `|m|² + |a|² = 1`. An empty field is left as is.
+    pub fn normalize(&mut self) {
+        let norm = self.norm();
+        if norm > 0.0 {
+            let [m, a] = self.amplitudes();
+            self.set_amplitudes([m / norm, a / norm]);
+        }
+    }
+
+    /// Equality of the normalized rays, ignoring a global phase `e^{iθ}`.
+    ///
+    /// Two empty fields compare equal; an empty field never equals a
+    /// non-empty one.
+    pub fn eq_up_to_global_phase(&self, other: &QuasiField, tol: f64) -> bool {
+        let (n1, n2) = (self.norm(), other.norm());
+        if n1 == 0.0 || n2 == 0.0 {
+            return n1 == n2;
+        }
+        let [m1, a1] = self.amplitudes();
+        let [m2, a2] = other.amplitudes();
+        let overlap = (m1.conj() * m2 + a1.conj() * a2).abs() / (n1 * n2);
+        (1.0 - overlap).abs() <= tol
+    }
+
+    /// Born-rule weights `(p_matter, p_antimatter)`.
+    ///
+    /// Token values are read as amplitudes, so each branch is weighted by
+    /// its squared magnitude. An empty field falls back to an even split.
+    pub fn born_weights(&self) -> (f64, f64) {
+        let [m, a] = self.amplitudes();
+        let (m, a) = (m.norm_sqr(), a.norm_sqr());
+        let total = m + a;
+        if total > 0.0 {
+            (m / total, a / total)
+        } else {
+            (0.5, 0.5)
+        }
+    }
+}
+
+/// One node of a [`FieldManifold`].
+#[derive(Clone, Debug)]
+pub struct FieldNode {
+    pub id: String,
+    pub field: QuasiField,
+    /// Local curvature `κ`; positive values damp coherence, negative pump it.
+    pub curvature: f64,
+}
+
+/// A weighted, undirected graph of fields.
+#[derive(Clone, Debug, Default)]
+pub struct FieldManifold {
+    nodes: Vec<FieldNode>,
+    /// Neighbour index to edge weight, per node.
+    edges: Vec<BTreeMap<usize, f64>>,
+}
+
+impl FieldManifold {
+    /// An empty manifold.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node, returning its index.
+    ///
+    /// # Panics
+    /// If `id` is already used or `curvature` is not finite.
+    pub fn add_node(&mut self, id: &str, field: QuasiField, curvature: f64) -> usize {
+        assert!(self.index_of(id).is_none(), "duplicate node id {}", id);
+        assert!(curvature.is_finite(), "curvature must be finite");
+        self.nodes.push(FieldNode {
+            id: id.to_string(),
+            field,
+            curvature,
+        });
+        self.edges.push(BTreeMap::new());
+        self.nodes.len() - 1
+    }
+
+    /// Join nodes `a` and `b` with an edge of the given weight, replacing
+    /// any existing edge between them.
+    ///
+    /// # Panics
+    /// If either index is out of range, `a == b`, or `weight` is not
+    /// positive and finite.
+    pub fn connect(&mut self, a: usize, b: usize, weight: f64) {
+        assert!(a < self.len() && b < self.len(), "node index out of range");
+        assert!(a != b, "a node cannot connect to itself");
+        assert!(
+            weight > 0.0 && weight.is_finite(),
+            "edge weight must be positive"
+        );
+        self.edges[a].insert(b, weight);
+        self.edges[b].insert(a, weight);
+    }
+
+    /// Remove the edge between `a` and `b`, returning its weight.
+    pub fn disconnect(&mut self, a: usize, b: usize) -> Option<f64> {
+        self.edges.get_mut(b)?.remove(&a);
+        self.edges.get_mut(a)?.remove(&b)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.id == id)
+    }
+
+    pub fn node(&self, index: usize) -> Option<&FieldNode> {
+        self.nodes.get(index)
+    }
+
+    pub fn node_mut(&mut self, index: usize) -> Option<&mut FieldNode> {
+        self.nodes.get_mut(index)
+    }
+
+    pub fn nodes(&self) -> &[FieldNode] {
+        &self.nodes
+    }
+
+    /// Neighbours of `index` with their edge weights, in index order.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn neighbors(&self, index: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        assert!(index < self.len(), "node index out of range");
+        self.edges[index].iter().map(|(&j, &w)| (j, w))
+    }
+
+    /// Every edge once, as `(a, b, weight)` with `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(|(a, adj)| adj.range(a + 1..).map(move |(&b, &w)| (a, b, w)))
+    }
+
+    /// Sum of the edge weights at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn degree(&self, index: usize) -> f64 {
+        assert!(index < self.len(), "node index out of range");
+        self.edges[index].values().sum()
+    }
+
+    pub fn coherences(&self) -> Vec<f64> {
+        self.nodes.iter().map(|n| n.field.coherence).collect()
+    }
+
+    /// Weighted coherence difference `w·(c_b − c_a)` along the edge from
+    /// `a` to `b`, or `None` if they are not adjacent.
+    pub fn gradient(&self, a: usize, b: usize) -> Option<f64> {
+        let w = *self.edges.get(a)?.get(&b)?;
+        Some(w * (self.nodes[b].field.coherence - self.nodes[a].field.coherence))
+    }
+
+    /// Gradient along every edge, oriented from the lower index.
+    pub fn gradients(&self) -> Vec<(usize, usize, f64)> {
+        let c = self.coherences();
+        self.edges()
+            .map(|(a, b, w)| (a, b, w * (c[b] - c[a])))
+            .collect()
+    }
+
+    /// Discrete Laplacian `Σⱼ wᵢⱼ(cⱼ − cᵢ)` of coherence at `index`:
+    /// positive where neighbours are more coherent.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub fn laplacian(&self, index: usize) -> f64 {
+        assert!(index < self.len(), "node index out of range");
+        let c = self.nodes[index].field.coherence;
+        self.neighbors(index)
+            .map(|(j, w)| w * (self.nodes[j].field.coherence - c))
+            .sum()
+    }
+
+    /// The Laplacian at every node.
+    pub fn laplacians(&self) -> Vec<f64> {
+        (0..self.len()).map(|i| self.laplacian(i)).collect()
+    }
+
+    /// Rate `D·(Lc)ᵢ − κᵢ·cᵢ` at which each node's coherence changes
+    /// under diffusion constant `diffusion`.
+    pub fn coherence_rates(&self, diffusion: f64) -> Vec<f64> {
+        self.laplacians()
+            .into_iter()
+            .zip(&self.nodes)
+            .map(|(lap, n)| diffusion * lap - n.curvature * n.field.coherence)
+            .collect()
+    }
+
+    /// One explicit Euler step of length `dt`.
+    ///
+    /// Each coherence is clamped to `[0, max_coherence]` of its field, so
+    /// a node never claims more coherence than its populations allow.
+    pub fn step(&mut self, dt: f64, diffusion: f64) {
+        let rates = self.coherence_rates(diffusion);
+        for (node, rate) in self.nodes.iter_mut().zip(rates) {
+            let c = node.field.coherence + dt * rate;
+            node.field.coherence = c.clamp(0.0, node.field.max_coherence());
+        }
+    }
+
+    /// Evolve for `duration`, splitting it into steps short enough for
+    /// the explicit scheme to stay stable.
+    pub fn evolve(&mut self, duration: f64, diffusion: f64) {
+        if duration <= 0.0 {
+            return;
+        }
+        let stiffness = (0..self.len())
+            .map(|i| 2.0 * diffusion.abs() * self.degree(i) + self.nodes[i].curvature.abs())
+            .fold(0.0, f64::max);
+        let steps = if stiffness > 0.0 {
+            (duration * stiffness).ceil().max(1.0) as usize
+        } else {
+            1
+        };
+        let dt = duration / steps as f64;
+        for _ in 0..steps {
+            self.step(dt, diffusion);
+        }
+    }
+
+    /// Mean coherence over all nodes; 0 for an empty manifold.
+    pub fn mean_coherence(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.coherences().iter().sum::<f64>() / self.len() as f64
+    }
+}
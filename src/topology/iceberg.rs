@@ -0,0 +1,155 @@
+//! Topological modeling for deep coherence.
+//!
+//! The Topology-Iceberg Model layers a visible surface state over hidden
+//! depth. An [`Iceberg`] owns its surface [`QuasiState`] and a stack of
+//! [`DepthLayer`]s, ordered from just below the surface downwards. Depth
+//! reaches the surface only through [`Iceberg::project_depth`]; observing
+//! an iceberg collapses the surface and leaves every layer untouched.
+
+use std::fmt::{Display, Formatter};
+
+use crate::complex::Complex;
+use crate::error::{check_range, QuasiError, Result};
+use crate::matrix::Matrix;
+use crate::quasi_core::{Observation, QuasiState};
+use crate::rng::{QuasiRng, SplitMix64};
+use crate::topology::field::QuasiField;
+
+/// One hidden layer of an iceberg.
+#[derive(Clone, Debug)]
+pub struct DepthLayer {
+    pub field: QuasiField,
+    /// Share of the surface this layer replaces when depth is projected,
+    /// in `[0, 1]`.
+    pub coupling: f64,
+}
+
+/// A surface state over an ordered stack of hidden layers.
+#[derive(Clone, Debug)]
+pub struct Iceberg {
+    pub surface: QuasiState,
+    layers: Vec<DepthLayer>,
+}
+
+impl Iceberg {
+    /// An iceberg with no depth.
+    pub fn new(surface: QuasiState) -> Self {
+        Self {
+            surface,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Add a layer below the current deepest one.
+    ///
+    /// Fails if the field is invalid, `coupling` is outside `[0, 1]`, or
+    /// the couplings of all layers would sum to more than 1.
+    pub fn push_layer(&mut self, field: QuasiField, coupling: f64) -> Result<()> {
+        field.validate()?;
+        check_range("coupling", coupling, 0.0, 1.0)?;
+        let total = self.total_coupling() + coupling;
+        if total > 1.0 {
+            return Err(QuasiError::OutOfRange {
+                field: "total coupling",
+                value: total,
+                min: 0.0,
+                max: 1.0,
+            });
+        }
+        self.layers.push(DepthLayer { field, coupling });
+        Ok(())
+    }
+
+    /// Builder form of [`push_layer`](Self::push_layer).
+    ///
+    /// # Panics
+    /// If the layer is rejected.
+    pub fn with_layer(mut self, field: QuasiField, coupling: f64) -> Self {
+        if let Err(e) = self.push_layer(field, coupling) {
+            panic!("{}", e);
+        }
+        self
+    }
+
+    /// Number of hidden layers.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Layers from just below the surface downwards.
+    pub fn layers(&self) -> &[DepthLayer] {
+        &self.layers
+    }
+
+    pub fn layer(&self, depth: usize) -> Option<&DepthLayer> {
+        self.layers.get(depth)
+    }
+
+    /// Sum of the layers' couplings.
+    pub fn total_coupling(&self) -> f64 {
+        self.layers.iter().map(|l| l.coupling).sum()
+    }
+
+    /// The surface density matrix after projection, without changing it:
+    /// `(1 − Σcₖ)·ρ_surface + Σ cₖ·ρₖ`.
+    pub fn projected_density(&self) -> Matrix {
+        let mut rho = self
+            .surface
+            .density_matrix()
+            .scale(Complex::real(1.0 - self.total_coupling()));
+        for layer in &self.layers {
+            rho = &rho
+                + &layer
+                    .field
+                    .density_matrix()
+                    .scale(Complex::real(layer.coupling));
+        }
+        rho
+    }
+
+    /// Mix the layers into the surface by their couplings.
+    ///
+    /// The surface keeps its scale and id. If any layer couples to it,
+    /// the surface is no longer observed: depth brings back whatever
+    /// superposition it holds.
+    pub fn project_depth(&mut self) {
+        if self.total_coupling() == 0.0 {
+            return;
+        }
+        let rho = self.projected_density();
+        self.surface.field.set_density(&rho);
+        self.surface.observed = false;
+    }
+
+    /// Coherence `2|ρ₀₁|/tr ρ` of the whole iceberg, where `ρ` sums the
+    /// surface's and every layer's density matrix weighted by the field's
+    /// squared norm. Layers with opposite phases cancel; an empty iceberg
+    /// has coherence 0.
+    pub fn aggregate_coherence(&self) -> f64 {
+        QuasiField::joint_coherence(
+            std::iter::once(&self.surface.field).chain(self.layers.iter().map(|l| &l.field)),
+        )
+    }
+
+    /// Collapse the surface with an entropy-seeded generator.
+    pub fn observe(&mut self) -> Observation {
+        self.observe_with(&mut SplitMix64::from_entropy())
+    }
+
+    /// Collapse the surface, drawing from `rng`. Depth is not revealed.
+    pub fn observe_with<R: QuasiRng + ?Sized>(&mut self, rng: &mut R) -> Observation {
+        self.surface.observe_with(rng)
+    }
+}
+
+impl Display for Iceberg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\nDepth: {} hidden layer{}",
+            self.surface,
+            self.depth(),
+            if self.depth() == 1 { "" } else { "s" }
+        )
+    }
+}
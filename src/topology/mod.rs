@@ -0,0 +1,8 @@
+//! Topological encoding — iceberg layering, field manifolds, bound
+//! entities, simplicial homology and anyon braiding.
+
+pub mod braid;
+pub mod entity;
+pub mod field;
+pub mod iceberg;
+pub mod simplicial;
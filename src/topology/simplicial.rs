@@ -0,0 +1,382 @@
+//! Simplicial complexes and their homology over Z2.
+//!
+//! A [`SimplicialComplex`] carries a [`QuasiState`] on every vertex and,
+//! optionally, on higher simplices. Adding a simplex adds all of its
+//! faces, so the complex stays closed.
+//!
+//! Coherence orders the complex into a filtration: a simplex enters at
+//! the largest coherence of any state on it or on its faces, so
+//! `K(t)` holds every simplex whose states are all at most `t` coherent
+//! and raising `t` only adds structure. [`SimplicialComplex::persistence`]
+//! tracks when each homology class is born and dies along the way;
+//! [`SimplicialComplex::betti_at`] gives the Betti numbers of one `K(t)`.
+//! Filtration values are computed once and kept until the complex next
+//! changes.
+//!
+//! Adding a simplex adds all `2ⁿ − 1` of its faces, so simplices are
+//! limited to [`MAX_SIMPLEX_VERTICES`] vertices.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+use crate::quasi_core::QuasiState;
+
+/// Most vertices a single simplex may span.
+pub const MAX_SIMPLEX_VERTICES: usize = 16;
+
+/// Why a simplex could not be added.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimplicialError {
+    /// The simplex spans more than [`MAX_SIMPLEX_VERTICES`] vertices.
+    TooLarge { vertices: usize, max: usize },
+}
+
+impl Display for SimplicialError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimplicialError::TooLarge { vertices, max } => write!(
+                f,
+                "a simplex on {} vertices exceeds the limit of {}",
+                vertices, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SimplicialError {}
+
+pub type Result<T> = std::result::Result<T, SimplicialError>;
+
+/// A homology class over the coherence filtration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PersistencePair {
+    /// Homology dimension: 0 for components, 1 for loops, 2 for voids.
+    pub dimension: usize,
+    /// Coherence at which the class appears.
+    pub birth: f64,
+    /// Coherence at which it is filled in, or `None` if it never is.
+    pub death: Option<f64>,
+}
+
+impl PersistencePair {
+    /// `death − birth`; infinite for a class that never dies.
+    pub fn lifetime(&self) -> f64 {
+        self.death.map_or(f64::INFINITY, |d| d - self.birth)
+    }
+
+    /// True if the class exists in `K(threshold)`.
+    pub fn is_alive_at(&self, threshold: f64) -> bool {
+        self.birth <= threshold && self.death.is_none_or(|d| d > threshold)
+    }
+}
+
+impl Display for PersistencePair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.death {
+            Some(death) => write!(f, "H{} [{:.4}, {:.4})", self.dimension, self.birth, death),
+            None => write!(f, "H{} [{:.4}, ∞)", self.dimension, self.birth),
+        }
+    }
+}
+
+/// Add `other` to `column` over Z2. Both are sorted.
+fn add_z2(column: &[usize], other: &[usize]) -> Vec<usize> {
+    let mut out = Vec::with_capacity(column.len() + other.len());
+    let (mut i, mut j) = (0, 0);
+    while i < column.len() && j < other.len() {
+        match column[i].cmp(&other[j]) {
+            std::cmp::Ordering::Less => {
+                out.push(column[i]);
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                out.push(other[j]);
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&column[i..]);
+    out.extend_from_slice(&other[j..]);
+    out
+}
+
+/// The codimension-1 faces of `simplex`; none for a vertex.
+fn facets(simplex: &[usize]) -> impl Iterator<Item = Vec<usize>> + '_ {
+    let n = if simplex.len() > 1 { simplex.len() } else { 0 };
+    (0..n).map(move |k| {
+        let mut face = simplex.to_vec();
+        face.remove(k);
+        face
+    })
+}
+
+/// A closed simplicial complex with states on its simplices.
+#[derive(Clone, Debug, Default)]
+pub struct SimplicialComplex {
+    vertices: Vec<QuasiState>,
+    /// Every simplex as its sorted vertex list, with any state attached
+    /// above dimension 0.
+    simplices: BTreeMap<Vec<usize>, Option<QuasiState>>,
+    /// Filtration value of every simplex, cleared on each change.
+    values: OnceLock<BTreeMap<Vec<usize>, f64>>,
+}
+
+impl SimplicialComplex {
+    /// An empty complex.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a vertex carrying `state`, returning its index.
+    pub fn add_vertex(&mut self, state: QuasiState) -> usize {
+        self.vertices.push(state);
+        let index = self.vertices.len() - 1;
+        self.simplices.insert(vec![index], None);
+        self.values.take();
+        index
+    }
+
+    /// Add the simplex spanned by `vertices` together with all its faces.
+    ///
+    /// Fails if it spans more than [`MAX_SIMPLEX_VERTICES`] vertices.
+    ///
+    /// # Panics
+    /// If `vertices` is empty, repeats a vertex, or names one that does
+    /// not exist.
+    pub fn add_simplex(&mut self, vertices: &[usize]) -> Result<()> {
+        let simplex = self.normalize(vertices)?;
+        if self.simplices.contains_key(&simplex) {
+            return Ok(());
+        }
+        let n = simplex.len();
+        // Every non-empty subset, by bitmask.
+        for mask in 1u32..(1 << n) {
+            let face: Vec<usize> = (0..n)
+                .filter(|&k| mask & (1 << k) != 0)
+                .map(|k| simplex[k])
+                .collect();
+            self.simplices.entry(face).or_insert(None);
+        }
+        self.values.take();
+        Ok(())
+    }
+
+    /// Attach `state` to the simplex spanned by `vertices`, adding it if
+    /// needed. On a single vertex this replaces the vertex's state.
+    ///
+    /// Fails as [`add_simplex`](Self::add_simplex) does.
+    ///
+    /// # Panics
+    /// As [`add_simplex`](Self::add_simplex).
+    pub fn attach(&mut self, vertices: &[usize], state: QuasiState) -> Result<()> {
+        let simplex = self.normalize(vertices)?;
+        self.add_simplex(&simplex)?;
+        if let [v] = simplex[..] {
+            self.vertices[v] = state;
+        } else {
+            self.simplices.insert(simplex, Some(state));
+        }
+        self.values.take();
+        Ok(())
+    }
+
+    pub fn vertex(&self, index: usize) -> Option<&QuasiState> {
+        self.vertices.get(index)
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// The state attached to a simplex, if any.
+    pub fn state(&self, vertices: &[usize]) -> Option<&QuasiState> {
+        let mut simplex = vertices.to_vec();
+        simplex.sort_unstable();
+        match simplex[..] {
+            [v] => self.vertices.get(v),
+            _ => self.simplices.get(&simplex)?.as_ref(),
+        }
+    }
+
+    pub fn contains(&self, vertices: &[usize]) -> bool {
+        let mut simplex = vertices.to_vec();
+        simplex.sort_unstable();
+        self.simplices.contains_key(&simplex)
+    }
+
+    /// Number of simplices of every dimension.
+    pub fn len(&self) -> usize {
+        self.simplices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.simplices.is_empty()
+    }
+
+    /// Largest simplex dimension, or `None` for an empty complex.
+    pub fn dimension(&self) -> Option<usize> {
+        self.simplices.keys().map(|s| s.len() - 1).max()
+    }
+
+    /// Simplices of dimension `dim`, as sorted vertex lists.
+    pub fn simplices(&self, dim: usize) -> impl Iterator<Item = &[usize]> {
+        self.simplices
+            .keys()
+            .filter(move |s| s.len() == dim + 1)
+            .map(Vec::as_slice)
+    }
+
+    /// `Σ (−1)ᵏ · #k-simplices`.
+    pub fn euler_characteristic(&self) -> i64 {
+        self.simplices
+            .keys()
+            .map(|s| if s.len() % 2 == 1 { 1 } else { -1 })
+            .sum()
+    }
+
+    /// Filtration value of a simplex: the largest coherence on it or any
+    /// of its faces.
+    pub fn coherence(&self, vertices: &[usize]) -> Option<f64> {
+        let mut simplex = vertices.to_vec();
+        simplex.sort_unstable();
+        self.values().get(&simplex).copied()
+    }
+
+    /// Every simplex with its filtration value, in filtration order:
+    /// by value, then dimension, then vertices.
+    pub fn filtration(&self) -> Vec<(Vec<usize>, f64)> {
+        let mut order: Vec<(Vec<usize>, f64)> =
+            self.values().iter().map(|(s, &v)| (s.clone(), v)).collect();
+        order.sort_by(|(a, va), (b, vb)| {
+            va.total_cmp(vb)
+                .then(a.len().cmp(&b.len()))
+                .then_with(|| a.cmp(b))
+        });
+        order
+    }
+
+    /// Persistent homology over the coherence filtration, by the standard
+    /// Z2 column reduction. Classes born and killed at the same value are
+    /// left out; the rest are sorted by dimension, then birth.
+    pub fn persistence(&self) -> Vec<PersistencePair> {
+        let order = self.filtration();
+        let position: BTreeMap<&[usize], usize> = order
+            .iter()
+            .enumerate()
+            .map(|(i, (s, _))| (s.as_slice(), i))
+            .collect();
+
+        // Reduced boundary columns, and the column owning each pivot.
+        let mut columns: Vec<Vec<usize>> = Vec::with_capacity(order.len());
+        let mut pivots: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut killed = vec![false; order.len()];
+        let mut pairs = Vec::new();
+        for (j, (simplex, value)) in order.iter().enumerate() {
+            let mut column: Vec<usize> = facets(simplex)
+                .map(|face| position[face.as_slice()])
+                .collect();
+            column.sort_unstable();
+            while let Some(&low) = column.last() {
+                match pivots.get(&low) {
+                    Some(&other) => column = add_z2(&column, &columns[other]),
+                    None => break,
+                }
+            }
+            if let Some(&low) = column.last() {
+                pivots.insert(low, j);
+                killed[low] = true;
+                let birth = order[low].1;
+                if *value > birth {
+                    pairs.push(PersistencePair {
+                        dimension: order[low].0.len() - 1,
+                        birth,
+                        death: Some(*value),
+                    });
+                }
+            }
+            columns.push(column);
+        }
+        for (j, (simplex, value)) in order.iter().enumerate() {
+            if columns[j].is_empty() && !killed[j] {
+                pairs.push(PersistencePair {
+                    dimension: simplex.len() - 1,
+                    birth: *value,
+                    death: None,
+                });
+            }
+        }
+        pairs.sort_by(|a, b| {
+            a.dimension
+                .cmp(&b.dimension)
+                .then(a.birth.total_cmp(&b.birth))
+                .then(a.lifetime().total_cmp(&b.lifetime()))
+        });
+        pairs
+    }
+
+    /// Betti numbers `β₀ … β_d` of the sub-complex `K(threshold)`, where
+    /// `d` is the dimension of the whole complex.
+    pub fn betti_at(&self, threshold: f64) -> Vec<usize> {
+        let mut betti = vec![0; self.dimension().map_or(0, |d| d + 1)];
+        for pair in self.persistence() {
+            if pair.is_alive_at(threshold) {
+                betti[pair.dimension] += 1;
+            }
+        }
+        betti
+    }
+
+    /// Betti numbers of the whole complex.
+    pub fn betti_numbers(&self) -> Vec<usize> {
+        self.betti_at(f64::INFINITY)
+    }
+
+    /// Filtration values, computed on first use after a change.
+    fn values(&self) -> &BTreeMap<Vec<usize>, f64> {
+        self.values.get_or_init(|| {
+            let mut values: BTreeMap<Vec<usize>, f64> = BTreeMap::new();
+            // Visit by dimension so faces are valued before their cofaces.
+            let mut by_dim: Vec<&Vec<usize>> = self.simplices.keys().collect();
+            by_dim.sort_by_key(|s| s.len());
+            for simplex in by_dim {
+                let own = match &simplex[..] {
+                    [v] => self.vertices[*v].measure_coherence(),
+                    _ => self.simplices[simplex]
+                        .as_ref()
+                        .map_or(0.0, QuasiState::measure_coherence),
+                };
+                let value = facets(simplex)
+                    .map(|face| values[&face])
+                    .fold(own, f64::max);
+                values.insert(simplex.clone(), value);
+            }
+            values
+        })
+    }
+
+    /// Sorted, deduplicated simplex, checked against the vertices.
+    fn normalize(&self, vertices: &[usize]) -> Result<Vec<usize>> {
+        assert!(!vertices.is_empty(), "a simplex needs at least one vertex");
+        let mut simplex = vertices.to_vec();
+        simplex.sort_unstable();
+        let len = simplex.len();
+        simplex.dedup();
+        assert!(simplex.len() == len, "a simplex cannot repeat a vertex");
+        assert!(
+            simplex[len - 1] < self.vertices.len(),
+            "vertex index out of range"
+        );
+        if len > MAX_SIMPLEX_VERTICES {
+            return Err(SimplicialError::TooLarge {
+                vertices: len,
+                max: MAX_SIMPLEX_VERTICES,
+            });
+        }
+        Ok(simplex)
+    }
+}
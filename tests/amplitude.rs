@@ -0,0 +1,63 @@
+use quasi::gate::{Gate, H, S, Z};
+use quasi::{Complex, QuasiField, QuasiState};
+
+fn sample() -> QuasiState {
+    QuasiState::from_amplitudes(
+        "s",
+        "energy",
+        Complex::new(0.6, 0.0),
+        Complex::new(0.0, 0.8),
+    )
+}
+
+fn close(a: [Complex; 2], b: [Complex; 2]) -> bool {
+    a.iter().zip(&b).all(|(x, y)| (*x - *y).abs() < 1e-12)
+}
+
+#[test]
+fn hadamard_twice_is_the_identity() {
+    for gates in [vec![&H as &dyn Gate, &H], vec![&S, &H, &H, &Z, &S]] {
+        let mut state = sample();
+        for gate in gates {
+            state.apply(gate);
+        }
+        assert!(close(state.amplitudes(), sample().amplitudes()));
+        assert!((state.measure_coherence() - sample().measure_coherence()).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn opposite_phases_cancel() {
+    let plus = QuasiState::new("p", "energy", 1.0, 1.0);
+    let minus = QuasiState::new("m", "energy", 1.0, -1.0);
+    assert!((plus.measure_coherence() - 1.0).abs() < 1e-12);
+    assert!((minus.measure_coherence() - 1.0).abs() < 1e-12);
+
+    // Taken together the two off-diagonal terms cancel...
+    assert!(QuasiField::joint_coherence([&plus.field, &minus.field]) < 1e-12);
+    assert!((QuasiField::joint_coherence([&plus.field, &plus.field]) - 1.0).abs() < 1e-12);
+
+    // ...and summed amplitude by amplitude the antimatter branch vanishes.
+    let sum = plus.interfere(&minus);
+    assert!(sum.amplitudes()[1].abs() < 1e-12);
+    assert!((sum.amplitudes()[0].re - 2.0).abs() < 1e-12);
+    assert_eq!(sum.measure_coherence(), 0.0);
+}
+
+#[test]
+fn global_phase_is_unobservable() {
+    let state = sample();
+    for theta in [0.3, 1.0, std::f64::consts::PI, -2.2] {
+        let phase = Complex::cis(theta);
+        let [m, a] = state.amplitudes();
+        let shifted = QuasiState::from_amplitudes("t", "energy", m * phase, a * phase);
+        assert!(state.field.eq_up_to_global_phase(&shifted.field, 1e-12));
+        assert!((state.measure_coherence() - shifted.measure_coherence()).abs() < 1e-12);
+        let (p, q) = (state.field.born_weights(), shifted.field.born_weights());
+        assert!((p.0 - q.0).abs() < 1e-12 && (p.1 - q.1).abs() < 1e-12);
+    }
+    // A relative phase is observable.
+    let mut relative = state.clone();
+    relative.apply(&Z);
+    assert!(!state.field.eq_up_to_global_phase(&relative.field, 1e-6));
+}
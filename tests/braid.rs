@@ -0,0 +1,73 @@
+use quasi::gate::{Gate, H, S, T, X};
+use quasi::topology::braid::{phase_distance, BraidGate, Charge};
+use quasi::{AnyonModel, BraidCompiler, BraidWord, Matrix, QuasiState};
+
+const MODELS: [AnyonModel; 2] = [AnyonModel::Fibonacci, AnyonModel::Ising];
+
+#[test]
+fn fusion_rules() {
+    use Charge::*;
+    let fib = AnyonModel::Fibonacci;
+    assert_eq!(fib.fuse(Tau, Tau), [Vacuum, Tau]);
+    assert_eq!(fib.fuse(Vacuum, Tau), [Tau]);
+    assert!(fib.fuse(Sigma, Tau).is_empty());
+
+    let ising = AnyonModel::Ising;
+    assert_eq!(ising.fuse(Sigma, Sigma), [Vacuum, Psi]);
+    assert_eq!(ising.fuse(Sigma, Psi), [Sigma]);
+    assert_eq!(ising.fuse(Psi, Psi), [Vacuum]);
+    assert_eq!(ising.channels(), [Vacuum, Psi]);
+}
+
+#[test]
+fn generators_satisfy_the_braid_relations() {
+    for model in MODELS {
+        let a = BraidWord::from_letters(&[1, 2, 1]).unitary(model);
+        let b = BraidWord::from_letters(&[2, 1, 2]).unitary(model);
+        assert!(a.max_abs_diff(&b) < 1e-12, "{}", model);
+
+        let word = BraidWord::from_letters(&[1, -2, 2, 2, -1]);
+        let round = &word.inverse().unitary(model) * &word.unitary(model);
+        assert!(round.max_abs_diff(&Matrix::identity(2)) < 1e-12);
+        assert_eq!(word.reduced(), BraidWord::from_letters(&[1, 2, -1]));
+    }
+    // Fibonacci exchanges have order 10.
+    let tenth = BraidWord::from_letters(&[2; 10]).unitary(AnyonModel::Fibonacci);
+    assert!(tenth.max_abs_diff(&Matrix::identity(2)) < 1e-12);
+}
+
+#[test]
+fn ising_braids_compile_cliffords_exactly() {
+    let compiler = BraidCompiler::new(AnyonModel::Ising);
+    for target in [H.matrix(), S.matrix(), X.matrix()] {
+        let compiled = compiler.compile(&target);
+        assert!(compiled.distance < 1e-9);
+        assert!(phase_distance(&compiled.word.unitary(AnyonModel::Ising), &target) < 1e-9);
+    }
+    // T is outside the Clifford group, so no braid reaches it.
+    assert!(compiler.max_length(20).compile(&T.matrix()).distance > 0.1);
+}
+
+#[test]
+fn fibonacci_approximations_improve_with_length() {
+    let target = T.matrix();
+    let short = BraidCompiler::new(AnyonModel::Fibonacci)
+        .max_length(4)
+        .compile(&target);
+    let long = BraidCompiler::new(AnyonModel::Fibonacci)
+        .max_length(10)
+        .compile(&target);
+    assert!(long.distance < short.distance);
+    assert!(long.distance < 0.1);
+
+    // The compiled braid acts on a state like the gate it approximates.
+    let gate = BraidGate::new(AnyonModel::Fibonacci, long.word);
+    let mut braided = QuasiState::new("q", "energy", 1.0, 1.0);
+    let mut direct = braided.clone();
+    braided.apply(&gate);
+    direct.apply(&T);
+    let overlap = braided.amplitudes()[0] * direct.amplitudes()[0].conj()
+        + braided.amplitudes()[1] * direct.amplitudes()[1].conj();
+    let norm = braided.field.norm() * direct.field.norm();
+    assert!(overlap.abs() / norm > 0.98);
+}
@@ -0,0 +1,108 @@
+use quasi::circuit::Condition;
+use quasi::gate::{CNot, Rx, H, X};
+use quasi::{Circuit, DensityMatrixBackend, Executor, StateVectorBackend};
+
+fn ghz(n: usize) -> Circuit {
+    let mut circuit = Circuit::new(n, n);
+    circuit.gate(H, &[0]);
+    for k in 1..n {
+        circuit.gate(CNot, &[k - 1, k]);
+    }
+    circuit.measure_all();
+    circuit
+}
+
+#[test]
+fn bell_shots_only_produce_correlated_bits() {
+    let result = Executor::new(StateVectorBackend::new())
+        .shots(1000)
+        .seed(2)
+        .run(&ghz(2));
+    assert_eq!(result.num_shots(), 1000);
+    assert_eq!(result.histogram.len(), 2);
+    assert_eq!(result.count("00") + result.count("11"), 1000);
+    assert!((result.frequency("00") - 0.5).abs() < 0.05);
+
+    // The same seed replays the same shots.
+    let again = Executor::new(StateVectorBackend::new())
+        .shots(1000)
+        .seed(2)
+        .run(&ghz(2));
+    assert_eq!(again.shots, result.shots);
+}
+
+#[test]
+fn conditionals_read_earlier_measurements() {
+    // Measure an even superposition, then flip it back to matter when
+    // the outcome was antimatter: the second reading is always 0.
+    let mut circuit = Circuit::new(2, 3);
+    circuit
+        .gate(H, &[0])
+        .measure(0, 0)
+        .gate_if(Condition::bit(0, true), X, &[0])
+        .measure(0, 1)
+        // Copy bit 0 onto state 1 through a two-bit register condition.
+        .gate_if(Condition::register(vec![0, 1], 1), X, &[1])
+        .measure(1, 2);
+    let result = Executor::new(StateVectorBackend::new())
+        .shots(200)
+        .seed(9)
+        .run(&circuit);
+    for shot in &result.shots {
+        assert!(!shot[1]);
+        assert_eq!(shot[2], shot[0]);
+    }
+    assert!(result.count("101") > 0 && result.count("000") > 0);
+}
+
+#[test]
+fn backends_agree_shot_for_shot() {
+    let mut circuit = ghz(3);
+    circuit.gate(Rx(0.9), &[2]).measure(2, 2);
+    let statevector = Executor::new(StateVectorBackend::new())
+        .shots(300)
+        .seed(17)
+        .run(&circuit);
+    let density = Executor::new(DensityMatrixBackend::new())
+        .shots(300)
+        .seed(17)
+        .run(&circuit);
+    assert_eq!(statevector.histogram, density.histogram);
+}
+
+#[test]
+#[should_panic(expected = "repeated gate target")]
+fn repeated_targets_are_rejected_when_recorded() {
+    Circuit::new(2, 0).gate(CNot, &[1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn out_of_range_targets_are_rejected_when_recorded() {
+    Circuit::new(2, 0).gate(CNot, &[0, 2]);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn conditional_operations_are_checked() {
+    use quasi::circuit::Operation;
+    Circuit::new(1, 1).push_conditional(
+        Condition::bit(0, false),
+        Operation::Measure { index: 5, bit: 0 },
+    );
+}
+
+#[test]
+#[should_panic(expected = "limited to 64 bits")]
+fn conditions_fit_one_word() {
+    Condition::register(vec![0; 65], 0);
+}
+
+#[test]
+fn wide_conditions_read_every_bit() {
+    let condition = Condition::register((0..64).collect(), 1 << 63);
+    let mut bits = vec![false; 64];
+    assert!(!condition.holds(&bits));
+    bits[63] = true;
+    assert!(condition.holds(&bits));
+}
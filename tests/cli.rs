@@ -0,0 +1,190 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+/// A fresh, empty directory under the system temp dir.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quasi-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn quasi(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_quasi"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn json(output: &Output) -> Value {
+    assert!(output.status.success(), "{:?}", output);
+    serde_json::from_str(&stdout(output)).unwrap()
+}
+
+#[test]
+fn new_show_and_coherence() {
+    let dir = scratch_dir("show");
+    let out = quasi(&dir, &["new", "s.json", "ice", "42", "-41.8"]);
+    assert!(out.status.success());
+    assert!(stdout(&out).contains("QuasiState [ice]"));
+
+    let state = json(&quasi(&dir, &["show", "s.json", "--json"]));
+    assert_eq!(state["version"], 1);
+    assert_eq!(state["id"], "ice");
+    assert_eq!(state["matter"], 42.0);
+    assert_eq!(state["antimatter"], -41.8);
+    assert_eq!(state["observed"], false);
+
+    let out = quasi(&dir, &["coherence", "s.json"]);
+    assert_eq!(stdout(&out).trim(), "0.999989");
+    let c = json(&quasi(&dir, &["coherence", "s.json", "--json"]));
+    assert_eq!(c["coherence"], 0.9999886080131236);
+
+    // Existing files are kept unless --force is given.
+    assert_eq!(
+        quasi(&dir, &["new", "s.json", "x", "1", "1"]).status.code(),
+        Some(1)
+    );
+    assert!(quasi(&dir, &["new", "s.json", "x", "1", "1", "--force"])
+        .status
+        .success());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn seeded_observe_is_reproducible() {
+    let dir = scratch_dir("observe");
+    let mut outcomes = Vec::new();
+    for _ in 0..2 {
+        quasi(&dir, &["new", "s.json", "ice", "3", "4", "--force"]);
+        outcomes.push(json(&quasi(
+            &dir,
+            &["observe", "s.json", "--seed", "7", "--json"],
+        )));
+    }
+    assert_eq!(outcomes[0], outcomes[1]);
+    let branch = outcomes[0]["branch"].as_str().unwrap();
+    assert!(branch == "matter" || branch == "antimatter");
+    assert_eq!(
+        json(&quasi(&dir, &["show", "s.json", "--json"]))["observed"],
+        true
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invert_and_strict_exit_codes() {
+    let dir = scratch_dir("invert");
+    quasi(&dir, &["new", "s.json", "ice", "42", "-41.8"]);
+    let state = json(&quasi(&dir, &["invert", "s.json", "--strict", "--json"]));
+    assert_eq!(state["matter"], -41.8);
+    assert_eq!(state["antimatter"], 42.0);
+
+    quasi(&dir, &["observe", "s.json", "--seed", "1"]);
+    // Lenient mode accepts transitions on an observed state; strict
+    // mode refuses them with a runtime error.
+    assert!(quasi(&dir, &["invert", "s.json"]).status.success());
+    let out = quasi(&dir, &["invert", "s.json", "--strict"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("already been observed"));
+    assert_eq!(
+        quasi(&dir, &["observe", "s.json", "--strict"])
+            .status
+            .code(),
+        Some(1)
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_input_exit_codes() {
+    let dir = scratch_dir("invalid");
+    let out = quasi(&dir, &["new", "n.json", "x", "NaN", "1"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("finite"));
+    assert!(!dir.join("n.json").exists());
+
+    assert_eq!(
+        quasi(&dir, &["new", "n.json", "x", "1"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        quasi(&dir, &["new", "n.json", "x", "one", "1"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(quasi(&dir, &["frobnicate"]).status.code(), Some(2));
+    assert_eq!(
+        quasi(&dir, &["show", "missing.json"]).status.code(),
+        Some(1)
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_prints_a_histogram() {
+    let dir = scratch_dir("run");
+    fs::write(
+        dir.join("bell.qasm"),
+        "OPENQASM 3;\nqubit[2] q;\nbit[2] c;\nh q[0];\ncx q[0], q[1];\nc = measure q;\n",
+    )
+    .unwrap();
+    let out = quasi(&dir, &["run", "bell.qasm", "--shots", "200", "--seed", "1"]);
+    assert!(out.status.success());
+    let lines: Vec<String> = stdout(&out).lines().map(str::to_string).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("00") && lines[1].starts_with("11"));
+
+    for backend in ["statevector", "density"] {
+        let result = json(&quasi(
+            &dir,
+            &[
+                "run",
+                "bell.qasm",
+                "--shots",
+                "200",
+                "--seed",
+                "1",
+                "--backend",
+                backend,
+                "--json",
+            ],
+        ));
+        assert_eq!(result["shots"], 200);
+        let histogram = result["histogram"].as_object().unwrap();
+        let total: u64 = histogram.values().map(|v| v.as_u64().unwrap()).sum();
+        assert_eq!(total, 200);
+        assert!(histogram.keys().all(|k| k == "00" || k == "11"));
+    }
+    let out = quasi(&dir, &["run", "bell.qasm", "--backend", "nope"]);
+    assert_eq!(out.status.code(), Some(2));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn run_refuses_registers_too_large_to_simulate() {
+    let dir = scratch_dir("large");
+    fs::write(dir.join("big.qasm"), "qubit[64] q;\nh q[0];\n").unwrap();
+    let out = quasi(&dir, &["run", "big.qasm"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("at most 20"));
+
+    fs::write(dir.join("mid.qasm"), "qubit[12] q;\nh q[0];\n").unwrap();
+    assert!(quasi(&dir, &["run", "mid.qasm", "--shots", "1"])
+        .status
+        .success());
+    let out = quasi(&dir, &["run", "mid.qasm", "--backend", "density"]);
+    assert_eq!(out.status.code(), Some(1));
+    fs::remove_dir_all(&dir).unwrap();
+}
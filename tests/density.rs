@@ -0,0 +1,110 @@
+use quasi::gate::{CNot, Rx, Ry, H, S, T};
+use quasi::{
+    Circuit, DensityMatrix, DensityMatrixBackend, Executor, KrausChannel, QuasiRegister,
+    QuasiState, StateVectorBackend,
+};
+
+fn states() -> Vec<QuasiState> {
+    vec![
+        QuasiState::new("a", "energy", 1.0, 2.0),
+        QuasiState::new("b", "energy", 3.0, -1.0),
+        QuasiState::new("c", "energy", 0.5, 0.5),
+    ]
+}
+
+/// An entangled register over `states()`.
+fn register() -> QuasiRegister {
+    let mut register = QuasiRegister::from_states(&states());
+    register.apply(&H, &[0]);
+    register.apply(&CNot, &[0, 2]);
+    register.apply(&Ry(0.7), &[1]);
+    register.apply(&T, &[2]);
+    register
+}
+
+fn assert_physical(rho: &DensityMatrix) {
+    assert!((rho.matrix().trace().re - 1.0).abs() < 1e-12);
+    assert!(rho.matrix().trace().im.abs() < 1e-12);
+    assert!(rho.matrix().is_hermitian(1e-12));
+}
+
+#[test]
+fn gates_and_channels_preserve_trace_and_hermiticity() {
+    let mut rho = DensityMatrix::from_register(&register());
+    assert_physical(&rho);
+    rho.apply(&Rx(1.3), &[1]);
+    rho.apply(&CNot, &[2, 1]);
+    rho.apply(&S, &[0]);
+    assert_physical(&rho);
+    for (index, channel) in [
+        (0, KrausChannel::amplitude_damping(0.3)),
+        (1, KrausChannel::phase_damping(0.6)),
+        (2, KrausChannel::depolarizing(0.25)),
+        (0, KrausChannel::bit_flip(0.1)),
+        (1, KrausChannel::phase_flip(0.4)),
+    ] {
+        rho.apply_channel(&channel, index);
+        assert_physical(&rho);
+    }
+    assert!(rho.purity() < 1.0);
+}
+
+#[test]
+fn purity_runs_from_pure_to_maximally_mixed() {
+    let rho = DensityMatrix::from_register(&register());
+    assert!((rho.purity() - 1.0).abs() < 1e-12);
+
+    let mut mixed = rho.clone();
+    mixed.apply_channel(&KrausChannel::depolarizing(1.0), 0);
+    // `b` and `c` are still partly pure.
+    assert!(mixed.purity() < 1.0 && mixed.purity() > 0.125);
+    for index in 1..3 {
+        mixed.apply_channel(&KrausChannel::depolarizing(1.0), index);
+    }
+    assert!((mixed.purity() - 0.125).abs() < 1e-12, "{}", mixed.purity());
+
+    let mut single = DensityMatrix::new(1);
+    single.apply_channel(&KrausChannel::depolarizing(1.0), 0);
+    assert!((single.purity() - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn reduced_views_agree_with_the_register() {
+    let register = register();
+    let rho = DensityMatrix::from_register(&register);
+    assert_eq!(rho.ids(), register.ids());
+    for index in 0..register.len() {
+        let (p0, p1) = register.marginal(index);
+        let (q0, q1) = rho.marginal(index);
+        assert!((p0 - q0).abs() < 1e-12 && (p1 - q1).abs() < 1e-12);
+        assert!(
+            rho.reduced_density(index)
+                .max_abs_diff(&register.reduced_density(index))
+                < 1e-12
+        );
+    }
+    for (p, q) in register.probabilities().iter().zip(rho.probabilities()) {
+        assert!((p - q).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn backends_agree_on_a_noiseless_bell_circuit() {
+    let mut circuit = Circuit::new(2, 2);
+    circuit
+        .gate(H, &[0])
+        .gate(CNot, &[0, 1])
+        .measure(0, 0)
+        .measure(1, 1);
+    let statevector = Executor::new(StateVectorBackend::new())
+        .shots(500)
+        .seed(3)
+        .run(&circuit);
+    let density = Executor::new(DensityMatrixBackend::new())
+        .shots(500)
+        .seed(3)
+        .run(&circuit);
+    assert_eq!(statevector.histogram, density.histogram);
+    assert_eq!(density.count("00") + density.count("11"), 500);
+    assert!(density.count("00") > 0 && density.count("11") > 0);
+}
@@ -0,0 +1,158 @@
+use quasi::topology::entity::EntityError;
+use quasi::{QuasiEntity, QuasiError, QuasiState};
+
+/// Charges 2, 0 and -1; joint coherence 18/23 ≈ 0.78.
+fn states() -> Vec<QuasiState> {
+    vec![
+        QuasiState::new("a", "energy", 3.0, 1.0),
+        QuasiState::new("b", "energy", 2.0, 2.0),
+        QuasiState::new("c", "energy", 1.0, 2.0),
+    ]
+}
+
+fn entity(min_coherence: f64) -> QuasiEntity {
+    QuasiEntity::bind("e", states(), min_coherence).unwrap()
+}
+
+fn assert_untouched(entity: &QuasiEntity) {
+    let fresh = states();
+    assert_eq!(entity.states().len(), fresh.len());
+    for (state, original) in entity.states().iter().zip(&fresh) {
+        assert_eq!(state.id, original.id);
+        assert_eq!(state.amplitudes(), original.amplitudes());
+        assert_eq!(state.field.coherence, original.field.coherence);
+    }
+    assert_eq!(entity.charge(), 1.0);
+    entity.validate().unwrap();
+}
+
+#[test]
+fn binding_fixes_the_charge() {
+    let entity = entity(0.5);
+    assert_eq!(entity.charge(), 1.0);
+    assert!((entity.joint_coherence() - 18.0 / 23.0).abs() < 1e-12);
+    assert!(matches!(
+        QuasiEntity::bind("e", states(), 0.9),
+        Err(EntityError::Decoherent { .. })
+    ));
+    assert!(matches!(
+        QuasiEntity::bind("e", Vec::new(), 0.0),
+        Err(EntityError::Empty { .. })
+    ));
+    assert!(matches!(
+        QuasiEntity::bind("", states(), 0.0),
+        Err(EntityError::Quasi(QuasiError::EmptyId))
+    ));
+}
+
+#[test]
+fn refused_transforms_leave_the_entity_untouched() {
+    let mut entity = entity(0.5);
+
+    // Inverting `a` turns its charge from 2 to -2.
+    let err = entity.transform("a", |s| s.invert()).unwrap_err();
+    assert!(matches!(
+        err,
+        EntityError::ChargeNotConserved { expected, actual, .. }
+            if expected == 1.0 && actual == -3.0
+    ));
+    assert_untouched(&entity);
+
+    // Fully decohering `b` drops the joint coherence to 10/23.
+    let err = entity.transform("b", |s| s.decohere(1.0)).unwrap_err();
+    assert!(matches!(err, EntityError::Decoherent { min, .. } if min == 0.5));
+    assert_untouched(&entity);
+
+    let mut broken = entity.clone();
+    assert!(matches!(
+        broken.transform("c", |s| s.field.matter.value = f64::NAN),
+        Err(EntityError::Quasi(QuasiError::NonFinite { .. }))
+    ));
+    assert_untouched(&broken);
+    assert_eq!(
+        entity.transform("z", |_| {}).unwrap_err(),
+        EntityError::UnknownState {
+            id: "z".to_string()
+        }
+    );
+
+    // Moving charge between branches of one state is allowed as long
+    // as the total holds.
+    entity
+        .transform("b", |s| {
+            s.field.matter.value = 2.5;
+            s.field.antimatter.value = 2.5;
+            s.field.refresh_coherence();
+        })
+        .unwrap();
+    assert_eq!(entity.charge(), 1.0);
+}
+
+#[test]
+fn duplicate_ids_are_refused() {
+    let mut twice = states();
+    twice.push(QuasiState::new("b", "energy", 1.0, 1.0));
+    assert_eq!(
+        QuasiEntity::bind("e", twice, 0.0).unwrap_err(),
+        EntityError::DuplicateState {
+            id: "b".to_string()
+        }
+    );
+
+    let mut entity = entity(0.5);
+    assert!(matches!(
+        entity.split(&["a", "a"], "part"),
+        Err(EntityError::DuplicateState { .. })
+    ));
+    let other =
+        QuasiEntity::bind("o", vec![QuasiState::new("c", "energy", 1.0, 1.0)], 0.0).unwrap();
+    assert!(matches!(
+        entity.merge(&other),
+        Err(EntityError::DuplicateState { .. })
+    ));
+    assert_untouched(&entity);
+}
+
+#[test]
+fn refused_splits_leave_the_entity_untouched() {
+    let mut entity = entity(0.5);
+    assert!(matches!(
+        entity.split(&["a", "b", "c"], "all"),
+        Err(EntityError::Empty { .. })
+    ));
+    assert!(matches!(
+        entity.split(&["z"], "part"),
+        Err(EntityError::UnknownState { .. })
+    ));
+    assert_untouched(&entity);
+
+    // Without `b`, `a` and `c` hold joint coherence 2/3.
+    let mut strict = QuasiEntity::bind("e", states(), 0.7).unwrap();
+    assert!(matches!(
+        strict.split(&["b"], "part"),
+        Err(EntityError::Decoherent { .. })
+    ));
+    assert_untouched(&strict);
+}
+
+#[test]
+fn split_then_merge_restores_the_charge() {
+    let mut entity = entity(0.5);
+    let part = entity.split(&["a"], "part").unwrap();
+    assert_eq!(part.charge(), 2.0);
+    assert_eq!(entity.charge(), -1.0);
+    assert_eq!(part.min_coherence(), 0.5);
+    assert!(!entity.contains("a") && part.contains("a"));
+
+    entity.merge(&part).unwrap();
+    assert_eq!(entity.charge(), 1.0);
+    assert_eq!(entity.states().len(), 3);
+    assert!((entity.joint_coherence() - 18.0 / 23.0).abs() < 1e-12);
+
+    // The stricter minimum wins.
+    let mut loose =
+        QuasiEntity::bind("l", vec![QuasiState::new("d", "energy", 1.0, 0.5)], 0.1).unwrap();
+    loose.merge(&entity).unwrap();
+    assert_eq!(loose.min_coherence(), 0.5);
+    assert_eq!(loose.charge(), 1.5);
+}
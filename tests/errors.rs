@@ -0,0 +1,106 @@
+use quasi::error::MAX_MAGNITUDE;
+use quasi::{Complex, QuasiError, QuasiState, SplitMix64, TransitionPolicy};
+
+#[test]
+fn try_new_rejects_invalid_input() {
+    assert_eq!(
+        QuasiState::try_new("", "energy", 1.0, 0.0).unwrap_err(),
+        QuasiError::EmptyId
+    );
+    for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(matches!(
+            QuasiState::try_new("s", "energy", bad, 0.0),
+            Err(QuasiError::NonFinite {
+                field: "matter",
+                ..
+            })
+        ));
+        assert!(matches!(
+            QuasiState::try_new("s", "energy", 0.0, bad),
+            Err(QuasiError::NonFinite {
+                field: "antimatter",
+                ..
+            })
+        ));
+    }
+    assert!(matches!(
+        QuasiState::try_new("s", "energy", 2.0 * MAX_MAGNITUDE, 0.0),
+        Err(QuasiError::OutOfRange {
+            field: "matter",
+            ..
+        })
+    ));
+    assert!(matches!(
+        QuasiState::try_from_amplitudes("s", "energy", Complex::new(0.0, f64::NAN), Complex::ONE),
+        Err(QuasiError::NonFinite { .. })
+    ));
+
+    let state = QuasiState::try_new("s", "energy", -MAX_MAGNITUDE, MAX_MAGNITUDE).unwrap();
+    assert!(state.measure_coherence().is_finite());
+}
+
+#[test]
+fn strict_policy_refuses_collapsed_states() {
+    let mut rng = SplitMix64::seed_from_u64(3);
+    let mut state = QuasiState::new("s", "energy", 3.0, 4.0);
+    state
+        .try_observe_with(TransitionPolicy::Strict, &mut rng)
+        .unwrap();
+    let collapsed = state.clone();
+
+    let err = state
+        .try_observe_with(TransitionPolicy::Strict, &mut rng)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        QuasiError::AlreadyObserved {
+            id: "s".to_string()
+        }
+    );
+    assert_eq!(err.to_string(), "state `s` has already been observed");
+    assert!(state.try_invert(TransitionPolicy::Strict).is_err());
+    assert_eq!(state.amplitudes(), collapsed.amplitudes());
+
+    // The default policy lets a collapsed state be observed and inverted.
+    assert_eq!(TransitionPolicy::default(), TransitionPolicy::Lenient);
+    state.try_invert(TransitionPolicy::Lenient).unwrap();
+    assert!(state.try_observe(TransitionPolicy::Lenient).is_ok());
+}
+
+#[test]
+fn transitions_refuse_invalid_fields_without_touching_them() {
+    let mut state = QuasiState::new("s", "energy", 1.0, 1.0);
+    state.field.coherence = f64::NAN;
+    assert!(matches!(
+        state.try_observe(TransitionPolicy::Lenient),
+        Err(QuasiError::NonFinite {
+            field: "coherence",
+            ..
+        })
+    ));
+    state.field.coherence = 1.5;
+    assert!(matches!(
+        state.try_invert(TransitionPolicy::Lenient),
+        Err(QuasiError::OutOfRange {
+            field: "coherence",
+            ..
+        })
+    ));
+    assert!(!state.observed);
+    assert_eq!(state.field.matter.value, 1.0);
+}
+
+#[test]
+fn messages_name_the_offending_value() {
+    let err = QuasiState::try_new("s", "energy", f64::NAN, 0.0).unwrap_err();
+    assert_eq!(err.to_string(), "matter must be finite, got NaN");
+    let err = QuasiState::try_new("s", "energy", 1e200, 0.0).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "matter = 1e200 is outside the allowed range [-1e150, 1e150]"
+    );
+    assert_eq!(
+        QuasiError::EmptyId.to_string(),
+        "state id must not be empty"
+    );
+}
@@ -0,0 +1,76 @@
+use quasi::{
+    DensityMatrix, Dynamics, Evolve, Hamiltonian, Matrix, QuasiError, QuasiState, Relaxation,
+    TrajectoryRecorder,
+};
+
+fn idle(t1: Option<f64>, t2: Option<f64>) -> Dynamics {
+    Dynamics::new(Hamiltonian::pauli(0.0, 0.0, 0.0))
+        .with_relaxation(Relaxation::new(t1, t2).unwrap())
+}
+
+#[test]
+fn t1_empties_the_antimatter_branch_exponentially() {
+    let dynamics = idle(Some(2.0), None);
+    for t in [0.5, 1.0, 4.0] {
+        let mut state = QuasiState::new("s", "energy", 0.0, 1.0);
+        dynamics.run(&mut state, t, 20);
+        let (_, p1) = state.field.born_weights();
+        assert!((p1 - (-t / 2.0_f64).exp()).abs() < 1e-9, "t = {}", t);
+    }
+}
+
+#[test]
+fn t2_decays_coherence_exponentially() {
+    for (t1, t2) in [(None, 3.0), (Some(2.0), 3.0), (Some(1.5), 3.0)] {
+        let dynamics = idle(t1, Some(t2));
+        let mut state = QuasiState::new("s", "energy", 1.0, 1.0);
+        dynamics.run(&mut state, 1.2, 12);
+        let expected = (-1.2 / t2).exp();
+        // T2 governs 2|ρ₀₁| whatever T1 does to the populations.
+        let off = 2.0 * state.density_matrix()[(0, 1)].abs();
+        assert!((off - expected).abs() < 1e-9, "{:?} {}", t1, off);
+    }
+}
+
+#[test]
+fn recorder_samples_at_every_interval() {
+    let dynamics = Dynamics::new(Hamiltonian::new(Matrix::zeros(4)))
+        .with_relaxation(Relaxation::new(None, Some(1.0)).unwrap());
+    let mut rho = DensityMatrix::from_states(&[
+        QuasiState::new("a", "energy", 1.0, 1.0),
+        QuasiState::new("b", "energy", 1.0, -1.0),
+    ]);
+    let trajectory = TrajectoryRecorder::new(0.5)
+        .substeps(4)
+        .record(&dynamics, &mut rho, 2.0);
+    let times: Vec<f64> = trajectory.times().collect();
+    assert_eq!(times, [0.0, 0.5, 1.0, 1.5, 2.0]);
+    for (t, c) in trajectory.samples {
+        assert!((c - (-t).exp()).abs() < 1e-9, "t = {}", t);
+    }
+    assert!((rho.measure_coherence() - (-2.0_f64).exp()).abs() < 1e-9);
+}
+
+#[test]
+fn unphysical_times_are_rejected() {
+    for (t1, t2) in [
+        (Some(0.0), None),
+        (Some(-1.0), None),
+        (None, Some(0.0)),
+        (None, Some(-2.0)),
+        (Some(1.0), Some(2.5)),
+    ] {
+        assert!(
+            matches!(Relaxation::new(t1, t2), Err(QuasiError::OutOfRange { .. })),
+            "{:?} {:?}",
+            t1,
+            t2
+        );
+    }
+    assert!(matches!(
+        Relaxation::new(Some(f64::NAN), None),
+        Err(QuasiError::NonFinite { .. })
+    ));
+    assert!(Relaxation::new(Some(1.0), Some(2.0)).is_ok());
+    assert!(Relaxation::new(None, Some(1e6)).is_ok());
+}
@@ -0,0 +1,92 @@
+use quasi::{FieldManifold, QuasiField, QuasiState};
+
+/// An even superposition, so `max_coherence` is 1, holding coherence `c`.
+fn field(c: f64) -> QuasiField {
+    let mut field = QuasiState::new("n", "energy", 1.0, 1.0).field;
+    field.coherence = c;
+    field
+}
+
+/// Nodes `n0`, `n1`, … with the given coherences and no curvature.
+fn manifold(coherences: &[f64]) -> FieldManifold {
+    let mut manifold = FieldManifold::new();
+    for (k, &c) in coherences.iter().enumerate() {
+        manifold.add_node(&format!("n{}", k), field(c), 0.0);
+    }
+    manifold
+}
+
+#[test]
+fn laplacian_on_a_weighted_path() {
+    let mut path = manifold(&[0.2, 0.5, 0.8]);
+    path.connect(0, 1, 1.0);
+    path.connect(1, 2, 2.0);
+    let expected = [0.3, 0.3, -0.6];
+    for (lap, want) in path.laplacians().into_iter().zip(expected) {
+        assert!((lap - want).abs() < 1e-12, "{} vs {}", lap, want);
+    }
+    assert_eq!(path.degree(1), 3.0);
+    assert!((path.gradient(1, 2).unwrap() - 0.6).abs() < 1e-12);
+    assert_eq!(path.gradient(0, 2), None);
+
+    // Dropping the first edge isolates node 0.
+    assert_eq!(path.disconnect(1, 0), Some(1.0));
+    assert_eq!(path.laplacian(0), 0.0);
+}
+
+#[test]
+fn diffusion_conserves_total_coherence_on_a_closed_graph() {
+    let mut ring = manifold(&[0.3, 0.4, 0.6, 0.7]);
+    for k in 0..4 {
+        ring.connect(k, (k + 1) % 4, 1.0 + k as f64 * 0.5);
+    }
+    let total: f64 = ring.coherences().iter().sum();
+    ring.evolve(5.0, 0.8);
+    let after = ring.coherences();
+    assert!((after.iter().sum::<f64>() - total).abs() < 1e-12);
+    for c in after {
+        assert!((c - 0.5).abs() < 1e-3, "{}", c);
+    }
+}
+
+#[test]
+fn curvature_damps_and_pumps() {
+    let mut manifold = FieldManifold::new();
+    manifold.add_node("damped", field(0.5), 1.0);
+    manifold.add_node("pumped", field(0.5), -1.0);
+    assert_eq!(manifold.coherence_rates(1.0), [-0.5, 0.5]);
+    manifold.evolve(0.2, 1.0);
+    let c = manifold.coherences();
+    assert!(c[0] < 0.5 && c[1] > 0.5 && c[1] <= 1.0);
+}
+
+#[test]
+#[should_panic(expected = "duplicate node id n0")]
+fn duplicate_ids_are_rejected() {
+    let mut manifold = manifold(&[0.5]);
+    manifold.add_node("n0", field(0.5), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "curvature must be finite")]
+fn non_finite_curvature_is_rejected() {
+    FieldManifold::new().add_node("n", field(0.5), f64::INFINITY);
+}
+
+#[test]
+#[should_panic(expected = "node index out of range")]
+fn neighbors_of_a_missing_node_panic() {
+    manifold(&[0.5]).neighbors(1).count();
+}
+
+#[test]
+#[should_panic(expected = "node index out of range")]
+fn degree_of_a_missing_node_panics() {
+    manifold(&[0.5]).degree(1);
+}
+
+#[test]
+#[should_panic(expected = "node index out of range")]
+fn laplacian_of_a_missing_node_panics() {
+    manifold(&[0.5]).laplacian(1);
+}
@@ -0,0 +1,128 @@
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4, PI};
+
+use quasi::gate::{
+    self, CNot, Gate, Phase, Rx, Ry, Rz, Sdg, Tdg, UnitaryGate, H, S, STANDARD, T, X, Y, Z,
+};
+use quasi::{Complex, Matrix, QuasiRegister};
+
+/// True if `a = e^{iφ}·b` for some global phase `φ`.
+fn eq_up_to_phase(a: &Matrix, b: &Matrix) -> bool {
+    let n = b.dim();
+    let (r, c) = (0..n)
+        .flat_map(|r| (0..n).map(move |c| (r, c)))
+        .find(|&(r, c)| b[(r, c)].abs() > 1e-9)
+        .unwrap();
+    let phase = a[(r, c)] * b[(r, c)].conj().scale(1.0 / b[(r, c)].norm_sqr());
+    (phase.abs() - 1.0).abs() < 1e-12 && a.max_abs_diff(&b.scale(phase)) < 1e-12
+}
+
+#[test]
+fn every_standard_gate_is_unitary() {
+    for (name, angles) in STANDARD {
+        for theta in [0.0, 0.3, -1.7, PI] {
+            let gate = gate::standard(name, &vec![theta; angles]).unwrap();
+            assert_eq!(gate.matrix().dim(), 1 << gate.arity());
+            assert!(gate.matrix().is_unitary(1e-12), "{} at {}", name, theta);
+            if angles == 0 {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn fixed_gate_matrices() {
+    let i = Complex::I;
+    let r = FRAC_1_SQRT_2;
+    let cases: [(&dyn Gate, Matrix); 8] = [
+        (&X, Matrix::from_real([[0.0, 1.0], [1.0, 0.0]])),
+        (
+            &Y,
+            Matrix::from_rows(vec![vec![Complex::ZERO, -i], vec![i, Complex::ZERO]]),
+        ),
+        (&Z, Matrix::from_real([[1.0, 0.0], [0.0, -1.0]])),
+        (&H, Matrix::from_real([[r, r], [r, -r]])),
+        (&S, Matrix::diagonal(&[Complex::ONE, i])),
+        (&Sdg, Matrix::diagonal(&[Complex::ONE, -i])),
+        (
+            &T,
+            Matrix::diagonal(&[Complex::ONE, Complex::cis(FRAC_PI_4)]),
+        ),
+        (
+            &Tdg,
+            Matrix::diagonal(&[Complex::ONE, Complex::cis(-FRAC_PI_4)]),
+        ),
+    ];
+    for (gate, expected) in cases {
+        assert!(
+            gate.matrix().max_abs_diff(&expected) < 1e-15,
+            "{}",
+            gate.name()
+        );
+    }
+    // S² = Z and T² = S.
+    assert!((&S.matrix() * &S.matrix()).max_abs_diff(&Z.matrix()) < 1e-15);
+    assert!((&T.matrix() * &T.matrix()).max_abs_diff(&S.matrix()) < 1e-15);
+}
+
+#[test]
+fn rotations_by_pi_match_the_paulis_up_to_phase() {
+    assert!(eq_up_to_phase(&Rx(PI).matrix(), &X.matrix()));
+    assert!(eq_up_to_phase(&Ry(PI).matrix(), &Y.matrix()));
+    assert!(eq_up_to_phase(&Rz(PI).matrix(), &Z.matrix()));
+    assert!(Phase(PI).matrix().max_abs_diff(&Z.matrix()) < 1e-15);
+    assert!(eq_up_to_phase(&Rz(PI / 2.0).matrix(), &S.matrix()));
+    assert!(!eq_up_to_phase(&Rx(PI / 2.0).matrix(), &X.matrix()));
+    assert!(Rx(0.0).matrix().max_abs_diff(&Matrix::identity(2)) < 1e-15);
+}
+
+#[test]
+fn cnot_controls_on_its_first_target() {
+    // Bit k of a basis index is state k.
+    let mut register = QuasiRegister::new(3);
+    register.apply(&X, &[2]);
+    register.apply(&CNot, &[2, 0]);
+    assert!((register.amplitudes()[0b101].re - 1.0).abs() < 1e-15);
+
+    // A control in the matter branch does nothing.
+    register.apply(&CNot, &[1, 0]);
+    assert!((register.amplitudes()[0b101].re - 1.0).abs() < 1e-15);
+
+    // Reversing the targets reverses the roles.
+    let mut register = QuasiRegister::new(2);
+    register.apply(&X, &[1]);
+    register.apply(&CNot, &[0, 1]);
+    assert!((register.amplitudes()[0b10].re - 1.0).abs() < 1e-15);
+    register.apply(&CNot, &[1, 0]);
+    assert!((register.amplitudes()[0b11].re - 1.0).abs() < 1e-15);
+}
+
+#[test]
+fn standard_lookup_checks_the_angle_count() {
+    assert!(gate::standard("h", &[]).is_some());
+    assert!(gate::standard("h", &[0.1]).is_none());
+    assert!(gate::standard("rx", &[]).is_none());
+    assert!(gate::standard("rx", &[0.1, 0.2]).is_none());
+    assert!(gate::standard("u3", &[0.1, 0.2, 0.3]).is_none());
+    let rx = gate::standard("rx", &[0.25]).unwrap();
+    assert_eq!(rx.params(), [0.25]);
+}
+
+#[test]
+fn unitary_gate_takes_its_arity_from_the_matrix() {
+    let swap = UnitaryGate::new("swap", gate::Swap.matrix());
+    assert_eq!(swap.arity(), 2);
+    assert_eq!(swap.name(), "swap");
+}
+
+#[test]
+#[should_panic(expected = "gate matrix must be unitary")]
+fn unitary_gate_rejects_non_unitary_matrices() {
+    UnitaryGate::new("amplify", Matrix::from_real([[2.0, 0.0], [0.0, 1.0]]));
+}
+
+#[test]
+#[should_panic(expected = "power of two")]
+fn unitary_gate_rejects_odd_dimensions() {
+    UnitaryGate::new("qutrit", Matrix::identity(3));
+}
@@ -0,0 +1,160 @@
+use quasi::topology::simplicial::{PersistencePair, SimplicialError, MAX_SIMPLEX_VERTICES};
+use quasi::{QuasiState, SimplicialComplex};
+
+/// An even superposition with its coherence set to `coherence`.
+fn state(coherence: f64) -> QuasiState {
+    let mut state = QuasiState::new("v", "energy", 1.0, 1.0);
+    state.field.coherence = coherence;
+    state
+}
+
+fn complex(vertices: usize, simplices: &[&[usize]]) -> SimplicialComplex {
+    let mut k = SimplicialComplex::new();
+    for _ in 0..vertices {
+        k.add_vertex(state(0.0));
+    }
+    for simplex in simplices {
+        k.add_simplex(simplex).unwrap();
+    }
+    k
+}
+
+#[test]
+fn betti_numbers_of_standard_spaces() {
+    let circle = complex(3, &[&[0, 1], &[1, 2], &[0, 2]]);
+    assert_eq!(circle.betti_numbers(), [1, 1]);
+
+    let sphere = complex(4, &[&[0, 1, 2], &[0, 1, 3], &[0, 2, 3], &[1, 2, 3]]);
+    assert_eq!(sphere.betti_numbers(), [1, 0, 1]);
+    assert_eq!(sphere.euler_characteristic(), 2);
+
+    // The 7-vertex torus: triangles {i, i+1, i+3} and {i, i+2, i+3} mod 7.
+    let mut faces = Vec::new();
+    for i in 0..7 {
+        faces.push([i, (i + 1) % 7, (i + 3) % 7]);
+        faces.push([i, (i + 2) % 7, (i + 3) % 7]);
+    }
+    let faces: Vec<&[usize]> = faces.iter().map(|f| &f[..]).collect();
+    let torus = complex(7, &faces);
+    assert_eq!(torus.betti_numbers(), [1, 2, 1]);
+    assert_eq!(torus.euler_characteristic(), 0);
+}
+
+#[test]
+fn persistence_follows_the_coherence_filtration() {
+    let mut k = SimplicialComplex::new();
+    for c in [0.1, 0.2, 0.3, 0.4] {
+        k.add_vertex(state(c));
+    }
+    k.add_simplex(&[0, 1]).unwrap();
+    k.add_simplex(&[1, 2]).unwrap();
+    k.add_simplex(&[0, 2]).unwrap();
+    k.attach(&[0, 1, 2], state(0.9)).unwrap();
+
+    assert_eq!(k.coherence(&[0, 2]), Some(0.3));
+    assert_eq!(k.coherence(&[0, 1, 2]), Some(0.9));
+    assert_eq!(
+        k.persistence(),
+        [
+            PersistencePair {
+                dimension: 0,
+                birth: 0.1,
+                death: None
+            },
+            PersistencePair {
+                dimension: 0,
+                birth: 0.4,
+                death: None
+            },
+            PersistencePair {
+                dimension: 1,
+                birth: 0.3,
+                death: Some(0.9)
+            },
+        ]
+    );
+    assert_eq!(k.betti_at(0.05), [0, 0, 0]);
+    assert_eq!(k.betti_at(0.15), [1, 0, 0]);
+    assert_eq!(k.betti_at(0.35), [1, 1, 0]);
+    assert_eq!(k.betti_at(0.5), [2, 1, 0]);
+    assert_eq!(k.betti_at(1.0), [2, 0, 0]);
+}
+
+#[test]
+fn betti_at_matches_the_sub_complex() {
+    // A filled square whose diagonal and triangles arrive last.
+    let mut k = SimplicialComplex::new();
+    for c in [0.1, 0.2, 0.3, 0.4, 0.5] {
+        k.add_vertex(state(c));
+    }
+    for edge in [[0, 1], [1, 2], [2, 3], [3, 0], [3, 4]] {
+        k.attach(&edge, state(0.5)).unwrap();
+    }
+    k.attach(&[0, 2], state(0.7)).unwrap();
+    k.attach(&[0, 1, 2], state(0.8)).unwrap();
+    k.attach(&[0, 2, 3], state(0.8)).unwrap();
+
+    for threshold in [0.0, 0.3, 0.5, 0.6, 0.7, 0.8, 1.0] {
+        let mut sub = SimplicialComplex::new();
+        for _ in 0..5 {
+            sub.add_vertex(state(0.0));
+        }
+        let mut alive = [false; 5];
+        for (simplex, value) in k.filtration() {
+            if value <= threshold {
+                if let [v] = simplex[..] {
+                    alive[v] = true;
+                } else {
+                    sub.add_simplex(&simplex).unwrap();
+                }
+            }
+        }
+        let mut expected = sub.betti_numbers();
+        expected.resize(3, 0);
+        // Vertices not yet in the filtration are not components.
+        expected[0] -= alive.iter().filter(|a| !**a).count();
+        assert_eq!(k.betti_at(threshold), expected, "threshold {}", threshold);
+    }
+}
+
+#[test]
+fn coherence_follows_changes_to_the_complex() {
+    let mut k = complex(3, &[&[0, 1, 2]]);
+    assert_eq!(k.coherence(&[2, 0, 1]), Some(0.0));
+    assert_eq!(k.coherence(&[0, 3]), None);
+    k.attach(&[1], state(0.6)).unwrap();
+    assert_eq!(k.coherence(&[0, 1]), Some(0.6));
+    assert_eq!(k.coherence(&[0, 2]), Some(0.0));
+    k.attach(&[0, 2], state(0.7)).unwrap();
+    assert_eq!(k.coherence(&[0, 1, 2]), Some(0.7));
+    let v = k.add_vertex(state(0.2));
+    assert_eq!(k.coherence(&[v]), Some(0.2));
+    k.add_simplex(&[0, v]).unwrap();
+    assert_eq!(k.coherence(&[0, v]), Some(0.2));
+    assert_eq!(k.filtration().len(), k.len());
+}
+
+#[test]
+fn oversized_simplices_are_refused() {
+    let n = MAX_SIMPLEX_VERTICES + 1;
+    let mut k = complex(n, &[]);
+    let all: Vec<usize> = (0..n).collect();
+    assert_eq!(
+        k.add_simplex(&all).unwrap_err(),
+        SimplicialError::TooLarge {
+            vertices: n,
+            max: MAX_SIMPLEX_VERTICES
+        }
+    );
+    assert!(k.attach(&all, state(0.5)).is_err());
+    assert_eq!(k.len(), n);
+
+    // The largest allowed simplex brings every one of its faces.
+    k.add_simplex(&all[1..]).unwrap();
+    assert_eq!(
+        k.len(),
+        n + (1 << MAX_SIMPLEX_VERTICES) - 1 - MAX_SIMPLEX_VERTICES
+    );
+    // A filled simplex plus the isolated vertex 0.
+    assert_eq!(k.euler_characteristic(), 2);
+}
@@ -0,0 +1,110 @@
+use quasi::{Iceberg, QuasiError, QuasiField, QuasiState, SplitMix64};
+
+fn field(matter: f64, antimatter: f64) -> QuasiField {
+    QuasiState::new("layer", "energy", matter, antimatter).field
+}
+
+#[test]
+fn couplings_may_not_sum_past_one() {
+    let mut iceberg = Iceberg::new(QuasiState::new("s", "energy", 1.0, 0.0));
+    iceberg.push_layer(field(1.0, 1.0), 0.6).unwrap();
+    assert!(matches!(
+        iceberg.push_layer(field(0.0, 1.0), 0.5),
+        Err(QuasiError::OutOfRange {
+            field: "total coupling",
+            ..
+        })
+    ));
+    assert!(matches!(
+        iceberg.push_layer(field(0.0, 1.0), -0.1),
+        Err(QuasiError::OutOfRange {
+            field: "coupling",
+            ..
+        })
+    ));
+    assert!(matches!(
+        iceberg.push_layer(field(0.0, 1.0), f64::NAN),
+        Err(QuasiError::NonFinite { .. })
+    ));
+    let mut invalid = field(1.0, 1.0);
+    invalid.coherence = 2.0;
+    assert!(iceberg.push_layer(invalid, 0.1).is_err());
+    assert_eq!(iceberg.depth(), 1);
+
+    iceberg.push_layer(field(0.0, 1.0), 0.4).unwrap();
+    assert_eq!(iceberg.depth(), 2);
+    assert!((iceberg.total_coupling() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+#[should_panic(expected = "total coupling")]
+fn with_layer_panics_on_rejection() {
+    Iceberg::new(QuasiState::new("s", "energy", 1.0, 0.0))
+        .with_layer(field(1.0, 1.0), 0.7)
+        .with_layer(field(1.0, 1.0), 0.7);
+}
+
+#[test]
+fn projection_mixes_layers_by_coupling() {
+    // (1 − 0.5 − 0.25)·|0⟩⟨0| + 0.5·|+⟩⟨+| + 0.25·|1⟩⟨1|
+    let mut iceberg = Iceberg::new(QuasiState::new("s", "energy", 2.0, 0.0))
+        .with_layer(field(1.0, 1.0), 0.5)
+        .with_layer(field(0.0, 3.0), 0.25);
+    let rho = iceberg.projected_density();
+    assert!((rho[(0, 0)].re - 0.5).abs() < 1e-12);
+    assert!((rho[(1, 1)].re - 0.5).abs() < 1e-12);
+    assert!((rho[(0, 1)].re - 0.25).abs() < 1e-12);
+
+    iceberg.surface.observed = true;
+    iceberg.project_depth();
+    let surface = &iceberg.surface;
+    assert!(!surface.observed);
+    assert_eq!(surface.id, "s");
+    assert!((surface.field.norm() - 2.0).abs() < 1e-12);
+    let (p0, p1) = surface.field.born_weights();
+    assert!((p0 - 0.5).abs() < 1e-12 && (p1 - 0.5).abs() < 1e-12);
+    assert!((surface.measure_coherence() - 0.5).abs() < 1e-12);
+    assert_eq!(iceberg.depth(), 2);
+}
+
+#[test]
+fn uncoupled_depth_leaves_the_surface_alone() {
+    let mut surface = QuasiState::new("s", "energy", 1.0, 0.0);
+    surface.observed = true;
+    let mut iceberg = Iceberg::new(surface).with_layer(field(1.0, 1.0), 0.0);
+    iceberg.project_depth();
+    assert!(iceberg.surface.observed);
+    assert_eq!(iceberg.surface.measure_coherence(), 0.0);
+}
+
+#[test]
+fn opposite_phases_cancel_in_aggregate() {
+    let plus = Iceberg::new(QuasiState::new("s", "energy", 1.0, 1.0));
+    assert!((plus.aggregate_coherence() - 1.0).abs() < 1e-12);
+
+    let cancelled = plus.clone().with_layer(field(1.0, -1.0), 0.0);
+    assert!(cancelled.aggregate_coherence() < 1e-12);
+
+    // A heavier layer outweighs the surface.
+    let tilted = plus.with_layer(field(2.0, -2.0), 0.0);
+    assert!((tilted.aggregate_coherence() - 0.6).abs() < 1e-12);
+}
+
+#[test]
+fn observation_does_not_reveal_depth() {
+    let mut iceberg = Iceberg::new(QuasiState::new("s", "energy", 1.0, 1.0))
+        .with_layer(field(1.0, 1.0), 0.3)
+        .with_layer(field(0.6, -0.8), 0.2);
+    let before = iceberg.layers().to_vec();
+    let mut rng = SplitMix64::seed_from_u64(5);
+    for _ in 0..3 {
+        iceberg.observe_with(&mut rng);
+    }
+    assert!(iceberg.surface.observed);
+    assert_eq!(iceberg.surface.measure_coherence(), 0.0);
+    for (layer, old) in iceberg.layers().iter().zip(&before) {
+        assert_eq!(layer.coupling, old.coupling);
+        assert_eq!(layer.field.amplitudes(), old.field.amplitudes());
+        assert_eq!(layer.field.coherence, old.field.coherence);
+    }
+}
@@ -0,0 +1,417 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use quasi::gate::{Gate, Rx, H};
+use quasi::memory::snapshot::crc32;
+use quasi::memory::store::{MemoryStore, StoreError};
+use quasi::memory::wal::{self, WalError, WalEvent, WAL_VERSION};
+use quasi::{BellKind, Branch, Complex, Matrix, QuasiMemory, QuasiState, SplitMix64};
+
+/// A single-state "gate" that doubles the matter amplitude.
+#[derive(Debug)]
+struct Amplify;
+
+impl Gate for Amplify {
+    fn name(&self) -> &str {
+        "amplify"
+    }
+
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn matrix(&self) -> Matrix {
+        Matrix::diagonal(&[Complex::real(2.0), Complex::ONE])
+    }
+}
+
+/// A fresh, empty directory under the system temp dir.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quasi-store-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn assert_same_memory(a: &QuasiMemory, b: &QuasiMemory) {
+    assert_eq!(a.frame(), b.frame());
+    let pairs = |m: &QuasiMemory| {
+        m.entanglement()
+            .pairs()
+            .map(|(x, y, k)| (x.to_string(), y.to_string(), k))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(pairs(a), pairs(b));
+    let a: Vec<_> = a.iter().collect();
+    let b: Vec<_> = b.iter().collect();
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(&b) {
+        assert_same_state(x, y);
+    }
+}
+
+fn assert_same_state(x: &QuasiState, y: &QuasiState) {
+    assert_eq!(x.id, y.id);
+    assert_eq!(x.observed, y.observed);
+    for (p, q) in [
+        (x.field.matter.value, y.field.matter.value),
+        (x.field.matter.phase, y.field.matter.phase),
+        (x.field.antimatter.value, y.field.antimatter.value),
+        (x.field.antimatter.phase, y.field.antimatter.phase),
+        (x.field.coherence, y.field.coherence),
+    ] {
+        assert_eq!(p.to_bits(), q.to_bits());
+    }
+}
+
+fn wal_file(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("wal-{}.log", generation))
+}
+
+/// Run a fixed workload, returning the memory and log length after each
+/// step.
+fn workload(store: &mut MemoryStore) -> Vec<(QuasiMemory, u64)> {
+    let mut rng = SplitMix64::seed_from_u64(11);
+    let mut steps = Vec::new();
+    let mut step = |store: &MemoryStore| {
+        let log = wal_file(store.dir(), store.generation());
+        steps.push((store.memory().clone(), fs::metadata(log).unwrap().len()));
+    };
+    step(store);
+    store
+        .allocate(QuasiState::new("a", "energy", 3.0, 4.0))
+        .unwrap();
+    step(store);
+    store
+        .allocate(QuasiState::new("b", "spin", 1.0, 1.0))
+        .unwrap();
+    step(store);
+    store.apply_gate("a", &Rx(0.7)).unwrap();
+    step(store);
+    store.commit_frame().unwrap();
+    step(store);
+    store.invert("b").unwrap();
+    step(store);
+    store.observe_with("a", &mut rng).unwrap();
+    step(store);
+    store.apply_gate("b", &H).unwrap();
+    step(store);
+    store.free("b").unwrap();
+    step(store);
+    store.commit_frame().unwrap();
+    step(store);
+    steps
+}
+
+#[test]
+fn reopen_replays_every_event() {
+    let dir = scratch_dir("reopen");
+    let mut store = MemoryStore::open(&dir).unwrap();
+    let steps = workload(&mut store);
+    let expected = store.memory().clone();
+    drop(store);
+
+    let store = MemoryStore::open(&dir).unwrap();
+    assert_eq!(store.recovery().replayed, steps.len() - 1);
+    assert_eq!(store.recovery().discarded, 0);
+    assert_same_memory(store.memory(), &expected);
+    // Frames sealed in the log stay readable after recovery.
+    let frame0 = store.memory().at_frame(0).unwrap();
+    assert!(frame0.contains("b"));
+    assert!(!store.memory().contains("b"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn every_truncated_tail_recovers_the_last_complete_event() {
+    let dir = scratch_dir("truncate");
+    let mut store = MemoryStore::open(&dir).unwrap();
+    let steps = workload(&mut store);
+    drop(store);
+    let full = fs::read(wal_file(&dir, 0)).unwrap();
+
+    let crash = dir.join("crash");
+    for cut in steps[0].1..=full.len() as u64 {
+        let _ = fs::remove_dir_all(&crash);
+        fs::create_dir_all(&crash).unwrap();
+        fs::write(wal_file(&crash, 0), &full[..cut as usize]).unwrap();
+
+        let (last, (expected, end)) = steps
+            .iter()
+            .enumerate()
+            .rfind(|(_, (_, end))| *end <= cut)
+            .unwrap();
+        let mut store = MemoryStore::open(&crash).unwrap();
+        assert_eq!(store.recovery().replayed, last, "cut at {}", cut);
+        assert_eq!(store.recovery().discarded, cut - end, "cut at {}", cut);
+        assert_same_memory(store.memory(), expected);
+
+        // The torn tail is gone, so new events append cleanly.
+        store
+            .allocate(QuasiState::new("late", "energy", 1.0, 0.0))
+            .unwrap();
+        drop(store);
+        let store = MemoryStore::open(&crash).unwrap();
+        assert_eq!(store.recovery().discarded, 0);
+        assert!(store.memory().contains("late"));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupt_last_record_is_discarded() {
+    let dir = scratch_dir("corrupt");
+    let mut store = MemoryStore::open(&dir).unwrap();
+    let steps = workload(&mut store);
+    drop(store);
+    let log = wal_file(&dir, 0);
+    let mut bytes = fs::read(&log).unwrap();
+    let at = bytes.len() - 6;
+    bytes[at] ^= 0x01;
+    fs::write(&log, bytes).unwrap();
+
+    let store = MemoryStore::open(&dir).unwrap();
+    let (expected, end) = &steps[steps.len() - 2];
+    assert_eq!(store.recovery().replayed, steps.len() - 2);
+    assert_eq!(fs::metadata(&log).unwrap().len(), *end);
+    assert_same_memory(store.memory(), expected);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn compaction_folds_the_log_into_a_snapshot() {
+    let dir = scratch_dir("compact");
+    let mut store = MemoryStore::open(&dir).unwrap().compact_every(4);
+    let _ = workload(&mut store);
+    let expected = store.memory().clone();
+    let generation = store.generation();
+    assert!(generation >= 2);
+    drop(store);
+
+    let mut names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            format!("snapshot-{}.snp", generation),
+            format!("wal-{}.log", generation)
+        ]
+    );
+
+    let store = MemoryStore::open(&dir).unwrap();
+    assert_eq!(store.recovery().generation, generation);
+    assert_same_memory(store.memory(), &expected);
+    assert!(store.memory().at_frame(0).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn crash_during_compaction_keeps_the_previous_generation() {
+    let dir = scratch_dir("midcompact");
+    let mut store = MemoryStore::open(&dir).unwrap();
+    let _ = workload(&mut store);
+    let expected = store.memory().clone();
+    drop(store);
+    // The next generation's log was created but its snapshot never landed.
+    fs::copy(wal_file(&dir, 0), wal_file(&dir, 1)).unwrap();
+    fs::write(dir.join("snapshot-1.snp.tmp"), b"QUASISNP partial").unwrap();
+
+    let store = MemoryStore::open(&dir).unwrap();
+    assert_eq!(store.generation(), 0);
+    assert_same_memory(store.memory(), &expected);
+    assert!(!wal_file(&dir, 1).exists());
+    assert!(!dir.join("snapshot-1.snp.tmp").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rejected_operations_are_not_logged() {
+    let dir = scratch_dir("rejected");
+    let mut store = MemoryStore::open(&dir).unwrap();
+    store
+        .allocate(QuasiState::new("a", "energy", 1.0, 1.0))
+        .unwrap();
+    let len = fs::metadata(wal_file(&dir, 0)).unwrap().len();
+
+    let mut invalid = QuasiState::new("bad", "energy", 1.0, 1.0);
+    invalid.field.coherence = f64::NAN;
+    assert!(matches!(store.allocate(invalid), Err(StoreError::Quasi(_))));
+    assert!(matches!(store.invert("ghost"), Err(StoreError::Memory(_))));
+    assert!(matches!(
+        store.allocate(QuasiState::new("a", "energy", 2.0, 0.0)),
+        Err(StoreError::Memory(_))
+    ));
+    assert!(matches!(
+        store.entangle("a", "a", BellKind::Phi),
+        Err(StoreError::Memory(_))
+    ));
+    assert!(matches!(
+        store.entangle("a", "ghost", BellKind::Phi),
+        Err(StoreError::Memory(_))
+    ));
+    assert!(matches!(
+        store.disentangle("ghost"),
+        Err(StoreError::Memory(_))
+    ));
+    assert!(matches!(
+        store.apply_gate("a", &Amplify),
+        Err(StoreError::Wal(WalError::NonUnitaryGate { .. }))
+    ));
+    assert_eq!(fs::metadata(wal_file(&dir, 0)).unwrap().len(), len);
+
+    store
+        .allocate(QuasiState::new("b", "energy", 1.0, 1.0))
+        .unwrap();
+    store.entangle("a", "b", BellKind::Phi).unwrap();
+    let len = fs::metadata(wal_file(&dir, 0)).unwrap().len();
+    // Mixing an entangled state's branches is refused by the memory.
+    assert!(matches!(
+        store.apply_gate("a", &H),
+        Err(StoreError::Memory(_))
+    ));
+    assert_eq!(fs::metadata(wal_file(&dir, 0)).unwrap().len(), len);
+    drop(store);
+    assert_eq!(MemoryStore::open(&dir).unwrap().recovery().replayed, 3);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Entangle three states into a chain, then mutate around the pairs.
+fn entangled_workload(store: &mut MemoryStore) {
+    for (id, m, a) in [("a", 3.0, 4.0), ("b", 1.0, 1.0), ("c", 2.0, 0.5)] {
+        store.allocate(QuasiState::new(id, "energy", m, a)).unwrap();
+    }
+    store.entangle("a", "b", BellKind::Phi).unwrap();
+    store.commit_frame().unwrap();
+    store.entangle("b", "c", BellKind::Psi).unwrap();
+    store.invert("c").unwrap();
+    store
+        .allocate(QuasiState::new("d", "energy", 1.0, 2.0))
+        .unwrap();
+    store.entangle("d", "a", BellKind::Psi).unwrap();
+    store.disentangle("d").unwrap();
+}
+
+#[test]
+fn entanglement_survives_log_replay_and_compaction() {
+    for compact in [None, Some(3)] {
+        let dir = scratch_dir(&format!("entangle-{:?}", compact));
+        let mut store = MemoryStore::open(&dir).unwrap();
+        if let Some(records) = compact {
+            store = store.compact_every(records);
+        }
+        entangled_workload(&mut store);
+        let expected = store.memory().clone();
+        assert_eq!(expected.entanglement().pairs().count(), 2);
+        drop(store);
+
+        let mut store = MemoryStore::open(&dir).unwrap();
+        if compact.is_some() {
+            assert!(store.recovery().generation > 0);
+            assert!(store.recovery().restored > 0);
+        }
+        assert_same_memory(store.memory(), &expected);
+        // c was inverted after pairing Ψ with b, so now it follows b.
+        assert_eq!(store.memory().correlated("c").len(), 2);
+        store
+            .observe_with("a", &mut SplitMix64::seed_from_u64(5))
+            .unwrap();
+        let branch = |m: &QuasiMemory, id: &str| {
+            if m.get(id).unwrap().field.matter.amplitude().abs() > 0.0 {
+                Branch::Matter
+            } else {
+                Branch::Antimatter
+            }
+        };
+        let m = store.memory();
+        assert!(["a", "b", "c"].iter().all(|id| m.get(id).unwrap().observed));
+        assert_eq!(branch(m, "a"), branch(m, "b"));
+        assert_eq!(branch(m, "b"), branch(m, "c"));
+        assert!(!m.get("d").unwrap().observed);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+fn collapse_follows_the_pair_relation() {
+    for (kind, flips, same) in [
+        (BellKind::Phi, 0, true),
+        (BellKind::Psi, 0, false),
+        (BellKind::Phi, 1, false),
+        (BellKind::Psi, 1, true),
+        (BellKind::Phi, 2, true),
+    ] {
+        for seed in 0..8 {
+            let mut memory = QuasiMemory::new();
+            memory
+                .allocate(QuasiState::new("a", "energy", 1.0, 1.0))
+                .unwrap();
+            memory
+                .allocate(QuasiState::new("b", "energy", 2.0, -2.0))
+                .unwrap();
+            memory.entangle("a", "b", kind).unwrap();
+            for _ in 0..flips {
+                memory.invert("b").unwrap();
+            }
+            let seen = memory
+                .observe_with("a", &mut SplitMix64::seed_from_u64(seed))
+                .unwrap();
+            let b = memory.get("b").unwrap();
+            assert!(b.observed);
+            assert!(!memory.entanglement().is_entangled("a"));
+            let partner = if b.field.antimatter.amplitude().abs() == 0.0 {
+                Branch::Matter
+            } else {
+                Branch::Antimatter
+            };
+            assert_eq!(partner == seen.branch, same, "{:?} {}", kind, flips);
+        }
+    }
+}
+
+/// A log of `events` whose header claims format `version`.
+fn log_as(version: u16, events: &[WalEvent]) -> Vec<u8> {
+    let mut bytes = wal::header(0).to_vec();
+    bytes[8..10].copy_from_slice(&version.to_le_bytes());
+    let crc = crc32(&bytes[..20]);
+    bytes[20..24].copy_from_slice(&crc.to_le_bytes());
+    for event in events {
+        bytes.extend(wal::encode(event).unwrap());
+    }
+    bytes
+}
+
+#[test]
+fn log_versions_gate_what_is_read() {
+    let allocate = WalEvent::Allocate(QuasiState::new("a", "energy", 1.0, 1.0));
+    for version in [0, WAL_VERSION + 1] {
+        let err = wal::read_wal(&log_as(version, &[])).unwrap_err();
+        assert!(matches!(
+            err,
+            WalError::UnsupportedVersion { found, supported: WAL_VERSION } if found == version
+        ));
+        assert!(err.to_string().contains("is not supported"), "{}", err);
+    }
+    let v1 = wal::read_wal(&log_as(1, &[allocate.clone(), WalEvent::Commit])).unwrap();
+    assert_eq!(v1.events.len(), 2);
+
+    // Entanglement records arrived in version 2.
+    let entangle = WalEvent::Entangle {
+        a: "a".to_string(),
+        b: "b".to_string(),
+        kind: BellKind::Phi,
+    };
+    let disentangle = WalEvent::Disentangle {
+        id: "a".to_string(),
+    };
+    for event in [entangle, disentangle] {
+        let events = [allocate.clone(), event];
+        assert_eq!(wal::read_wal(&log_as(2, &events)).unwrap().events.len(), 2);
+        assert!(matches!(
+            wal::read_wal(&log_as(1, &events)),
+            Err(WalError::InvalidRecord { reason, .. }) if reason.starts_with("unknown tag")
+        ));
+    }
+}
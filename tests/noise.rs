@@ -0,0 +1,151 @@
+use quasi::gate::X;
+use quasi::{
+    Circuit, DensityMatrixBackend, Executor, KrausChannel, Matrix, NoiseModel, QuasiState,
+    StateVectorBackend,
+};
+
+fn plus() -> QuasiState {
+    QuasiState::new("s", "energy", 1.0, 1.0)
+}
+
+fn antimatter() -> QuasiState {
+    QuasiState::new("s", "energy", 0.0, 1.0)
+}
+
+/// `state` after `channel`, as `(p₁, coherence)`.
+fn through(mut state: QuasiState, channel: &KrausChannel) -> (f64, f64) {
+    state.apply_channel(channel);
+    (state.field.born_weights().1, state.measure_coherence())
+}
+
+fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12
+}
+
+#[test]
+fn channels_match_their_analytic_effect() {
+    for p in [0.0, 0.1, 0.5, 1.0] {
+        let damping = KrausChannel::amplitude_damping(p);
+        assert!(close(through(antimatter(), &damping), (1.0 - p, 0.0)));
+        assert!(close(
+            through(plus(), &damping),
+            (0.5 * (1.0 - p), (1.0 - p).sqrt())
+        ));
+
+        let dephasing = KrausChannel::phase_damping(p);
+        assert!(close(through(plus(), &dephasing), (0.5, (1.0 - p).sqrt())));
+        assert!(close(through(antimatter(), &dephasing), (1.0, 0.0)));
+
+        let depolarizing = KrausChannel::depolarizing(p);
+        assert!(close(through(plus(), &depolarizing), (0.5, 1.0 - p)));
+        assert!(close(
+            through(antimatter(), &depolarizing),
+            (1.0 - p / 2.0, 0.0)
+        ));
+
+        let bit_flip = KrausChannel::bit_flip(p);
+        assert!(close(through(antimatter(), &bit_flip), (1.0 - p, 0.0)));
+        // |+⟩ is an eigenstate of X.
+        assert!(close(through(plus(), &bit_flip), (0.5, 1.0)));
+
+        let phase_flip = KrausChannel::phase_flip(p);
+        assert!(close(
+            through(plus(), &phase_flip),
+            (0.5, (1.0 - 2.0 * p).abs())
+        ));
+    }
+}
+
+#[test]
+fn noise_lowers_measured_coherence() {
+    let mut state = plus();
+    let before = state.measure_coherence();
+    state.apply_channel(&KrausChannel::depolarizing(0.2));
+    assert!(state.measure_coherence() < before);
+    // Probabilities outside [0, 1] are clamped.
+    assert!(close(
+        through(plus(), &KrausChannel::phase_damping(7.0)),
+        (0.5, 0.0)
+    ));
+}
+
+#[test]
+#[should_panic(expected = "Σ K†K = I")]
+fn incomplete_kraus_sets_are_rejected() {
+    KrausChannel::new("lossy", vec![Matrix::from_real([[1.0, 0.0], [0.0, 0.5]])]);
+}
+
+#[test]
+#[should_panic(expected = "2×2")]
+fn kraus_operators_act_on_one_state() {
+    KrausChannel::new("wide", vec![Matrix::identity(4)]);
+}
+
+fn flips(gates: usize) -> Circuit {
+    let mut circuit = Circuit::new(1, 1);
+    for _ in 0..gates {
+        circuit.gate(X, &[0]);
+    }
+    circuit.measure(0, 0);
+    circuit
+}
+
+#[test]
+fn executor_applies_noise_after_each_configured_gate() {
+    let run = |noise: NoiseModel, gates: usize| {
+        Executor::new(StateVectorBackend::new())
+            .shots(400)
+            .seed(11)
+            .noise(noise)
+            .run(&flips(gates))
+    };
+    let noiseless = run(NoiseModel::new(), 1);
+    assert_eq!(noiseless.count("1"), 400);
+
+    let noisy = run(
+        NoiseModel::new().with_gate("x", KrausChannel::bit_flip(0.3)),
+        1,
+    );
+    assert_ne!(noisy.histogram, noiseless.histogram);
+    assert!(
+        (noisy.frequency("0") - 0.3).abs() < 0.06,
+        "{:?}",
+        noisy.histogram
+    );
+
+    // Noise on other gates leaves `x` alone, and a gate's own channels
+    // replace the default.
+    let elsewhere = NoiseModel::new().with_gate("h", KrausChannel::bit_flip(1.0));
+    assert_eq!(run(elsewhere, 1).histogram, noiseless.histogram);
+    let overridden = NoiseModel::new()
+        .with_default(KrausChannel::bit_flip(1.0))
+        .with_gate("x", KrausChannel::phase_flip(0.5));
+    assert_eq!(run(overridden, 1).histogram, noiseless.histogram);
+
+    // A certain flip after each of three `x` gates undoes every one of them.
+    let certain = NoiseModel::new().with_default(KrausChannel::bit_flip(1.0));
+    assert_eq!(run(certain.clone(), 3).count("0"), 400);
+    assert_eq!(run(certain, 2).count("0"), 400);
+}
+
+#[test]
+fn backends_agree_on_noisy_runs() {
+    let noise = NoiseModel::new().with_gate("x", KrausChannel::amplitude_damping(0.4));
+    let statevector = Executor::new(StateVectorBackend::new())
+        .shots(2000)
+        .seed(5)
+        .noise(noise.clone())
+        .run(&flips(1));
+    let density = Executor::new(DensityMatrixBackend::new())
+        .shots(2000)
+        .seed(5)
+        .noise(noise)
+        .run(&flips(1));
+    for result in [&statevector, &density] {
+        assert!(
+            (result.frequency("0") - 0.4).abs() < 0.04,
+            "{:?}",
+            result.histogram
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::f64::consts::PI;
+
+use quasi::circuit::{Condition, Operation};
+use quasi::gate::{CNot, Phase, Rx, Ry, Rz, Sdg, Swap, Tdg, UnitaryGate, CZ, H, S, T, X, Y, Z};
+use quasi::qasm::{export, parse, QasmError};
+use quasi::{Circuit, Executor, Matrix, StateVectorBackend};
+
+/// Structural equality: same sizes and the same operations in order.
+fn assert_same_circuit(a: &Circuit, b: &Circuit) {
+    assert_eq!(a.num_states(), b.num_states());
+    assert_eq!(a.num_bits(), b.num_bits());
+    assert_eq!(a.operations().len(), b.operations().len());
+    for (x, y) in a.operations().iter().zip(b.operations()) {
+        assert_same_op(x, y);
+    }
+}
+
+fn assert_same_op(a: &Operation, b: &Operation) {
+    match (a, b) {
+        (
+            Operation::Gate {
+                gate: g,
+                targets: t,
+            },
+            Operation::Gate {
+                gate: h,
+                targets: u,
+            },
+        ) => {
+            assert_eq!(g.name(), h.name());
+            let (p, q) = (g.params(), h.params());
+            assert_eq!(p.len(), q.len());
+            for (x, y) in p.iter().zip(&q) {
+                assert_eq!(x.to_bits(), y.to_bits(), "angle {} vs {}", x, y);
+            }
+            assert_eq!(t, u);
+        }
+        (Operation::Measure { index: i, bit: b }, Operation::Measure { index: j, bit: c }) => {
+            assert_eq!((i, b), (j, c))
+        }
+        (
+            Operation::Conditional {
+                condition: c,
+                op: o,
+            },
+            Operation::Conditional {
+                condition: d,
+                op: p,
+            },
+        ) => {
+            assert_eq!(c, d);
+            assert_same_op(o, p);
+        }
+        _ => panic!("operations differ: {:?} vs {:?}", a, b),
+    }
+}
+
+fn round_trip(circuit: &Circuit) {
+    let text = export(circuit).unwrap();
+    let parsed = parse(&text).unwrap();
+    assert_same_circuit(circuit, &parsed);
+    assert_eq!(export(&parsed).unwrap(), text);
+}
+
+#[test]
+fn every_standard_gate_round_trips() {
+    let mut c = Circuit::new(3, 0);
+    c.gate(X, &[0])
+        .gate(Y, &[1])
+        .gate(Z, &[2])
+        .gate(H, &[0])
+        .gate(S, &[1])
+        .gate(Sdg, &[1])
+        .gate(T, &[2])
+        .gate(Tdg, &[2])
+        .gate(CNot, &[2, 0])
+        .gate(CZ, &[0, 1])
+        .gate(Swap, &[1, 2]);
+    round_trip(&c);
+}
+
+#[test]
+fn angles_round_trip_bit_for_bit() {
+    let mut c = Circuit::new(1, 0);
+    for theta in [
+        PI,
+        -PI / 2.0,
+        3.0 * PI / 4.0,
+        0.1,
+        -1e-17,
+        12345.678,
+        0.0,
+        2.0 / 3.0,
+    ] {
+        c.gate(Rx(theta), &[0])
+            .gate(Ry(theta), &[0])
+            .gate(Rz(theta), &[0])
+            .gate(Phase(theta), &[0]);
+    }
+    round_trip(&c);
+    let text = export(&c).unwrap();
+    assert!(text.contains("rx(pi) q[0];"));
+    assert!(text.contains("ry(-pi/2) q[0];"));
+    assert!(text.contains("rz(3*pi/4) q[0];"));
+}
+
+#[test]
+fn measurements_and_conditionals_round_trip() {
+    let mut c = Circuit::new(3, 3);
+    c.gate(H, &[0])
+        .measure(0, 0)
+        .gate_if(Condition::bit(0, true), X, &[1])
+        .measure(1, 1)
+        .gate_if(Condition::register(vec![0, 1, 2], 3), Z, &[2])
+        .gate_if(Condition::register(vec![2, 0], 1), H, &[2])
+        .push_conditional(
+            Condition::bit(1, false),
+            Operation::Measure { index: 2, bit: 2 },
+        );
+    round_trip(&c);
+}
+
+#[test]
+fn parses_the_supported_subset() {
+    let source = r#"
+        OPENQASM 3;
+        include "stdgates.inc";
+        /* two registers of each kind */
+        qubit[2] a;
+        qubit b;
+        bit[2] m;
+        bit flag;
+        h a;                      // broadcast
+        cnot a[0], b;
+        rz(π/4 + pi/4) a[1];
+        m = measure a;
+        measure b -> flag;
+        if (m == 3) { x b; z a[0]; }
+        if (!flag && m[1]) phase(-pi) b;
+    "#;
+    let c = parse(source).unwrap();
+    assert_eq!((c.num_states(), c.num_bits()), (3, 3));
+    let expected = r#"OPENQASM 3.0;
+include "stdgates.inc";
+qubit[3] q;
+bit[3] c;
+h q[0];
+h q[1];
+cx q[0], q[2];
+rz(pi/2) q[1];
+c[0] = measure q[0];
+c[1] = measure q[1];
+c[2] = measure q[2];
+if (c[0] == 1 && c[1] == 1) x q[2];
+if (c[0] == 1 && c[1] == 1) z q[0];
+if (c[2] == 0 && c[1] == 1) p(-pi) q[2];
+"#;
+    assert_eq!(export(&c).unwrap(), expected);
+}
+
+#[test]
+fn round_tripped_circuits_sample_identically() {
+    let mut c = Circuit::new(3, 3);
+    c.gate(H, &[0])
+        .gate(CNot, &[0, 1])
+        .gate(Ry(0.7), &[2])
+        .measure_all()
+        .gate_if(Condition::bit(2, true), X, &[0]);
+    let parsed = parse(&export(&c).unwrap()).unwrap();
+    let run = |circuit: &Circuit| {
+        Executor::new(StateVectorBackend::new())
+            .shots(500)
+            .seed(11)
+            .run(circuit)
+            .histogram
+    };
+    assert_eq!(run(&c), run(&parsed));
+}
+
+#[test]
+fn errors_point_at_the_offending_token() {
+    let err = parse("qubit[2] q;\nh q[0];\nfoo q[1];\n").unwrap_err();
+    assert!(
+        matches!(
+            err,
+            QasmError::Parse {
+                line: 3,
+                column: 1,
+                ..
+            }
+        ),
+        "{:?}",
+        err
+    );
+    let err = parse("qubit[2] q;\ncx q[0], q[5];").unwrap_err();
+    assert!(matches!(err, QasmError::Parse { line: 2, .. }), "{:?}", err);
+    assert!(parse("OPENQASM 2.0;").is_err());
+    assert!(parse("qubit q; h q").is_err());
+}
+
+#[test]
+fn wide_register_conditions_are_rejected_not_panicking() {
+    let err = parse("bit[70] c; qubit q; if (c == 5) x q;").unwrap_err();
+    assert!(matches!(err, QasmError::Parse { line: 1, .. }), "{:?}", err);
+    // A full 64-bit register still compares against any value.
+    let c = parse("bit[64] c; qubit q; if (c == 18446744073709551615) x q;").unwrap();
+    assert_eq!(c.num_bits(), 64);
+}
+
+#[test]
+fn custom_gates_do_not_export() {
+    let mut c = Circuit::new(1, 0);
+    c.gate(UnitaryGate::new("h", Matrix::identity(2)), &[0]);
+    assert!(matches!(export(&c), Err(QasmError::Export { .. })));
+}
@@ -0,0 +1,146 @@
+use quasi::qec::{MatchingDecoder, Pauli, MAX_STATES};
+use quasi::{MemoryExperiment, StabilizerCode};
+
+/// Decode a data error seen by perfect checks and report whether the
+/// corrected data is back in the code space with its logical value.
+fn corrects(code: &StabilizerCode, pauli: Pauli, errors: &[usize]) -> bool {
+    let decoder = MatchingDecoder::new(code, pauli, 1, 0.01, 0.01);
+    let mut data = vec![false; code.num_data()];
+    for &q in errors {
+        data[q] = true;
+    }
+    let syndrome = code.syndrome(pauli, &data);
+    let correction =
+        decoder.decode(&decoder.detections(std::slice::from_ref(&syndrome), &syndrome));
+    for (d, c) in data.iter_mut().zip(correction) {
+        *d ^= c;
+    }
+    let logical = code.logical(pauli).iter().filter(|&&q| data[q]).count();
+    code.syndrome(pauli, &data).iter().all(|s| !s) && logical % 2 == 0
+}
+
+#[test]
+fn surface_code_layout() {
+    for d in [3, 5] {
+        let code = StabilizerCode::surface(d);
+        assert_eq!(code.num_data(), d * d);
+        assert_eq!(code.stabilizers().len(), d * d - 1);
+        assert_eq!(code.checks(Pauli::X).len(), (d * d - 1) / 2);
+        assert_eq!(code.logical(Pauli::Z).len(), d);
+    }
+}
+
+#[test]
+fn matching_corrects_every_error_below_half_the_distance() {
+    for d in [3, 5] {
+        let code = StabilizerCode::surface(d);
+        let n = code.num_data();
+        for pauli in [Pauli::X, Pauli::Z] {
+            for a in 0..n {
+                assert!(corrects(&code, pauli, &[a]), "d={} {} on {}", d, pauli, a);
+                if d == 5 {
+                    for b in a + 1..n {
+                        assert!(corrects(&code, pauli, &[a, b]));
+                    }
+                }
+            }
+        }
+    }
+    let code = StabilizerCode::repetition(7);
+    assert!(corrects(&code, Pauli::Z, &[0, 3, 6]));
+    assert!(!corrects(&code, Pauli::Z, &[0, 1, 2, 3]));
+}
+
+#[test]
+fn a_readout_error_is_matched_in_time() {
+    let code = StabilizerCode::repetition(3);
+    let decoder = MatchingDecoder::new(&code, Pauli::Z, 2, 0.01, 0.01);
+    // The first check misreads in round 0 only.
+    let rounds = [vec![true, false], vec![false, false]];
+    let events = decoder.detections(&rounds, &[false, false]);
+    assert_eq!(events, [0, 2]);
+    assert_eq!(decoder.decode(&events), [false; 3]);
+}
+
+#[test]
+fn noiseless_memory_never_fails() {
+    for (code, basis) in [
+        (StabilizerCode::repetition(3), Pauli::Z),
+        (StabilizerCode::surface(3), Pauli::Z),
+        (StabilizerCode::surface(3), Pauli::X),
+    ] {
+        let result = MemoryExperiment::new(code)
+            .basis(basis)
+            .rounds(2)
+            .shots(4)
+            .seed(3)
+            .run();
+        assert_eq!(result.failures, 0);
+    }
+}
+
+#[test]
+fn repetition_code_suppresses_bit_flips() {
+    let experiment = MemoryExperiment::new(StabilizerCode::repetition(5))
+        .rounds(3)
+        .shots(400)
+        .seed(7);
+    let noisy = experiment
+        .clone()
+        .data_error(0.1)
+        .measurement_error(0.02)
+        .run();
+    // Unencoded, a state flips with probability ≈ 3 · 0.05 over three
+    // rounds.
+    assert!(noisy.rate() < 0.05, "{}", noisy);
+
+    let sweep = experiment.sweep(&[0.02, 0.3]);
+    assert!(sweep[0].rate() < sweep[1].rate());
+}
+
+#[test]
+#[should_panic(expected = "surface-5 needs 26 register entries")]
+fn large_codes_are_refused() {
+    assert!(StabilizerCode::surface(5).num_states() > MAX_STATES);
+    MemoryExperiment::new(StabilizerCode::surface(5)).run();
+}
+
+#[test]
+#[should_panic(expected = "repetition-3 has no X checks")]
+fn repetition_codes_do_not_protect_the_x_basis() {
+    MemoryExperiment::new(StabilizerCode::repetition(3))
+        .basis(Pauli::X)
+        .run();
+}
+
+#[test]
+fn error_probabilities_may_span_the_unit_interval() {
+    let experiment = MemoryExperiment::new(StabilizerCode::repetition(3))
+        .data_error(0.0)
+        .measurement_error(1.0);
+    experiment.data_error(1.0).measurement_error(0.0);
+}
+
+#[test]
+#[should_panic(expected = "data error probability must be in [0, 1]")]
+fn data_error_above_one_is_refused() {
+    MemoryExperiment::new(StabilizerCode::repetition(3)).data_error(1.5);
+}
+
+#[test]
+#[should_panic(expected = "data error probability must be in [0, 1]")]
+fn negative_data_error_is_refused() {
+    MemoryExperiment::new(StabilizerCode::repetition(3)).data_error(-0.1);
+}
+
+#[test]
+#[should_panic(expected = "measurement error probability must be in [0, 1]")]
+fn nan_measurement_error_is_refused() {
+    MemoryExperiment::new(StabilizerCode::repetition(3)).measurement_error(f64::NAN);
+}
+
+#[test]
+#[should_panic(expected = "data error probability must be in [0, 1]")]
+fn sweeps_check_their_rates() {
+    MemoryExperiment::new(StabilizerCode::repetition(3)).sweep(&[0.1, f64::INFINITY]);
+}
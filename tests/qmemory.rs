@@ -0,0 +1,226 @@
+use quasi::gate::{Gate, Rx, H, S, X, Y};
+use quasi::memory::qmemory::MemoryError;
+use quasi::memory::qtype::Operation;
+use quasi::{BellKind, Branch, QType, QTypeRegistry, QuasiError, QuasiMemory, QuasiState};
+
+fn matter(memory: &QuasiMemory, frame: u64, id: &str) -> Option<f64> {
+    let frame = memory.at_frame(frame).unwrap();
+    frame.get(id).map(|s| s.field.matter.value)
+}
+
+/// Frame 0: `a` = (1, 0) and `gone`. Frame 1: `a` flipped, `b` added,
+/// `gone` freed. Frame 2: `a` inverted back. Frame 3 is open.
+fn history() -> QuasiMemory {
+    let mut memory = QuasiMemory::new();
+    memory
+        .allocate(QuasiState::new("a", "energy", 1.0, 0.0))
+        .unwrap();
+    memory
+        .allocate(QuasiState::new("gone", "energy", 1.0, 1.0))
+        .unwrap();
+    assert_eq!(memory.commit_frame(), 0);
+    memory.apply_gate("a", &X).unwrap();
+    memory
+        .allocate(QuasiState::new("b", "energy", 2.0, 0.0))
+        .unwrap();
+    memory.free("gone").unwrap();
+    assert_eq!(memory.commit_frame(), 1);
+    memory.invert("a").unwrap();
+    assert_eq!(memory.commit_frame(), 2);
+    memory
+}
+
+#[test]
+fn sealed_frames_do_not_change() {
+    let mut memory = history();
+    assert_eq!(memory.frame(), 3);
+    assert_eq!(matter(&memory, 0, "a"), Some(1.0));
+    assert_eq!(matter(&memory, 1, "a"), Some(0.0));
+    assert_eq!(matter(&memory, 2, "a"), Some(1.0));
+
+    let frame0 = memory.at_frame(0).unwrap();
+    assert_eq!(frame0.number(), 0);
+    assert!(frame0.contains("gone") && !frame0.contains("b"));
+    let ids: Vec<&str> = memory
+        .at_frame(1)
+        .unwrap()
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect();
+    assert_eq!(ids, ["a", "b"]);
+
+    // Writes to the open frame only show up there, and the last write
+    // to it wins.
+    memory.apply_gate("a", &X).unwrap();
+    memory.apply_gate("a", &X).unwrap();
+    memory.apply_gate("a", &X).unwrap();
+    memory.free("b").unwrap();
+    assert_eq!(matter(&memory, 3, "a"), Some(0.0));
+    assert!(!memory.at_frame(3).unwrap().contains("b"));
+    assert_eq!(matter(&memory, 2, "a"), Some(1.0));
+    assert_eq!(matter(&memory, 2, "b"), Some(2.0));
+    assert_eq!(memory.at_frame(0).unwrap().len(), 2);
+}
+
+#[test]
+fn future_and_pruned_frames_are_refused() {
+    let mut memory = history();
+    assert!(matches!(
+        memory.at_frame(4),
+        Err(MemoryError::FutureFrame {
+            frame: 4,
+            current: 3
+        })
+    ));
+    memory.retain_from(2);
+    assert_eq!(memory.oldest_frame(), 2);
+    assert!(matches!(
+        memory.at_frame(1),
+        Err(MemoryError::PrunedFrame {
+            frame: 1,
+            oldest: 2
+        })
+    ));
+    assert!(memory.at_frame(2).is_ok());
+
+    let fresh = QuasiMemory::starting_at(5);
+    assert_eq!(fresh.frame(), 5);
+    assert!(matches!(
+        fresh.at_frame(4),
+        Err(MemoryError::PrunedFrame { oldest: 5, .. })
+    ));
+    assert!(fresh.at_frame(5).unwrap().is_empty());
+}
+
+#[test]
+fn retain_from_drops_only_older_history() {
+    let mut memory = history();
+    memory.retain_from(1);
+    // Frame 1 still reads `a` and `b` as they were; the freed state is
+    // gone from every readable frame.
+    assert_eq!(matter(&memory, 1, "a"), Some(0.0));
+    assert_eq!(matter(&memory, 1, "b"), Some(2.0));
+    assert_eq!(matter(&memory, 2, "a"), Some(1.0));
+    assert!(!memory.at_frame(1).unwrap().contains("gone"));
+
+    // Going backwards is a no-op; going past the open frame is clamped.
+    memory.retain_from(0);
+    assert_eq!(memory.oldest_frame(), 1);
+    memory.retain_from(100);
+    assert_eq!(memory.oldest_frame(), 3);
+    assert_eq!(matter(&memory, 3, "a"), Some(1.0));
+    assert_eq!(memory.ids().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+/// `a`, `b` and `c` in even superpositions, `a`–`b` as Φ and `b`–`c` as Ψ.
+fn chain(memory: &mut QuasiMemory, c_qtype: &str) {
+    memory
+        .allocate(QuasiState::new("a", "energy", 1.0, 1.0))
+        .unwrap();
+    memory
+        .allocate(QuasiState::new("b", "energy", 2.0, 2.0))
+        .unwrap();
+    memory
+        .allocate(QuasiState::new("c", c_qtype, 3.0, 3.0))
+        .unwrap();
+    memory.entangle("a", "b", BellKind::Phi).unwrap();
+    memory.entangle("b", "c", BellKind::Psi).unwrap();
+}
+
+fn branch(memory: &QuasiMemory, id: &str) -> Branch {
+    let (p0, p1) = memory.get(id).unwrap().field.born_weights();
+    assert!(p0 * p1 == 0.0, "{} is not collapsed", id);
+    if p0 == 1.0 {
+        Branch::Matter
+    } else {
+        Branch::Antimatter
+    }
+}
+
+#[test]
+fn collapse_follows_phi_and_psi() {
+    for seen in [Branch::Matter, Branch::Antimatter] {
+        let mut memory = QuasiMemory::new();
+        chain(&mut memory, "energy");
+        assert_eq!(memory.correlated("a").len(), 2);
+        memory.collapse("a", seen).unwrap();
+        assert_eq!(branch(&memory, "a"), seen);
+        assert_eq!(branch(&memory, "b"), seen);
+        assert_ne!(branch(&memory, "c"), seen);
+        for id in ["a", "b", "c"] {
+            assert!(memory.get(id).unwrap().observed);
+            assert!(!memory.entanglement().is_entangled(id));
+        }
+    }
+}
+
+#[test]
+fn disentangled_states_collapse_alone() {
+    let mut memory = QuasiMemory::new();
+    chain(&mut memory, "energy");
+    memory.disentangle("b");
+    assert!(memory.correlated("a").is_empty());
+    assert!(memory.correlated("c").is_empty());
+    // Each half keeps its mixed field.
+    assert_eq!(memory.get("b").unwrap().field.coherence, 0.0);
+    memory.collapse("a", Branch::Antimatter).unwrap();
+    assert!(!memory.get("b").unwrap().observed);
+    assert_eq!(memory.get("b").unwrap().field.born_weights(), (0.5, 0.5));
+}
+
+#[test]
+fn entangled_states_refuse_branch_mixing_gates() {
+    let mut memory = QuasiMemory::new();
+    chain(&mut memory, "energy");
+    for gate in [&H as &dyn Gate, &Rx(0.3)] {
+        assert_eq!(
+            memory.apply_gate("b", gate).unwrap_err(),
+            MemoryError::EntangledGate {
+                id: "b".to_string(),
+                gate: gate.name().to_string(),
+            }
+        );
+    }
+    assert_eq!(memory.correlated("b").len(), 2);
+
+    // Diagonal gates keep the pairs; anti-diagonal ones swap Φ and Ψ.
+    memory.apply_gate("b", &S).unwrap();
+    assert_eq!(
+        memory.entanglement().relation("a", "b"),
+        Some(BellKind::Phi)
+    );
+    memory.apply_gate("b", &Y).unwrap();
+    assert_eq!(
+        memory.entanglement().relation("a", "b"),
+        Some(BellKind::Psi)
+    );
+    assert_eq!(
+        memory.entanglement().relation("b", "c"),
+        Some(BellKind::Phi)
+    );
+
+    memory.disentangle("b");
+    memory.apply_gate("b", &H).unwrap();
+}
+
+#[test]
+fn collapse_checks_every_partner_before_changing_anything() {
+    let registry = QTypeRegistry::standard()
+        .with(QType::new("sealed", "J").operations(&[Operation::Entangle]));
+    registry.declare_all().unwrap();
+    let mut memory = QuasiMemory::with_registry(registry);
+    chain(&mut memory, "sealed");
+    assert_eq!(
+        memory.collapse("a", Branch::Matter).unwrap_err(),
+        MemoryError::Type(QuasiError::OperationNotAllowed {
+            qtype: "sealed".to_string(),
+            operation: Operation::Observe,
+        })
+    );
+    for id in ["a", "b", "c"] {
+        let state = memory.get(id).unwrap();
+        assert!(!state.observed);
+        assert_eq!(state.field.born_weights(), (0.5, 0.5));
+    }
+    assert_eq!(memory.correlated("a").len(), 2);
+}
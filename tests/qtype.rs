@@ -0,0 +1,216 @@
+use quasi::gate::{CNot, H, X, Z};
+use quasi::memory::qmemory::MemoryError;
+use quasi::memory::qtype::{declare, declared, Operation};
+use quasi::{QToken, QType, QTypeRegistry, QuasiError, QuasiMemory, QuasiRegister, QuasiState};
+
+fn registry() -> QTypeRegistry {
+    let registry = QTypeRegistry::standard()
+        .with(QType::new("heat", "J").range(0.0, 10.0))
+        .with(QType::new("frozen", "J").operations(&[Operation::Observe]));
+    registry.declare_all().unwrap();
+    registry
+}
+
+#[test]
+fn registration_rejects_empty_and_duplicate_names() {
+    let mut registry = registry();
+    assert_eq!(
+        registry.register(QType::new("", "J")).unwrap_err(),
+        QuasiError::EmptyQType
+    );
+    assert_eq!(
+        registry.register(QType::new("spin", "J")).unwrap_err(),
+        QuasiError::DuplicateQType {
+            name: "spin".to_string()
+        }
+    );
+    // The failed registration left the original in place.
+    assert_eq!(registry.lookup("spin").unwrap().unit, "ħ");
+    assert_eq!(
+        registry.lookup("mass").unwrap_err(),
+        QuasiError::UnknownQType {
+            name: "mass".to_string()
+        }
+    );
+    let names: Vec<&str> = registry.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, ["charge", "energy", "frozen", "heat", "spin"]);
+}
+
+#[test]
+fn registration_rejects_invalid_ranges() {
+    let mut registry = QTypeRegistry::new();
+    let mut nan = QType::new("heat", "J");
+    nan.min = f64::NAN;
+    assert!(matches!(
+        registry.register(nan),
+        Err(QuasiError::NonFinite {
+            field: "qtype min",
+            ..
+        })
+    ));
+    let mut empty = QType::new("heat", "J");
+    empty.min = 2.0;
+    empty.max = 1.0;
+    assert!(matches!(
+        registry.register(empty),
+        Err(QuasiError::OutOfRange {
+            field: "qtype max",
+            ..
+        })
+    ));
+    let mut infinite = QType::new("heat", "J");
+    infinite.max = f64::INFINITY;
+    assert!(registry.register(infinite.clone()).is_err());
+    assert!(declare(infinite).is_err());
+    assert!(registry.get("heat").is_none());
+}
+
+#[test]
+#[should_panic(expected = "finite")]
+fn ranges_need_finite_bounds() {
+    QType::new("heat", "J").range(f64::NAN, 1.0);
+}
+
+#[test]
+#[should_panic(expected = "must not be empty")]
+fn ranges_must_not_be_empty() {
+    QType::new("heat", "J").range(1.0, 0.0);
+}
+
+#[test]
+fn typed_constructors_check_name_and_range() {
+    let registry = registry();
+    assert!(QToken::typed(&registry, "heat", 10.0).is_ok());
+    assert!(matches!(
+        QToken::typed(&registry, "heat", -0.5),
+        Err(QuasiError::OutOfRange {
+            field: "token value",
+            ..
+        })
+    ));
+    assert!(matches!(
+        QuasiState::typed(&registry, "s", "mass", 1.0, 0.0),
+        Err(QuasiError::UnknownQType { .. })
+    ));
+}
+
+#[test]
+fn undeclared_types_cannot_be_constructed() {
+    let unknown = QuasiError::UnknownQType {
+        name: "enrgy".to_string(),
+    };
+    assert_eq!(QToken::try_new("enrgy", 1.0).unwrap_err(), unknown);
+    assert_eq!(
+        QuasiState::try_new("a", "enrgy", 1.0, 1.0).unwrap_err(),
+        unknown
+    );
+    assert!(QuasiState::try_new("a", "energy", 1.0, 1.0).is_ok());
+
+    // Readers of saved states validate the names they find.
+    let mut state = QuasiState::new("a", "energy", 1.0, 1.0);
+    state.field.antimatter.qtype = "enrgy".to_string();
+    assert_eq!(state.validate().unwrap_err(), unknown);
+
+    // Declaring the same definition twice is harmless; redefining is not.
+    declare(QType::new("mass", "kg")).unwrap();
+    declare(QType::new("mass", "kg")).unwrap();
+    assert!(matches!(
+        declare(QType::new("mass", "g")),
+        Err(QuasiError::DuplicateQType { .. })
+    ));
+    assert_eq!(declared("mass").unwrap().unit, "kg");
+    assert_eq!(QToken::new("mass", -5.0).qtype, "mass");
+}
+
+#[test]
+#[should_panic(expected = "unknown qtype `enrgy`")]
+fn typos_fail_where_they_are_written() {
+    QuasiState::new("a", "enrgy", 1.0, 1.0);
+}
+
+#[test]
+fn compatibility_follows_units() {
+    let registry = registry();
+    assert!(registry.check_compatible("energy", "heat").is_ok());
+    assert_eq!(
+        registry.check_compatible("energy", "spin").unwrap_err(),
+        QuasiError::IncompatibleQTypes {
+            a: "energy".to_string(),
+            b: "spin".to_string()
+        }
+    );
+
+    let mut mixed = QuasiState::new("s", "energy", 1.0, 1.0);
+    mixed.field.antimatter.qtype = "spin".to_string();
+    assert!(matches!(
+        registry.check_field(&mixed.field),
+        Err(QuasiError::IncompatibleQTypes { .. })
+    ));
+
+    let energy = QuasiState::new("a", "energy", 1.0, 0.0);
+    let spin = QuasiState::new("b", "spin", 0.0, 1.0);
+    assert!(energy.interfere_typed(&registry, &spin).is_err());
+    let heat = QuasiState::new("c", "heat", 1.0, 2.0);
+    assert!(energy.interfere_typed(&registry, &heat).is_ok());
+}
+
+#[test]
+fn gates_check_operations_and_compatibility() {
+    let registry = registry();
+    let mut register = QuasiRegister::from_states(&[
+        QuasiState::new("a", "energy", 1.0, 0.0),
+        QuasiState::new("b", "spin", 1.0, 0.0),
+        QuasiState::new("c", "frozen", 1.0, 0.0),
+    ]);
+    let before = register.clone();
+    assert_eq!(
+        register.apply_typed(&registry, &CNot, &[0, 1]).unwrap_err(),
+        QuasiError::IncompatibleGate {
+            gate: "cx".to_string(),
+            a: "energy".to_string(),
+            b: "spin".to_string()
+        }
+    );
+    assert_eq!(
+        register.apply_typed(&registry, &X, &[2]).unwrap_err(),
+        QuasiError::OperationNotAllowed {
+            qtype: "frozen".to_string(),
+            operation: Operation::Gate
+        }
+    );
+    assert_eq!(register.amplitudes(), before.amplitudes());
+    register.apply_typed(&registry, &H, &[1]).unwrap();
+}
+
+#[test]
+fn memory_gates_keep_values_in_range() {
+    let mut memory = QuasiMemory::with_registry(registry());
+    memory
+        .allocate(QuasiState::new("h", "heat", 1.0, 1.0))
+        .unwrap();
+    assert!(matches!(
+        memory.allocate(QuasiState::new("cold", "heat", -1.0, 0.0)),
+        Err(MemoryError::Type(QuasiError::OutOfRange { .. }))
+    ));
+
+    // Z would leave the antimatter value at -1, below the range.
+    assert!(matches!(
+        memory.apply_gate("h", &Z),
+        Err(MemoryError::Type(QuasiError::OutOfRange { .. }))
+    ));
+    assert_eq!(memory.get("h").unwrap().field.antimatter.value, 1.0);
+    memory.apply_gate("h", &X).unwrap();
+
+    memory
+        .allocate(QuasiState::new("f", "frozen", 1.0, 0.0))
+        .unwrap();
+    assert!(matches!(
+        memory.apply_gate("f", &X),
+        Err(MemoryError::Type(QuasiError::OperationNotAllowed { .. }))
+    ));
+    assert!(matches!(
+        memory.invert("f"),
+        Err(MemoryError::Type(QuasiError::OperationNotAllowed { .. }))
+    ));
+    memory.observe("f").unwrap();
+}
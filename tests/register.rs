@@ -0,0 +1,50 @@
+use quasi::gate::{CNot, H};
+use quasi::{Branch, DensityMatrix, QuasiRegister, QuasiState, SplitMix64};
+
+fn bell() -> QuasiRegister {
+    let mut register = QuasiRegister::new(2);
+    register.apply(&H, &[0]);
+    register.apply(&CNot, &[0, 1]);
+    register
+}
+
+#[test]
+fn observing_half_a_bell_pair_fixes_the_other_marginal() {
+    for seed in 0..16 {
+        let mut rng = SplitMix64::seed_from_u64(seed);
+        let mut register = bell();
+        assert!((register.marginal(1).0 - 0.5).abs() < 1e-12);
+        let seen = register.observe_with(0, &mut rng);
+        let expected = match seen.branch {
+            Branch::Matter => (1.0, 0.0),
+            Branch::Antimatter => (0.0, 1.0),
+        };
+        let (p0, p1) = register.marginal(1);
+        assert!((p0 - expected.0).abs() < 1e-12 && (p1 - expected.1).abs() < 1e-12);
+
+        let mut rho = DensityMatrix::from_register(&bell());
+        let seen = rho.observe_with(0, &mut SplitMix64::seed_from_u64(seed));
+        let (p0, p1) = rho.marginal(1);
+        assert!((p0 - expected.0).abs() < 1e-12 && (p1 - expected.1).abs() < 1e-12);
+        assert_eq!(seen.branch == Branch::Matter, expected.0 == 1.0);
+    }
+}
+
+#[test]
+fn observed_values_keep_the_token_sign() {
+    let state = QuasiState::new("ice", "energy", 42.0, -41.8);
+    for seed in 0..16 {
+        let mut single = state.clone();
+        let expected = single.observe_with(&mut SplitMix64::seed_from_u64(seed));
+
+        let mut register = QuasiRegister::from_states(std::slice::from_ref(&state));
+        let seen = register.observe_with(0, &mut SplitMix64::seed_from_u64(seed));
+        assert_eq!(seen.branch, expected.branch);
+        assert!((seen.value - expected.value).abs() < 1e-9, "{:?}", seen);
+
+        let mut rho = DensityMatrix::from_states(std::slice::from_ref(&state));
+        let seen = rho.observe_with(0, &mut SplitMix64::seed_from_u64(seed));
+        assert_eq!(seen.branch, expected.branch);
+        assert!((seen.value - expected.value).abs() < 1e-9, "{:?}", seen);
+    }
+}
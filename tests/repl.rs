@@ -0,0 +1,130 @@
+use quasi::gate::{self, STANDARD};
+use quasi::repl::{ReplError, Reply, Session, COMMANDS};
+
+fn text(session: &mut Session, line: &str) -> String {
+    match session.execute(line).unwrap() {
+        Reply::Text(text) => text,
+        Reply::Quit => panic!("`{}` ended the session", line),
+    }
+}
+
+#[test]
+fn commands_create_change_and_drop_states() {
+    let mut session = Session::seeded(1);
+    assert_eq!(text(&mut session, "   "), "");
+    assert_eq!(text(&mut session, "# a comment"), "");
+    text(&mut session, "new a 1 0");
+    text(&mut session, "new b 3 4 spin");
+    assert_eq!(text(&mut session, "list"), "a\nb");
+    assert_eq!(session.state("b").unwrap().field.matter.qtype, "spin");
+
+    text(&mut session, "invert a");
+    let [m, a] = session.state("a").unwrap().amplitudes();
+    assert!(m.abs() < 1e-12 && (a.abs() - 1.0).abs() < 1e-12);
+
+    // Angle lists may contain spaces and constant expressions.
+    text(&mut session, "apply h a");
+    text(&mut session, "apply rz( pi / 2 ) a");
+    let coherence: f64 = text(&mut session, "coherence a").parse().unwrap();
+    assert!((coherence - 1.0).abs() < 1e-6);
+
+    let observed = text(&mut session, "observe b");
+    assert!(observed.starts_with("matter") || observed.starts_with("antimatter"));
+    assert!(session.state("b").unwrap().observed);
+
+    text(&mut session, "drop a");
+    assert_eq!(session.ids().collect::<Vec<_>>(), ["b"]);
+    assert_eq!(session.execute(":q").unwrap(), Reply::Quit);
+}
+
+#[test]
+fn same_seed_same_observations() {
+    let run = |seed: &str| {
+        let mut session = Session::new();
+        text(&mut session, seed);
+        (0..16)
+            .map(|k| {
+                text(&mut session, &format!("new s{} 1 1", k));
+                text(&mut session, &format!("observe s{}", k))
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(run("seed 7"), run("seed 7"));
+}
+
+#[test]
+fn bad_lines_are_refused_without_side_effects() {
+    let mut session = Session::seeded(1);
+    text(&mut session, "new a 1 0");
+    let before = session.state("a").unwrap().amplitudes();
+
+    for (line, expected) in [
+        ("show nope", "no state named `nope`"),
+        ("invert nope", "no state named `nope`"),
+        ("apply h nope", "no state named `nope`"),
+        ("new a 0 1", "state `a` already exists (drop it first)"),
+        ("new b one 0", "matter must be a number, got `one`"),
+        ("apply foo a", "unknown gate `foo` with 0 angles"),
+        ("apply rx a", "unknown gate `rx` with 0 angles"),
+        ("apply rx(theta) a", "invalid angle `theta`"),
+        (
+            "apply cx a",
+            "`cx` acts on 2 states; the shell applies one-state gates",
+        ),
+        ("invert", "usage: invert <id>"),
+        ("seed x", "invalid seed `x`"),
+        ("frobnicate", "unknown command `frobnicate` (try :help)"),
+    ] {
+        let err = session.execute(line).unwrap_err();
+        assert_eq!(err.to_string(), expected, "{}", line);
+    }
+    assert!(matches!(
+        session.execute("new c nan 0").unwrap_err(),
+        ReplError::Quasi(_)
+    ));
+    assert_eq!(session.state("a").unwrap().amplitudes(), before);
+    assert!(session.state("c").is_none());
+}
+
+#[test]
+fn completion_follows_the_command_word() {
+    let mut session = Session::seeded(1);
+    text(&mut session, "new alpha 1 0");
+    text(&mut session, "new beta 1 0");
+
+    assert_eq!(
+        session.complete("", 0),
+        (0, COMMANDS.map(str::to_string).to_vec())
+    );
+    assert_eq!(session.complete("ob", 2), (0, vec!["observe".to_string()]));
+    assert_eq!(session.complete(":s", 2), (0, vec![":save".to_string()]));
+    assert_eq!(
+        session.complete("observe a", 9),
+        (8, vec!["alpha".to_string()])
+    );
+    assert_eq!(session.complete("new a", 5), (4, Vec::new()));
+    assert_eq!(session.complete(":load a", 7), (6, Vec::new()));
+    // The cursor need not be at the end of the line.
+    assert_eq!(
+        session.complete("drop b x", 6),
+        (5, vec!["beta".to_string()])
+    );
+
+    let (start, gates) = session.complete("apply ", 6);
+    assert_eq!(start, 6);
+    assert_eq!(
+        gates,
+        ["x", "y", "z", "h", "s", "sdg", "t", "tdg", "rx", "ry", "rz", "p"]
+    );
+    assert_eq!(session.complete("apply r", 7).1, ["rx", "ry", "rz"]);
+    assert_eq!(session.complete("apply h al", 10).1, ["alpha"]);
+}
+
+#[test]
+fn standard_table_matches_the_gate_library() {
+    for (name, angles) in STANDARD {
+        let gate = gate::standard(name, &vec![0.5; angles]).unwrap();
+        assert_eq!(gate.name(), name);
+        assert!(gate::standard(name, &vec![0.5; angles + 1]).is_none());
+    }
+}
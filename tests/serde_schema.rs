@@ -0,0 +1,78 @@
+#![cfg(feature = "serde")]
+
+use quasi::schema::{StateDocument, STATE_SCHEMA_VERSION};
+use quasi::{Complex, QuasiError, QuasiState, SplitMix64};
+
+fn assert_same(a: &QuasiState, b: &QuasiState) {
+    assert_eq!(a.id, b.id);
+    assert_eq!(a.observed, b.observed);
+    assert_eq!(a.field.coherence.to_bits(), b.field.coherence.to_bits());
+    for (x, y) in [
+        (&a.field.matter, &b.field.matter),
+        (&a.field.antimatter, &b.field.antimatter),
+    ] {
+        assert_eq!(x.qtype, y.qtype);
+        assert_eq!(x.value.to_bits(), y.value.to_bits());
+        assert_eq!(x.phase.to_bits(), y.phase.to_bits());
+    }
+}
+
+#[test]
+fn document_carries_the_schema_fields() {
+    let state = QuasiState::new("iceberg_01", "energy", 42.0, -41.8);
+    let json: serde_json::Value = serde_json::to_value(&state).unwrap();
+    assert_eq!(json["version"], STATE_SCHEMA_VERSION);
+    assert_eq!(json["id"], "iceberg_01");
+    assert_eq!(json["qtype"], "energy");
+    assert_eq!(json["matter"], 42.0);
+    assert_eq!(json["antimatter"], -41.8);
+    assert_eq!(json["observed"], false);
+    assert!(json.get("matter_phase").is_none());
+}
+
+#[test]
+fn round_trip_is_lossless() {
+    let mut observed = QuasiState::new("b", "energy", 0.1, 0.7);
+    observed.observe_with(&mut SplitMix64::seed_from_u64(9));
+    let mut decohered = QuasiState::new("c", "spin", 1.0 / 3.0, 2.0_f64.sqrt());
+    decohered.decohere(0.37);
+    let states = [
+        QuasiState::new("a", "energy", 42.0, -41.8),
+        observed,
+        decohered,
+        QuasiState::from_amplitudes("d", "charge", Complex::new(0.3, -0.4), Complex::cis(2.5)),
+    ];
+    for state in &states {
+        let json = serde_json::to_string(state).unwrap();
+        let back: QuasiState = serde_json::from_str(&json).unwrap();
+        assert_same(state, &back);
+    }
+}
+
+#[test]
+fn rejects_newer_versions_and_invalid_values() {
+    let newer = r#"{"version":2,"id":"a","qtype":"e","matter":1.0,"antimatter":0.0,"coherence":0.0,"observed":false}"#;
+    assert!(serde_json::from_str::<QuasiState>(newer).is_err());
+    let zero = newer.replace(r#""version":2"#, r#""version":0"#);
+    let err = serde_json::from_str::<QuasiState>(&zero).unwrap_err();
+    assert!(err.to_string().contains("format version 0"), "{}", err);
+    let bad = r#"{"version":1,"id":"a","qtype":"e","matter":1.0,"antimatter":0.0,"coherence":1.5,"observed":false}"#;
+    assert!(serde_json::from_str::<QuasiState>(bad).is_err());
+    let undeclared = bad.replace("1.5", "0.0");
+    let err = serde_json::from_str::<QuasiState>(&undeclared).unwrap_err();
+    assert!(err.to_string().contains("unknown qtype `e`"), "{}", err);
+}
+
+#[test]
+fn mixed_qtypes_cannot_be_written() {
+    let mut state = QuasiState::new("m", "energy", 1.0, 1.0);
+    state.field.antimatter.qtype = "charge".to_string();
+    assert_eq!(
+        StateDocument::try_from(&state),
+        Err(QuasiError::IncompatibleQTypes {
+            a: "energy".to_string(),
+            b: "charge".to_string()
+        })
+    );
+    assert!(serde_json::to_string(&state).is_err());
+}
@@ -0,0 +1,156 @@
+use quasi::memory::snapshot::{
+    crc32, read_snapshot, write_snapshot, SnapshotError, SnapshotReader, SnapshotWriter,
+    SNAPSHOT_VERSION,
+};
+use quasi::{BellKind, Complex, QuasiState, SplitMix64};
+
+fn sample_states() -> Vec<QuasiState> {
+    let mut observed = QuasiState::new("b", "energy", 0.1, 0.7);
+    observed.observe_with(&mut SplitMix64::seed_from_u64(4));
+    let mut mixed = QuasiState::new("c", "spin", 1.0, 2.0);
+    mixed.field.antimatter.qtype = "charge".to_string();
+    mixed.decohere(0.25);
+    vec![
+        QuasiState::new("iceberg_01", "energy", 42.0, -41.8),
+        observed,
+        mixed,
+        QuasiState::from_amplitudes("d", "energy", Complex::new(0.3, -0.4), Complex::cis(2.5)),
+    ]
+}
+
+fn encode(states: &[QuasiState]) -> Vec<u8> {
+    write_snapshot(Vec::new(), states).unwrap()
+}
+
+#[test]
+fn round_trip_preserves_every_bit() {
+    let states = sample_states();
+    let back = read_snapshot(encode(&states).as_slice()).unwrap();
+    assert_eq!(back.len(), states.len());
+    for (a, b) in states.iter().zip(&back) {
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.observed, b.observed);
+        assert_eq!(a.field.matter.qtype, b.field.matter.qtype);
+        assert_eq!(a.field.antimatter.qtype, b.field.antimatter.qtype);
+        assert_eq!(
+            a.field.matter.value.to_bits(),
+            b.field.matter.value.to_bits()
+        );
+        assert_eq!(
+            a.field.antimatter.phase.to_bits(),
+            b.field.antimatter.phase.to_bits()
+        );
+        assert_eq!(a.field.coherence.to_bits(), b.field.coherence.to_bits());
+    }
+}
+
+#[test]
+fn qtypes_are_interned_once() {
+    let states: Vec<_> = (0..100)
+        .map(|k| QuasiState::new(&format!("s{}", k), "energy", 1.0, 0.5))
+        .collect();
+    let bytes = encode(&states);
+    let mut reader = SnapshotReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.by_ref().count(), 100);
+    assert_eq!(reader.qtypes(), ["energy"]);
+}
+
+#[test]
+fn flipped_byte_is_a_checksum_mismatch() {
+    let mut bytes = encode(&sample_states());
+    let last = bytes.len() - 20;
+    bytes[last] ^= 0x40;
+    match read_snapshot(bytes.as_slice()) {
+        Err(SnapshotError::ChecksumMismatch { .. }) => {}
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_tail_is_truncation_at_the_file_length() {
+    let bytes = encode(&sample_states());
+    for cut in [bytes.len() - 1, bytes.len() - 13, 40] {
+        match read_snapshot(&bytes[..cut]) {
+            Err(SnapshotError::Truncated { offset }) => assert_eq!(offset, cut as u64),
+            other => panic!("cut at {}: expected truncation, got {:?}", cut, other),
+        }
+    }
+}
+
+#[test]
+fn foreign_files_are_rejected_by_magic() {
+    let mut bytes = encode(&sample_states());
+    bytes[0] = b'X';
+    assert!(matches!(
+        read_snapshot(bytes.as_slice()),
+        Err(SnapshotError::BadMagic { .. })
+    ));
+}
+
+#[test]
+fn pairs_follow_their_states() {
+    let states = sample_states();
+    let mut writer = SnapshotWriter::new(Vec::new()).unwrap();
+    for state in &states {
+        writer.write_state(state).unwrap();
+    }
+    writer.write_pair("c", "d", BellKind::Psi).unwrap();
+    let bytes = writer.finish().unwrap();
+    let mut reader = SnapshotReader::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.by_ref().count(), states.len());
+    assert_eq!(
+        reader.pairs(),
+        [("c".to_string(), "d".to_string(), BellKind::Psi)]
+    );
+
+    // A pair may not name a state that has not been written yet.
+    let mut writer = SnapshotWriter::new(Vec::new()).unwrap();
+    writer.write_state(&states[0]).unwrap();
+    writer.write_pair("iceberg_01", "b", BellKind::Phi).unwrap();
+    writer.write_state(&states[1]).unwrap();
+    let bytes = writer.finish().unwrap();
+    assert!(matches!(
+        read_snapshot(bytes.as_slice()),
+        Err(SnapshotError::InvalidPair { .. })
+    ));
+}
+
+/// `bytes` relabelled as format `version`, header checksum included.
+fn with_version(mut bytes: Vec<u8>, version: u16) -> Vec<u8> {
+    bytes[8..10].copy_from_slice(&version.to_le_bytes());
+    let crc = crc32(&bytes[..12]);
+    bytes[12..16].copy_from_slice(&crc.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn versions_outside_the_supported_range_are_rejected() {
+    let bytes = encode(&sample_states());
+    for version in [0, SNAPSHOT_VERSION + 1] {
+        let err = read_snapshot(with_version(bytes.clone(), version).as_slice()).unwrap_err();
+        assert!(matches!(
+            err,
+            SnapshotError::UnsupportedVersion { found, supported: SNAPSHOT_VERSION }
+                if found == version
+        ));
+        assert!(err.to_string().contains("is not supported"), "{}", err);
+    }
+    // Version 1 differs only in lacking pair records.
+    let v1 = with_version(bytes, 1);
+    assert_eq!(read_snapshot(v1.as_slice()).unwrap().len(), 4);
+}
+
+#[test]
+fn version_1_files_cannot_hold_pairs() {
+    let states = sample_states();
+    let mut writer = SnapshotWriter::new(Vec::new()).unwrap();
+    for state in &states {
+        writer.write_state(state).unwrap();
+    }
+    writer.write_pair("c", "d", BellKind::Phi).unwrap();
+    let bytes = with_version(writer.finish().unwrap(), 1);
+    assert!(matches!(
+        read_snapshot(bytes.as_slice()),
+        Err(SnapshotError::UnknownTag { tag: 0x03, .. })
+    ));
+}